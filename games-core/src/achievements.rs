@@ -0,0 +1,128 @@
+//! Achievements, unlocked by playing and remembered in `achievements.txt` in the data directory
+//! (one achievement id per line, after the version line).
+use std::{io, path::PathBuf};
+
+use crate::{
+    dirs,
+    migrate::{self, Format},
+};
+
+const FORMAT: Format = Format {
+    name: "achievements",
+    migrations: &[migrate::add_version_line],
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Achievement {
+    FirstWin,
+    FirstDraw,
+    NoCenter,
+    QuickWin,
+    Streak,
+    BeatBot,
+    Regular,
+}
+
+impl Achievement {
+    pub const ALL: [Self; 7] = [
+        Self::FirstWin,
+        Self::FirstDraw,
+        Self::NoCenter,
+        Self::QuickWin,
+        Self::Streak,
+        Self::BeatBot,
+        Self::Regular,
+    ];
+
+    /// How the achievement is stored on disk. Must never change.
+    fn id(self) -> &'static str {
+        match self {
+            Self::FirstWin => "first-win",
+            Self::FirstDraw => "first-draw",
+            Self::NoCenter => "no-center",
+            Self::QuickWin => "quick-win",
+            Self::Streak => "streak",
+            Self::BeatBot => "beat-bot",
+            Self::Regular => "regular",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::FirstWin => "First blood",
+            Self::FirstDraw => "Cat's game",
+            Self::NoCenter => "Off center",
+            Self::QuickWin => "Lightning",
+            Self::Streak => "Unstoppable",
+            Self::BeatBot => "Machine tamer",
+            Self::Regular => "Regular",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::FirstWin => "Win a game",
+            Self::FirstDraw => "Draw a game",
+            Self::NoCenter => "Win without taking the center",
+            Self::QuickWin => "Win a game in under 10 seconds",
+            Self::Streak => "Win 10 games in a row",
+            Self::BeatBot => "Beat the computer",
+            Self::Regular => "Play the game of the day 7 days in a row",
+        }
+    }
+}
+
+pub struct Achievements {
+    unlocked: Vec<Achievement>,
+    /// Whether unlocking an achievement writes it to the file
+    save: bool,
+}
+
+impl Achievements {
+    /// Read which achievements have been unlocked. If the file can't be read, nothing is.
+    pub fn load() -> Self {
+        let contents = path().and_then(|path| FORMAT.read(&path));
+        let contents = contents.ok().flatten().unwrap_or_default();
+        let unlocked = Achievement::ALL
+            .into_iter()
+            .filter(|achievement| contents.lines().any(|line| line.trim() == achievement.id()))
+            .collect();
+        Self {
+            unlocked,
+            save: true,
+        }
+    }
+
+    /// None unlocked, and nothing written when they are, for games that shouldn't count
+    pub fn unsaved() -> Self {
+        Self {
+            unlocked: vec![],
+            save: false,
+        }
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    /// Returns `true` if the achievement wasn't already unlocked.
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.is_unlocked(achievement) {
+            return false;
+        }
+        self.unlocked.push(achievement);
+        if self.save {
+            // still show the unlock this session even if it can't be saved
+            let _ = save(achievement);
+        }
+        true
+    }
+}
+
+fn save(achievement: Achievement) -> io::Result<()> {
+    FORMAT.append(&path()?, achievement.id())
+}
+
+fn path() -> io::Result<PathBuf> {
+    dirs::data_file("achievements.txt")
+}
@@ -0,0 +1,128 @@
+//! Comments and highlighted squares on the moves of a recorded game, so a stronger player can
+//! explain a game to someone else.
+//!
+//! A game's annotations are kept in `annotations/<game>-<finished>.tsv` in the data directory,
+//! where `<finished>` is when it finished, in unix seconds, as in its line in the stats file.
+//! After the version line (see [`migrate`]), each line is one move:
+//!
+//! ```text
+//! <move number, from 1>  <highlighted squares, comma separated>  <comment>
+//! ```
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    dirs,
+    migrate::{self, Format},
+};
+
+const FORMAT: Format = Format {
+    name: "annotations",
+    migrations: &[migrate::add_version_line],
+};
+
+#[derive(Default)]
+pub struct Annotation {
+    pub comment: String,
+    /// Squares in the game's notation
+    pub highlights: Vec<String>,
+}
+
+impl Annotation {
+    fn is_empty(&self) -> bool {
+        self.comment.is_empty() && self.highlights.is_empty()
+    }
+}
+
+pub struct Annotations {
+    path: PathBuf,
+    /// By move, from 0
+    moves: BTreeMap<usize, Annotation>,
+}
+
+impl Annotations {
+    /// The annotations on the game of `game` that finished at `finished`. Lines that can't be
+    /// parsed are skipped.
+    pub fn load(game: &str, finished: SystemTime) -> io::Result<Self> {
+        let secs = finished
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = dirs::data_file(&format!("annotations/{game}-{secs}.tsv"))?;
+        let contents = FORMAT.read(&path)?.unwrap_or_default();
+        let moves = contents.lines().filter_map(parse_line).collect();
+        Ok(Self { path, moves })
+    }
+
+    /// The annotation on move `step`, counting from 0
+    pub fn get(&self, step: usize) -> Option<&Annotation> {
+        self.moves.get(&step)
+    }
+
+    /// Replace the comment on move `step`, and save
+    pub fn set_comment(&mut self, step: usize, comment: &str) -> io::Result<()> {
+        // tabs and newlines would break up the line in the file
+        let comment = comment.replace(['\t', '\n'], " ").trim().to_owned();
+        self.moves.entry(step).or_default().comment = comment;
+        self.save()
+    }
+
+    /// Highlight `square` on move `step`, or stop highlighting it if it already is, and save
+    pub fn toggle_highlight(&mut self, step: usize, square: &str) -> io::Result<()> {
+        let highlights = &mut self.moves.entry(step).or_default().highlights;
+        match highlights.iter().position(|highlight| highlight == square) {
+            Some(idx) => {
+                highlights.remove(idx);
+            }
+            None => highlights.push(square.to_owned()),
+        }
+        self.save()
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        self.moves.retain(|_, annotation| !annotation.is_empty());
+        if self.moves.is_empty() {
+            return match fs::remove_file(&self.path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        let mut contents = String::new();
+        for (step, annotation) in &self.moves {
+            // writing to a string can't fail
+            writeln!(
+                contents,
+                "{}\t{}\t{}",
+                step + 1,
+                annotation.highlights.join(","),
+                annotation.comment
+            )
+            .unwrap();
+        }
+        FORMAT.write(&self.path, &contents)
+    }
+}
+
+fn parse_line(line: &str) -> Option<(usize, Annotation)> {
+    let mut fields = line.splitn(3, '\t');
+    let step = fields.next()?.parse::<usize>().ok()?.checked_sub(1)?;
+    let highlights = fields
+        .next()?
+        .split(',')
+        .filter(|square| !square.is_empty())
+        .map(str::to_owned)
+        .collect();
+    let comment = fields.next().unwrap_or("").to_owned();
+    Some((
+        step,
+        Annotation {
+            comment,
+            highlights,
+        },
+    ))
+}
@@ -0,0 +1,192 @@
+//! Computer opponents, written against `GameState` so they work for any game that implements it.
+use std::{
+    hash::Hash,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+pub mod alpha_beta;
+pub mod book;
+mod eval;
+mod mcts;
+pub mod script;
+mod solver;
+
+pub use alpha_beta::AlphaBeta;
+pub use book::{Book, BookBot};
+pub use eval::{Evaluator, Weighted};
+pub use mcts::Mcts;
+pub use script::Script;
+pub use solver::{Outcome, Solver};
+
+use crate::player::Seat;
+
+/// The rules of a two-player, turn-based game, as much as a bot needs to know them
+///
+/// Bots search on several threads, hence the `Send` and `Sync` bounds. Positions are `Hash` so
+/// they can be looked up in opening books.
+///
+/// `Mcts` works with any number of players. `AlphaBeta` and `Solver` assume two, taking turns.
+pub trait GameState: Clone + Eq + Hash + Send + Sync {
+    type Move: Copy + PartialEq + Send + Sync;
+    type Player: Seat;
+
+    /// Whose turn it is
+    fn current_player(&self) -> Self::Player;
+    /// Every move that can be made from here. Empty once the game is over.
+    fn legal_moves(&self) -> Vec<Self::Move>;
+    /// Make `mv` for the current player, who must be allowed to make it
+    fn apply(&mut self, mv: Self::Move);
+    /// `None` while the game is still going
+    fn result(&self) -> Option<GameResult<Self::Player>>;
+    /// How `mv` is written down, e.g. in opening books
+    fn move_name(&self, mv: Self::Move) -> String;
+    /// The inverse of `move_name`. `None` unless the move is legal.
+    fn parse_move(&self, name: &str) -> Option<Self::Move>;
+
+    /// The same position for every other position that plays out the same way, like its
+    /// rotations and reflections, so `Solver` only has to solve one of them
+    fn canonical(&self) -> Self {
+        self.clone()
+    }
+
+    /// The number of positions exactly `depth` moves from here, for checking move generation
+    /// against known counts. Games that end sooner aren't counted.
+    fn perft(&self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        moves
+            .into_iter()
+            .map(|mv| {
+                let mut next = self.clone();
+                next.apply(mv);
+                next.perft(depth - 1)
+            })
+            .sum()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GameResult<P> {
+    Winner(P),
+    Draw,
+}
+
+pub trait Bot<G: GameState> {
+    /// Pick a move for the current player, or `None` if the game is over
+    fn choose_move(&mut self, state: &G) -> Option<G::Move>;
+}
+
+impl<G: GameState, B: Bot<G> + ?Sized> Bot<G> for Box<B> {
+    fn choose_move(&mut self, state: &G) -> Option<G::Move> {
+        (**self).choose_move(state)
+    }
+}
+
+/// Which kind of bot to play against
+#[derive(Clone, Copy, PartialEq)]
+pub enum BotKind {
+    /// `Mcts`, which only needs the rules
+    Mcts,
+    /// `AlphaBeta`, which needs an evaluation for the game
+    AlphaBeta,
+}
+
+impl BotKind {
+    pub const ALL: [Self; 2] = [Self::Mcts, Self::AlphaBeta];
+
+    /// The name used in the settings file
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Mcts => "mcts",
+            Self::AlphaBeta => "alpha-beta",
+        }
+    }
+
+    pub fn from_str(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// Runs a bot on its own thread, so the UI stays responsive while it thinks
+pub struct Worker<G: GameState> {
+    requests: Sender<G>,
+    replies: Receiver<Option<G::Move>>,
+    /// When the move being thought about was asked for, if it hasn't been collected yet
+    thinking: Option<Instant>,
+    /// How long the last move took to choose
+    last_think: Option<Duration>,
+}
+
+impl<G: GameState + 'static> Worker<G> {
+    /// Start the bot's thread. `on_reply` is called from it each time a move is ready, so
+    /// whatever is waiting for it can `poll` straight away.
+    pub fn spawn(
+        mut bot: impl Bot<G> + Send + 'static,
+        on_reply: impl Fn() + Send + 'static,
+    ) -> Self {
+        let (requests, request_rx) = mpsc::channel::<G>();
+        let (reply_tx, replies) = mpsc::channel();
+        // stops once the `Worker` is dropped and its sender with it
+        thread::spawn(move || {
+            for state in request_rx {
+                if reply_tx.send(bot.choose_move(&state)).is_err() {
+                    break;
+                }
+                on_reply();
+            }
+        });
+        Self {
+            requests,
+            replies,
+            thinking: None,
+            last_think: None,
+        }
+    }
+
+    pub fn is_thinking(&self) -> bool {
+        self.thinking.is_some()
+    }
+
+    /// How long the bot took over its last move, if it has made one
+    pub fn last_think(&self) -> Option<Duration> {
+        self.last_think
+    }
+
+    /// Start choosing a move for `state`, which must not be over
+    pub fn think(&mut self, state: G) {
+        // the thread only stops if we've been dropped, so this can't fail
+        let _ = self.requests.send(state);
+        self.thinking = Some(Instant::now());
+    }
+
+    /// The chosen move, once the bot has finished thinking
+    pub fn poll(&mut self) -> Option<G::Move> {
+        let mv = self.replies.try_recv().ok()?;
+        self.last_think = self.thinking.take().map(|started| started.elapsed());
+        mv
+    }
+}
+
+/// How much work a bot may do for each move
+#[derive(Clone, Copy, PartialEq)]
+pub enum Budget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+impl Budget {
+    /// Parses either a number of iterations (`5000`) or a time in milliseconds (`250ms`)
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value.strip_suffix("ms") {
+            Some(ms) => Some(Self::Time(Duration::from_millis(ms.trim().parse().ok()?))),
+            None => Some(Self::Iterations(value.parse().ok()?)),
+        }
+    }
+}
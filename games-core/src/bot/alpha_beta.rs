@@ -0,0 +1,145 @@
+//! Depth-limited minimax with alpha-beta pruning, scoring the positions where it stops with an
+//! `Evaluator`.
+use std::time::{Duration, Instant};
+
+use super::{Bot, Evaluator, GameResult, GameState};
+
+/// The score of a won position. Wins found sooner score a little higher, so the bot doesn't
+/// dawdle.
+pub const WIN: f64 = 1_000_000.;
+
+pub struct AlphaBeta<G: GameState> {
+    /// How many moves ahead to look, when there's no time limit
+    depth: usize,
+    /// If set, search as deep as possible in this long instead of to `depth`
+    time: Option<Duration>,
+    evaluator: Box<dyn Evaluator<G>>,
+}
+
+impl<G: GameState> AlphaBeta<G> {
+    pub fn new(depth: usize, time: Option<Duration>, evaluator: Box<dyn Evaluator<G>>) -> Self {
+        Self {
+            depth,
+            time,
+            evaluator,
+        }
+    }
+
+    /// Search one move deeper each time until `time` runs out, and return the best move from the
+    /// deepest search that finished
+    ///
+    /// Always finishes at least the one-move search, so there's a move even when `time` is tiny.
+    /// Stops early once a search has seen to the end of every line.
+    pub fn think(&self, state: &G, time: Duration) -> Option<G::Move> {
+        let deadline = Instant::now() + time;
+        let mut best = None;
+        for depth in 1.. {
+            let mut search = Search {
+                evaluator: &*self.evaluator,
+                deadline: best.is_some().then_some(deadline),
+                timed_out: false,
+                truncated: false,
+            };
+            let result = search.root(state, depth, best);
+            if search.timed_out {
+                break;
+            }
+            best = result;
+            if !search.truncated || Instant::now() >= deadline {
+                break;
+            }
+        }
+        best
+    }
+}
+
+impl<G: GameState> Bot<G> for AlphaBeta<G> {
+    fn choose_move(&mut self, state: &G) -> Option<G::Move> {
+        match self.time {
+            Some(time) => self.think(state, time),
+            None => {
+                let mut search = Search {
+                    evaluator: &*self.evaluator,
+                    deadline: None,
+                    timed_out: false,
+                    truncated: false,
+                };
+                search.root(state, self.depth, None)
+            }
+        }
+    }
+}
+
+/// One search to a fixed depth
+struct Search<'a, G: GameState> {
+    evaluator: &'a dyn Evaluator<G>,
+    /// Give up once this passes
+    deadline: Option<Instant>,
+    /// Set if the deadline passed, in which case the result is meaningless
+    timed_out: bool,
+    /// Set if any line was cut off by the depth limit rather than reaching the end of the game
+    truncated: bool,
+}
+
+impl<G: GameState> Search<'_, G> {
+    /// The best move from `state`, trying `first` before the others
+    fn root(&mut self, state: &G, depth: usize, first: Option<G::Move>) -> Option<G::Move> {
+        let mut moves = state.legal_moves();
+        if let Some(idx) = moves.iter().position(|mv| Some(*mv) == first) {
+            // searching the best move from last time first makes for more pruning
+            moves[..=idx].rotate_right(1);
+        }
+        let mut best = None;
+        let mut alpha = f64::NEG_INFINITY;
+        for mv in moves {
+            let mut next = state.clone();
+            next.apply(mv);
+            let score = -self.negamax(&next, depth.saturating_sub(1), f64::NEG_INFINITY, -alpha);
+            // strictly better, so ties go to the first move
+            if best.is_none() || score > alpha {
+                best = Some(mv);
+                alpha = score;
+            }
+        }
+        best
+    }
+
+    /// The score of `state` for the player to move, looking `depth` moves ahead. Only exact
+    /// between `alpha` and `beta`: outside that, it's enough to know which side of the window
+    /// it's on.
+    fn negamax(&mut self, state: &G, depth: usize, mut alpha: f64, beta: f64) -> f64 {
+        if self.timed_out
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.timed_out = true;
+            return 0.;
+        }
+        if let Some(result) = state.result() {
+            return match result {
+                GameResult::Winner(winner) if winner == state.current_player() => {
+                    WIN + depth as f64
+                }
+                GameResult::Winner(_) => -WIN - depth as f64,
+                GameResult::Draw => 0.,
+            };
+        }
+        if depth == 0 {
+            self.truncated = true;
+            return self.evaluator.evaluate(state);
+        }
+        let mut best = f64::NEG_INFINITY;
+        for mv in state.legal_moves() {
+            let mut next = state.clone();
+            next.apply(mv);
+            let score = -self.negamax(&next, depth - 1, -beta, -alpha);
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
@@ -0,0 +1,128 @@
+//! Opening books: moves worked out ahead of time, so a bot can answer instantly in the early game.
+//!
+//! A book is a text file with one line per position. Each line lists the moves that lead to the
+//! position from the start of the game, then `->` and the move to play there:
+//!
+//! ```text
+//! # X opens in the center
+//! -> b2
+//! b2 -> a1
+//! b2 b1 -> a1
+//! ```
+//!
+//! Moves are written in the game's own notation (see `GameState::move_name`). Blank lines and
+//! lines starting with `#` are ignored.
+use std::{collections::HashMap, fmt::Write, fs, io, path::Path};
+
+use super::{Bot, GameState};
+use crate::rng::Rng;
+
+pub struct Book<G: GameState> {
+    moves: HashMap<G, G::Move>,
+}
+
+impl<G: GameState> Book<G> {
+    /// Read a book for the game starting at `start`. A missing file is an empty book.
+    pub fn load(path: &Path, start: &G) -> Result<Self, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("could not read {}: {e}", path.display())),
+        };
+        let mut moves = HashMap::new();
+        for (idx, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (state, mv) = parse_line(line, start)
+                .map_err(|msg| format!("{}:{}: {msg}", path.display(), idx + 1))?;
+            moves.insert(state, mv);
+        }
+        Ok(Self { moves })
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            moves: HashMap::new(),
+        }
+    }
+
+    /// The book move for `state`, if there is one
+    pub fn get(&self, state: &G) -> Option<G::Move> {
+        self.moves.get(state).copied()
+    }
+}
+
+/// The position a line leads to, and the move to play there
+fn parse_line<G: GameState>(line: &str, start: &G) -> Result<(G, G::Move), String> {
+    let Some((history, mv)) = line.split_once("->") else {
+        return Err(format!("expected `moves -> move`, found \"{line}\""));
+    };
+    let mut state = start.clone();
+    for name in history.split_whitespace() {
+        let mv = state
+            .parse_move(name)
+            .ok_or_else(|| format!("\"{name}\" isn't a legal move here"))?;
+        state.apply(mv);
+    }
+    let mv = state
+        .parse_move(mv.trim())
+        .ok_or_else(|| format!("\"{}\" isn't a legal move here", mv.trim()))?;
+    Ok((state, mv))
+}
+
+/// Play `games` games of `bot` against itself, recording what it plays in the first `depth`
+/// moves, and return the result as the contents of a book file
+///
+/// Sometimes a random move is played instead, so the book covers what to do when the opponent
+/// doesn't play the bot's way.
+pub fn build<G: GameState>(start: &G, bot: &mut impl Bot<G>, games: usize, depth: usize) -> String {
+    let mut rng = Rng::from_time();
+    let mut chosen = HashMap::new();
+    let mut book = String::new();
+    for _ in 0..games {
+        let mut state = start.clone();
+        let mut history = vec![];
+        for _ in 0..depth {
+            let moves = state.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            let mv = if rng.below(4) == 0 {
+                moves[rng.below(moves.len())]
+            } else if let Some(mv) = chosen.get(&state) {
+                *mv
+            } else {
+                let Some(mv) = bot.choose_move(&state) else {
+                    break;
+                };
+                chosen.insert(state.clone(), mv);
+                let line: String = history.iter().map(|name| format!("{name} ")).collect();
+                writeln!(&mut book, "{line}-> {}", state.move_name(mv)).unwrap();
+                mv
+            };
+            history.push(state.move_name(mv));
+            state.apply(mv);
+        }
+    }
+    book
+}
+
+/// Plays from the book while it can, then asks `bot`
+pub struct BookBot<G: GameState, B> {
+    book: Book<G>,
+    bot: B,
+}
+
+impl<G: GameState, B: Bot<G>> BookBot<G, B> {
+    pub fn new(book: Book<G>, bot: B) -> Self {
+        Self { book, bot }
+    }
+}
+
+impl<G: GameState, B: Bot<G>> Bot<G> for BookBot<G, B> {
+    fn choose_move(&mut self, state: &G) -> Option<G::Move> {
+        self.book.get(state).or_else(|| self.bot.choose_move(state))
+    }
+}
@@ -0,0 +1,41 @@
+//! Heuristic scores for positions, for searches that can't see to the end of the game.
+use super::GameState;
+
+/// A guess at how good a position is for the player to move
+pub trait Evaluator<G: GameState>: Send {
+    /// Positive is good for `state.current_player()` and negative is bad. Should stay well away
+    /// from `alpha_beta::WIN`, which is reserved for positions that are actually won.
+    fn evaluate(&self, state: &G) -> f64;
+}
+
+/// Adds up several evaluators, each multiplied by its weight
+pub struct Weighted<G: GameState> {
+    features: Vec<(f64, Box<dyn Evaluator<G>>)>,
+}
+
+impl<G: GameState> Weighted<G> {
+    /// `weights` gives a weight for each feature by name, and `feature` looks the names up
+    pub fn new(
+        weights: &[(String, f64)],
+        feature: impl Fn(&str) -> Option<Box<dyn Evaluator<G>>>,
+    ) -> Result<Self, String> {
+        let features = weights
+            .iter()
+            .map(|(name, weight)| {
+                let evaluator =
+                    feature(name).ok_or_else(|| format!("unknown evaluation {name}"))?;
+                Ok((*weight, evaluator))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { features })
+    }
+}
+
+impl<G: GameState> Evaluator<G> for Weighted<G> {
+    fn evaluate(&self, state: &G) -> f64 {
+        self.features
+            .iter()
+            .map(|(weight, evaluator)| weight * evaluator.evaluate(state))
+            .sum()
+    }
+}
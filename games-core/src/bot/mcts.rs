@@ -0,0 +1,174 @@
+//! Monte Carlo tree search: play lots of random games, concentrating on the moves that have done
+//! well so far, and pick the move that was explored the most.
+use std::time::Instant;
+
+use rayon::prelude::*;
+
+use super::{Bot, Budget, GameResult, GameState};
+use crate::rng::Rng;
+
+/// How much to favour trying rarely-visited moves over the ones that have won the most. √2 is
+/// the usual choice for rewards between 0 and 1.
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+pub struct Mcts {
+    budget: Budget,
+    rng: Rng,
+}
+
+impl Mcts {
+    pub fn new(budget: Budget) -> Self {
+        Self {
+            budget,
+            rng: Rng::from_time(),
+        }
+    }
+}
+
+struct Node<G: GameState> {
+    parent: Option<usize>,
+    /// The move that led here, and who made it. `None` for the root.
+    mv: Option<(G::Move, G::Player)>,
+    children: Vec<usize>,
+    /// Moves from here that don't have a child yet
+    untried: Vec<G::Move>,
+    visits: u32,
+    /// Total reward for the player who made `mv`: 1 for a win and 0.5 for a draw
+    reward: f64,
+}
+
+impl<G: GameState> Node<G> {
+    fn new(parent: Option<usize>, mv: Option<(G::Move, G::Player)>, state: &G) -> Self {
+        Self {
+            parent,
+            mv,
+            children: vec![],
+            untried: state.legal_moves(),
+            visits: 0,
+            reward: 0.,
+        }
+    }
+
+    /// Upper confidence bound, for choosing which child to explore
+    fn uct(&self, parent_visits: u32) -> f64 {
+        let visits = self.visits as f64;
+        self.reward / visits + EXPLORATION * ((parent_visits as f64).ln() / visits).sqrt()
+    }
+}
+
+impl<G: GameState> Bot<G> for Mcts {
+    fn choose_move(&mut self, state: &G) -> Option<G::Move> {
+        let moves = state.legal_moves();
+        if moves.len() <= 1 {
+            return moves.first().copied();
+        }
+        // root parallelization: grow a separate tree on each thread, then add up how often each
+        // tree visited each move. Each tree's seed comes from ours, so an iteration budget on a
+        // given number of threads always gives the same move.
+        let trees = rayon::current_num_threads();
+        let seed = self.rng.next_u64();
+        let visits = (0..trees)
+            .into_par_iter()
+            .map(|idx| {
+                let budget = match self.budget {
+                    Budget::Iterations(max) => {
+                        Budget::Iterations(max / trees + usize::from(idx < max % trees))
+                    }
+                    time => time,
+                };
+                let tree = search(state, budget, &mut Rng::new(seed.wrapping_add(idx as u64)));
+                root_visits(&tree, &moves)
+            })
+            .reduce(
+                || vec![0; moves.len()],
+                |a, b| a.iter().zip(b).map(|(a, b)| a + b).collect(),
+            );
+        // the first of the most visited, so ties don't depend on thread timing
+        let mut best = 0;
+        for (idx, count) in visits.iter().enumerate() {
+            if *count > visits[best] {
+                best = idx;
+            }
+        }
+        Some(moves[best])
+    }
+}
+
+/// How many times the root's children were visited, in the same order as `moves`
+fn root_visits<G: GameState>(tree: &[Node<G>], moves: &[G::Move]) -> Vec<u32> {
+    let mut visits = vec![0; moves.len()];
+    for child in &tree[0].children {
+        let (mv, _) = tree[*child].mv.unwrap();
+        if let Some(idx) = moves.iter().position(|other| *other == mv) {
+            visits[idx] = tree[*child].visits;
+        }
+    }
+    visits
+}
+
+/// Grow a tree from `root` until the budget runs out. The tree is kept in a `Vec`, with nodes
+/// referring to each other by index.
+fn search<G: GameState>(root: &G, budget: Budget, rng: &mut Rng) -> Vec<Node<G>> {
+    let mut tree = vec![Node::new(None, None, root)];
+    let started = Instant::now();
+    let mut iterations = 0;
+    while match budget {
+        Budget::Iterations(max) => iterations < max,
+        Budget::Time(max) => iterations == 0 || started.elapsed() < max,
+    } {
+        iterations += 1;
+        iterate(&mut tree, root, rng);
+    }
+    tree
+}
+
+/// Walk down the tree to a new node, play randomly from there, and record how it went
+fn iterate<G: GameState>(tree: &mut Vec<Node<G>>, root: &G, rng: &mut Rng) {
+    let mut state = root.clone();
+    let mut node = 0;
+    // selection
+    while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+        let parent_visits = tree[node].visits;
+        let uct = |child: &usize| tree[*child].uct(parent_visits);
+        node = *tree[node]
+            .children
+            .iter()
+            .max_by(|a, b| uct(a).total_cmp(&uct(b)))
+            .unwrap();
+        let (mv, _) = tree[node].mv.unwrap();
+        state.apply(mv);
+    }
+    // expansion
+    if !tree[node].untried.is_empty() {
+        let untried = &mut tree[node].untried;
+        let mv = untried.swap_remove(rng.below(untried.len()));
+        let player = state.current_player();
+        state.apply(mv);
+        tree.push(Node::new(Some(node), Some((mv, player)), &state));
+        let child = tree.len() - 1;
+        tree[node].children.push(child);
+        node = child;
+    }
+    // simulation
+    let result = loop {
+        if let Some(result) = state.result() {
+            break result;
+        }
+        let moves = state.legal_moves();
+        state.apply(moves[rng.below(moves.len())]);
+    };
+    // backpropagation
+    let mut current = Some(node);
+    while let Some(idx) = current {
+        let node = &mut tree[idx];
+        node.visits += 1;
+        if let Some((_, player)) = node.mv {
+            node.reward += match result {
+                GameResult::Winner(winner) if winner == player => 1.,
+                GameResult::Winner(_) => 0.,
+                GameResult::Draw => 0.5,
+            };
+        }
+        current = node.parent;
+    }
+}
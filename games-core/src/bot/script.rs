@@ -0,0 +1,156 @@
+//! Computer players written by the player, as programs in the `bots` directory of the data
+//! directory, in whatever language they like.
+//!
+//! The program is run afresh for each move and sent the position on its standard input, like
+//!
+//! ```text
+//! board X.O/.X./...
+//! player O
+//! legal b1 a2 c2 a3 b3 c3
+//! ```
+//!
+//! `board` is each row from the top, separated by `/`. It should print the move it chooses, like
+//! `c3`, within the time limit. If it takes too long it's stopped, and if it fails or chooses
+//! something that isn't legal, another bot moves instead.
+//!
+//! Each run is in an empty directory of its own, removed afterwards, with nothing in its
+//! environment but `PATH`, and `HOME` set to that directory. Its standard error goes nowhere.
+//! On unix it's confined too, as described in [`confine`]: where Linux has Landlock it can't
+//! write outside that directory, read the player's files or use TCP, and everywhere its CPU
+//! time, memory and files are limited. The time limit keeps one that hangs from holding up the
+//! game.
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+    process::{self, Command, Stdio},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Duration,
+};
+
+use super::{Bot, GameState};
+use crate::{dirs, player::Seat};
+
+#[cfg(unix)]
+mod confine;
+
+pub struct Script<G: GameState> {
+    path: PathBuf,
+    time_limit: Duration,
+    /// Moves when the script doesn't
+    fallback: Box<dyn Bot<G> + Send>,
+}
+
+/// The path of the script called `name` in the bots directory, if there is one
+pub fn find(name: &str) -> Result<PathBuf, String> {
+    let path = dirs::data_file("bots")
+        .map_err(|e| e.to_string())?
+        .join(name);
+    if !path.is_file() {
+        return Err(format!("there's no bot at {}", path.display()));
+    }
+    Ok(path)
+}
+
+impl<G: GameState> Script<G> {
+    pub fn new(path: PathBuf, time_limit: Duration, fallback: Box<dyn Bot<G> + Send>) -> Self {
+        Self {
+            path,
+            time_limit,
+            fallback,
+        }
+    }
+
+    /// The move the script chose, if it chose one in time
+    fn ask(&self, state: &G) -> Option<String>
+    where
+        G: Display,
+    {
+        // the script runs somewhere else, so a relative path wouldn't find it
+        let program = self.path.canonicalize().ok()?;
+        let dir = WorkDir::new().ok()?;
+        let mut command = Command::new(&program);
+        #[cfg(unix)]
+        confine::confine(&mut command, &dir.0, program.parent()?, self.time_limit);
+        let mut child = command
+            .current_dir(&dir.0)
+            .env_clear()
+            .env("PATH", env::var_os("PATH").unwrap_or_default())
+            .env("HOME", &dir.0)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let legal: Vec<_> = state
+            .legal_moves()
+            .into_iter()
+            .map(|mv| state.move_name(mv))
+            .collect();
+        let input = format!(
+            "board {state}\nplayer {}\nlegal {}\n",
+            state.current_player().glyph(),
+            legal.join(" ")
+        );
+        // closing its input once it's written tells the script there's no more
+        let written = child.stdin.take()?.write_all(input.as_bytes());
+        let output = child.stdout.take()?;
+        let (reply_tx, reply) = mpsc::channel();
+        thread::spawn(move || {
+            let mut line = String::new();
+            let _ = BufReader::new(output).read_line(&mut line);
+            let _ = reply_tx.send(line);
+        });
+        let line = reply.recv_timeout(self.time_limit);
+        #[cfg(unix)]
+        confine::stop(&mut child);
+        #[cfg(not(unix))]
+        let _ = child.kill();
+        let _ = child.wait();
+        written.ok()?;
+        Some(line.ok()?.trim().to_owned())
+    }
+}
+
+/// An empty directory for one run of a script, which only the player can get into, removed
+/// once it's dropped
+struct WorkDir(PathBuf);
+
+impl WorkDir {
+    fn new() -> io::Result<Self> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "games-bot-{}-{}",
+            process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = env::temp_dir().join(name);
+        let mut builder = fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        // failing if it's already there, so nothing else can have put files in it
+        builder.create(&path)?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+impl<G: GameState + Display> Bot<G> for Script<G> {
+    fn choose_move(&mut self, state: &G) -> Option<G::Move> {
+        match self.ask(state).and_then(|name| state.parse_move(&name)) {
+            Some(mv) => Some(mv),
+            None => self.fallback.choose_move(state),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Keeping a script to itself, as far as the system lets us.
+//!
+//! On any unix a script gets limits on its CPU time, memory, open files and the size of the files
+//! it writes, and its own process group, so whatever it starts is stopped along with it. On Linux
+//! 5.13 or later, Landlock also stops it writing anywhere but its own directory, or reading
+//! anything but the system's directories, the bots directory and its own directory, and from 6.7
+//! on, using TCP. Where Landlock isn't available the limits still apply.
+use std::{
+    io,
+    os::unix::process::CommandExt,
+    path::Path,
+    process::{Child, Command},
+    time::Duration,
+};
+
+/// Memory a script may allocate
+const MEMORY: u64 = 1 << 30;
+
+/// Files a script may have open at once
+const OPEN_FILES: u64 = 64;
+
+/// The biggest file a script may write
+const FILE_SIZE: u64 = 16 << 20;
+
+/// Where programs and the libraries they use live, which a script may read but not change
+const SYSTEM: [&str; 12] = [
+    "/bin", "/dev", "/etc", "/lib", "/lib32", "/lib64", "/nix", "/opt", "/proc", "/sbin", "/sys",
+    "/usr",
+];
+
+/// Set up `command` so what it runs can only write in `dir`, and read the system's directories
+/// and `readable`, within its limits for a move that should take `time_limit`
+pub fn confine(command: &mut Command, dir: &Path, readable: &Path, time_limit: Duration) {
+    // a second of CPU time to spare, as the clock stops it anyway
+    let cpu = time_limit.as_secs() + 1;
+    // without Landlock, there are still the limits
+    #[cfg(target_os = "linux")]
+    let ruleset = landlock::Ruleset::new(dir, readable).ok();
+    #[cfg(not(target_os = "linux"))]
+    let _ = (dir, readable);
+    command.process_group(0);
+    // SAFETY: between fork and exec only async-signal-safe calls are made, and nothing is
+    // allocated: the ruleset was made before forking
+    unsafe {
+        command.pre_exec(move || {
+            limit(libc::RLIMIT_CPU, cpu)?;
+            limit(libc::RLIMIT_DATA, MEMORY)?;
+            limit(libc::RLIMIT_NOFILE, OPEN_FILES)?;
+            limit(libc::RLIMIT_FSIZE, FILE_SIZE)?;
+            limit(libc::RLIMIT_CORE, 0)?;
+            #[cfg(target_os = "linux")]
+            if let Some(ruleset) = &ruleset {
+                ruleset.restrict_self()?;
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Stop a script started by a `confine`d command, and anything it started
+pub fn stop(child: &mut Child) {
+    // its process group has the same id as it
+    if let Ok(group) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: only sends a signal
+        unsafe { libc::kill(-group, libc::SIGKILL) };
+    }
+    let _ = child.kill();
+}
+
+/// What `getrlimit` and `setrlimit` take, which glibc gives a type of its own
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+type Resource = libc::c_int;
+
+/// Lower the limit on `resource` to `max`, unless it's already lower
+///
+/// # Safety
+///
+/// Only makes system calls, so can be called between fork and exec.
+unsafe fn limit(resource: Resource, max: libc::rlim_t) -> io::Result<()> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if libc::getrlimit(resource, &mut limit) != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // raising the hard limit isn't allowed
+    let max = max.min(limit.rlim_max);
+    let limit = libc::rlimit {
+        rlim_cur: max,
+        rlim_max: max,
+    };
+    if libc::setrlimit(resource, &limit) != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Landlock, Linux's way for a process to give up access to files and the network. The kernel
+/// doesn't need anything of us but the system calls, which are described in
+/// `include/uapi/linux/landlock.h`.
+#[cfg(target_os = "linux")]
+mod landlock {
+    use std::{
+        fs::File,
+        io, mem,
+        os::fd::{AsRawFd, FromRawFd, OwnedFd},
+        path::Path,
+        ptr,
+    };
+
+    use super::SYSTEM;
+
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+        handled_access_net: u64,
+    }
+
+    #[repr(C, packed)]
+    struct PathBeneathAttr {
+        allowed_access: u64,
+        parent_fd: i32,
+    }
+
+    const CREATE_RULESET_VERSION: libc::c_uint = 1 << 0;
+    const RULE_PATH_BENEATH: libc::c_uint = 1;
+
+    const EXECUTE: u64 = 1 << 0;
+    const WRITE_FILE: u64 = 1 << 1;
+    const READ_FILE: u64 = 1 << 2;
+    const READ_DIR: u64 = 1 << 3;
+    /// Everything else the first version handles, which is all about changing directories
+    const CHANGE_DIR: u64 = (1 << 13) - (1 << 4);
+    /// From version 2
+    const REFER: u64 = 1 << 13;
+    /// From version 3
+    const TRUNCATE: u64 = 1 << 14;
+    /// From version 5
+    const IOCTL_DEV: u64 = 1 << 15;
+    /// What can be allowed on a file, rather than a directory
+    const FILE: u64 = EXECUTE | WRITE_FILE | READ_FILE | TRUNCATE | IOCTL_DEV;
+
+    /// From version 4
+    const NET_BIND_TCP: u64 = 1 << 0;
+    const NET_CONNECT_TCP: u64 = 1 << 1;
+
+    /// What a process will be allowed to do once it's restricted to it
+    pub struct Ruleset {
+        fd: OwnedFd,
+        /// What the kernel can stop, of what we know about
+        handled: u64,
+    }
+
+    impl Ruleset {
+        /// Everything in `dir`, and reading the system's directories and `readable`. Fails
+        /// when the kernel doesn't have Landlock.
+        pub fn new(dir: &Path, readable: &Path) -> io::Result<Self> {
+            // SAFETY: asking for the version doesn't read the attributes
+            let version = unsafe {
+                libc::syscall(
+                    libc::SYS_landlock_create_ruleset,
+                    ptr::null::<RulesetAttr>(),
+                    0usize,
+                    CREATE_RULESET_VERSION,
+                )
+            };
+            if version < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let mut handled = EXECUTE | WRITE_FILE | READ_FILE | READ_DIR | CHANGE_DIR;
+            for (since, access) in [(2, REFER), (3, TRUNCATE), (5, IOCTL_DEV)] {
+                if version >= since {
+                    handled |= access;
+                }
+            }
+            let attr = RulesetAttr {
+                handled_access_fs: handled,
+                // nothing is allowed, so there's no TCP at all
+                handled_access_net: if version >= 4 {
+                    NET_BIND_TCP | NET_CONNECT_TCP
+                } else {
+                    0
+                },
+            };
+            // SAFETY: `attr` is the size given
+            let fd = unsafe {
+                libc::syscall(
+                    libc::SYS_landlock_create_ruleset,
+                    &attr as *const RulesetAttr,
+                    mem::size_of::<RulesetAttr>(),
+                    0 as libc::c_uint,
+                )
+            };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: the kernel just gave us the file descriptor, to close when we're done
+            let fd = unsafe { OwnedFd::from_raw_fd(fd as i32) };
+            let ruleset = Self { fd, handled };
+            ruleset.allow(dir, handled)?;
+            ruleset.allow(Path::new("/dev/null"), READ_FILE | WRITE_FILE | TRUNCATE)?;
+            for path in SYSTEM.iter().map(Path::new).chain([readable]) {
+                // not every system has all of them
+                if path.exists() {
+                    ruleset.allow(path, EXECUTE | READ_FILE | READ_DIR)?;
+                }
+            }
+            Ok(ruleset)
+        }
+
+        /// Allow `access` to `path`, and to everything in it if it's a directory
+        fn allow(&self, path: &Path, access: u64) -> io::Result<()> {
+            let file = File::open(path)?;
+            let access = if file.metadata()?.is_dir() {
+                access
+            } else {
+                access & FILE
+            };
+            let attr = PathBeneathAttr {
+                allowed_access: access & self.handled,
+                parent_fd: file.as_raw_fd(),
+            };
+            // SAFETY: both file descriptors are open, and `attr` is the kind of rule given
+            let result = unsafe {
+                libc::syscall(
+                    libc::SYS_landlock_add_rule,
+                    self.fd.as_raw_fd(),
+                    RULE_PATH_BENEATH,
+                    &attr as *const PathBeneathAttr,
+                    0 as libc::c_uint,
+                )
+            };
+            if result < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        /// Restrict this process, and whatever it runs, to the ruleset. There's no undoing it.
+        ///
+        /// # Safety
+        ///
+        /// Only makes system calls, so can be called between fork and exec.
+        pub unsafe fn restrict_self(&self) -> io::Result<()> {
+            // which is needed to restrict ourselves without being root
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let result = libc::syscall(
+                libc::SYS_landlock_restrict_self,
+                self.fd.as_raw_fd(),
+                0 as libc::c_uint,
+            );
+            if result < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Perfect play, by searching the whole game tree.
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use super::{GameResult, GameState};
+
+/// How the game ends with perfect play, from the point of view of one player
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Loss => "loss",
+            Self::Draw => "draw",
+            Self::Win => "win",
+        }
+    }
+
+    /// The same result from the other player's point of view
+    fn flip(self) -> Self {
+        match self {
+            Self::Loss => Self::Win,
+            Self::Draw => Self::Draw,
+            Self::Win => Self::Loss,
+        }
+    }
+}
+
+/// Minimax search, remembering positions it has already solved
+///
+/// Positions with the same `GameState::canonical` (rotations of each other, say) have the same
+/// outcome, so they share an entry. Only practical for games small enough to search completely.
+pub struct Solver<G: GameState> {
+    table: HashMap<G, Outcome>,
+}
+
+impl<G: GameState> Default for Solver<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G: GameState> Solver<G> {
+    pub fn new() -> Self {
+        Self {
+            table: HashMap::new(),
+        }
+    }
+
+    /// The outcome for the player to move if both sides play perfectly from here
+    fn solve(&mut self, state: &G) -> Outcome {
+        let player = state.current_player();
+        match state.result() {
+            Some(GameResult::Winner(winner)) if winner == player => return Outcome::Win,
+            Some(GameResult::Winner(_)) => return Outcome::Loss,
+            Some(GameResult::Draw) => return Outcome::Draw,
+            None => (),
+        }
+        let key = state.canonical();
+        if let Some(outcome) = self.table.get(&key) {
+            return *outcome;
+        }
+        let mut best = None;
+        for mv in state.legal_moves() {
+            let outcome = self.outcome_of(state, mv);
+            best = best.max(Some(outcome));
+            if outcome == Outcome::Win {
+                break;
+            }
+        }
+        // games are over once there are no moves, so there's always at least one
+        let outcome = best.unwrap_or(Outcome::Draw);
+        self.table.insert(key, outcome);
+        outcome
+    }
+
+    /// The outcome for the player to move of making `mv`
+    pub fn outcome_of(&mut self, state: &G, mv: G::Move) -> Outcome {
+        let mut next = state.clone();
+        next.apply(mv);
+        let outcome = self.solve(&next);
+        if next.current_player() == state.current_player() {
+            outcome
+        } else {
+            outcome.flip()
+        }
+    }
+
+    /// The outcome of each legal move for the player to move
+    pub fn evaluate(&mut self, state: &G) -> Vec<(G::Move, Outcome)> {
+        // each move is searched on its own thread with its own copy of the table, and what they
+        // found is merged back in afterwards. The outcomes are exact, so they don't depend on
+        // which thread finishes first.
+        let results: Vec<_> = state
+            .legal_moves()
+            .into_par_iter()
+            .map(|mv| {
+                let mut solver = Self {
+                    table: self.table.clone(),
+                };
+                let outcome = solver.outcome_of(state, mv);
+                (mv, outcome, solver.table)
+            })
+            .collect();
+        let mut outcomes = vec![];
+        for (mv, outcome, table) in results {
+            outcomes.push((mv, outcome));
+            self.table.extend(table);
+        }
+        outcomes
+    }
+}
@@ -0,0 +1,30 @@
+//! Calendar dates, as whole days since the unix epoch (UTC).
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+pub fn today() -> u64 {
+    day_of(SystemTime::now())
+}
+
+pub fn day_of(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECS_PER_DAY
+}
+
+/// Formats `day` as `yyyy-mm-dd`
+pub fn format(day: u64) -> String {
+    // Howard Hinnant's `civil_from_days`, for dates after 1970
+    let z = day + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + u64::from(m <= 2);
+    format!("{y:04}-{m:02}-{d:02}")
+}
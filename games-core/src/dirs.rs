@@ -0,0 +1,27 @@
+//! Where settings and saved data live on disk.
+use std::{env, io, path::PathBuf};
+
+/// `$XDG_CONFIG_HOME/games`, or `~/.config/games`
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// `$XDG_DATA_HOME/games`, or `~/.local/share/games`
+pub fn data_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// The path of `name` in the data directory
+pub fn data_file(name: &str) -> io::Result<PathBuf> {
+    data_dir()
+        .map(|dir| dir.join(name))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))
+}
+
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    let base = match env::var_os(var) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(fallback),
+    };
+    Some(base.join("games"))
+}
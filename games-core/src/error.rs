@@ -0,0 +1,84 @@
+//! What can go wrong running the games, worded for the player rather than for a programmer.
+use std::{error::Error, fmt, io, path::PathBuf};
+
+#[derive(Debug)]
+pub enum GamesError {
+    /// Reading from or writing to the terminal, or a file we don't know the name of, failed
+    Io(io::Error),
+    /// Reading or writing a particular file failed
+    File { path: PathBuf, source: io::Error },
+    /// A line in the settings file couldn't be understood
+    Config {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// A file the games wrote, like a recording, couldn't be read back
+    Corrupt {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// Connecting to, or talking with, another computer failed
+    Network(io::Error),
+    /// Another program, like a plugin or another computer, sent something that doesn't make sense
+    Protocol(String),
+}
+
+impl GamesError {
+    /// Whether this is from Ctrl+C or a signal, which isn't worth an error message
+    pub fn is_interrupted(&self) -> bool {
+        match self {
+            Self::Io(e) | Self::File { source: e, .. } | Self::Network(e) => {
+                e.kind() == io::ErrorKind::Interrupted
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for GamesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::File { path, source } => match source.kind() {
+                io::ErrorKind::NotFound => write!(f, "{} doesn't exist", path.display()),
+                io::ErrorKind::PermissionDenied => {
+                    write!(f, "not allowed to use {}", path.display())
+                }
+                _ => write!(f, "could not use {}: {source}", path.display()),
+            },
+            Self::Config {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{line}: {message}", path.display()),
+            Self::Corrupt {
+                path,
+                line,
+                message,
+            } => write!(
+                f,
+                "{} is damaged or not from this program (line {line}: {message})",
+                path.display()
+            ),
+            Self::Network(e) => write!(f, "lost the connection: {e}"),
+            Self::Protocol(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl Error for GamesError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) | Self::File { source: e, .. } | Self::Network(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GamesError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
@@ -0,0 +1,81 @@
+//! A log of what happens in games, one JSON object per line, for tools that want to follow along.
+//!
+//! Turned on with `--event-log <file>`. Every line has the time in milliseconds since the Unix
+//! epoch, the game, and what happened, like:
+//!
+//! ```text
+//! {"time":1760000000000,"game":"tic-tac-toe","event":"move","player":"X","square":"b2"}
+//! ```
+//!
+//! The events are `start`, `move`, `state` (with `from` and `to`) and `result` (with `winner`
+//! and `winner_name`, which are `null` for a draw).
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+static LOG: Mutex<Option<File>> = Mutex::new(None);
+
+pub enum Value<'a> {
+    Str(&'a str),
+    Num(u64),
+    Null,
+}
+
+/// Write events to `path` from now on, replacing anything already there
+pub fn open(path: &Path) -> io::Result<()> {
+    *LOG.lock().unwrap() = Some(File::create(path)?);
+    Ok(())
+}
+
+/// Add an `event` line for `game`, if the log is on
+pub fn log(game: &str, event: &str, fields: &[(&str, Value)]) {
+    let mut log = LOG.lock().unwrap();
+    let Some(file) = &mut *log else {
+        return;
+    };
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis());
+    let mut line = format!(
+        "{{\"time\":{time},\"game\":{},\"event\":{}",
+        quote(game),
+        quote(event)
+    );
+    for (name, value) in fields {
+        let value = match value {
+            Value::Str(text) => quote(text),
+            Value::Num(num) => num.to_string(),
+            Value::Null => "null".to_owned(),
+        };
+        line.push_str(&format!(",{}:{value}", quote(name)));
+    }
+    line.push('}');
+    // the log is for other tools, so the game carries on without it
+    let _ = writeln!(file, "{line}");
+}
+
+/// `text` as a JSON string
+pub fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            ch if ch.is_control() => quoted.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// `items` as a JSON array of strings
+pub fn quote_list(items: &[String]) -> String {
+    let items: Vec<_> = items.iter().map(|item| quote(item)).collect();
+    format!("[{}]", items.join(","))
+}
@@ -0,0 +1,139 @@
+//! The stats and ratings as CSV or JSON, for `./games stats export`, to look at in a spreadsheet
+//! or another tool.
+//!
+//! A CSV file only holds one table, so there are two: the games, one row each, and the players,
+//! one row for each game they've played. JSON has both in one object:
+//!
+//! ```text
+//! {"players":[{"game":"tic-tac-toe","name":"Ann","rating":1216.0,"wins":1,"draws":0,
+//!               "losses":0}],
+//!  "games":[{"finished":1760000000,"date":"2025-10-09","game":"tic-tac-toe",
+//!            "players":["Ann","Bob"],"winner":"Ann","duration":12.5,"moves":["b2","a1","a3"],
+//!            "setup":""}]}
+//! ```
+//!
+//! The winner is empty in CSV, and `null` in JSON, for a draw.
+use std::{fmt::Write as _, time::UNIX_EPOCH};
+
+use crate::{
+    date,
+    event_log::{quote, quote_list},
+    rating::Ratings,
+    stats::Record,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// A row for each game in `records`, with a header
+pub fn games_csv(records: &[Record]) -> String {
+    let mut csv = String::from("date,finished,game,players,winner,duration,moves,setup\n");
+    for record in records {
+        let fields = [
+            date::format(date::day_of(record.finished)),
+            finished_secs(record).to_string(),
+            record.game.clone(),
+            record.players.join(","),
+            record.winner.clone().unwrap_or_default(),
+            format!("{:.1}", record.duration.as_secs_f64()),
+            record.moves.join(" "),
+            record.setup.clone(),
+        ];
+        let fields: Vec<_> = fields.iter().map(|field| csv_field(field)).collect();
+        // writing to a string can't fail
+        writeln!(csv, "{}", fields.join(",")).unwrap();
+    }
+    csv
+}
+
+/// A row for each player of each game in `ratings`, best first within each game, with a header
+pub fn players_csv(ratings: &Ratings) -> String {
+    let mut csv = String::from("game,name,rating,wins,draws,losses\n");
+    for game in ratings.games() {
+        for (name, rating) in ratings.leaderboard(game) {
+            writeln!(
+                csv,
+                "{},{},{:.1},{},{},{}",
+                csv_field(game),
+                csv_field(name),
+                rating.elo,
+                rating.wins,
+                rating.draws,
+                rating.losses
+            )
+            .unwrap();
+        }
+    }
+    csv
+}
+
+/// The players in `ratings` and the games in `records`, as one JSON object
+pub fn to_json(records: &[Record], ratings: &Ratings) -> String {
+    let mut players = vec![];
+    for game in ratings.games() {
+        for (name, rating) in ratings.leaderboard(game) {
+            players.push(format!(
+                "{{\"game\":{},\"name\":{},\"rating\":{:.1},\"wins\":{},\"draws\":{},\
+                 \"losses\":{}}}",
+                quote(game),
+                quote(name),
+                rating.elo,
+                rating.wins,
+                rating.draws,
+                rating.losses
+            ));
+        }
+    }
+    let games: Vec<_> = records
+        .iter()
+        .map(|record| {
+            format!(
+                "{{\"finished\":{},\"date\":{},\"game\":{},\"players\":{},\"winner\":{},\
+                 \"duration\":{:.1},\"moves\":{},\"setup\":{}}}",
+                finished_secs(record),
+                quote(&date::format(date::day_of(record.finished))),
+                quote(&record.game),
+                quote_list(&record.players),
+                record.winner.as_deref().map_or("null".to_owned(), quote),
+                record.duration.as_secs_f64(),
+                quote_list(&record.moves),
+                quote(&record.setup)
+            )
+        })
+        .collect();
+    format!(
+        "{{\"players\":[{}],\"games\":[{}]}}\n",
+        players.join(","),
+        games.join(",")
+    )
+}
+
+fn finished_secs(record: &Record) -> u64 {
+    record
+        .finished
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// `text` as a CSV field, quoted if it has to be
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
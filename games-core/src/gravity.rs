@@ -0,0 +1,188 @@
+//! The rules of gravity tic-tac-toe: tic-tac-toe where marks fall, like Connect Four's, to the
+//! lowest empty square in the column they're dropped in.
+//!
+//! The board is 3x3 or 4x4, and a line has to go all the way across it to win. A move is just
+//! the column, by its letter, like `b`, since that's all a player chooses.
+use crate::{
+    bot::{GameResult, GameState},
+    player::Seat,
+    tic_tac_toe::board::{Player, X},
+};
+
+/// The smallest board, tic-tac-toe's
+pub const MIN_SIZE: usize = 3;
+
+/// The biggest board, `MIN_SIZE` up to Connect Four's four in a row
+pub const MAX_SIZE: usize = 4;
+
+/// A board size like `4`, checked against `MIN_SIZE` and `MAX_SIZE`
+pub fn parse_size(value: &str) -> Result<usize, String> {
+    let size = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("expected a number, found \"{value}\""))?;
+    if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+        return Err(format!(
+            "the board has to be from {MIN_SIZE} to {MAX_SIZE} squares across, not {size}"
+        ));
+    }
+    Ok(size)
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// How many squares across and down the board is
+    size: usize,
+    /// Each square's mark, row by row from the top left
+    cells: Vec<Option<Player>>,
+    to_move: Player,
+}
+
+impl Position {
+    /// An empty board `size` squares across and down, with X to move first
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            cells: vec![None; size * size],
+            to_move: X,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get(&self, pos: usize) -> Option<Player> {
+        self.cells[pos]
+    }
+
+    /// The square a mark dropped in `column` lands on, or `None` once the column is full
+    pub fn landing(&self, column: usize) -> Option<usize> {
+        (0..self.size)
+            .rev()
+            .map(|row| row * self.size + column)
+            .find(|&pos| self.cells[pos].is_none())
+    }
+
+    /// Every line across the board: the rows, the columns and the two diagonals
+    fn lines(&self) -> Vec<Vec<usize>> {
+        let size = self.size;
+        let mut lines: Vec<Vec<usize>> = (0..size)
+            .flat_map(|i| {
+                let row = (0..size).map(|j| i * size + j).collect();
+                let column = (0..size).map(|j| j * size + i).collect();
+                [row, column]
+            })
+            .collect();
+        lines.push((0..size).map(|i| i * size + i).collect());
+        lines.push((0..size).map(|i| i * size + size - 1 - i).collect());
+        lines
+    }
+
+    /// The line someone has made, and whose it is
+    pub fn line(&self) -> Option<(Vec<usize>, Player)> {
+        self.lines().into_iter().find_map(|line| {
+            let player = self.cells[line[0]]?;
+            line.iter()
+                .all(|&pos| self.cells[pos] == Some(player))
+                .then_some((line, player))
+        })
+    }
+}
+
+impl GameState for Position {
+    /// The column the mark is dropped in
+    type Move = usize;
+    type Player = Player;
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        if self.result().is_some() {
+            return vec![];
+        }
+        (0..self.size)
+            .filter(|&column| self.landing(column).is_some())
+            .collect()
+    }
+
+    fn apply(&mut self, column: usize) {
+        let pos = self
+            .landing(column)
+            .expect("only columns with room are played");
+        self.cells[pos] = Some(self.to_move);
+        self.to_move = self.to_move.other();
+    }
+
+    fn result(&self) -> Option<GameResult<Player>> {
+        if let Some((_, player)) = self.line() {
+            Some(GameResult::Winner(player))
+        } else if self.cells.iter().all(Option::is_some) {
+            Some(GameResult::Draw)
+        } else {
+            None
+        }
+    }
+
+    fn move_name(&self, column: usize) -> String {
+        ((b'a' + column as u8) as char).to_string()
+    }
+
+    fn parse_move(&self, name: &str) -> Option<usize> {
+        let mut chars = name.chars();
+        let column = (chars.next()? as usize).checked_sub('a' as usize)?;
+        if chars.next().is_some() {
+            return None;
+        }
+        self.legal_moves().contains(&column).then_some(column)
+    }
+
+    /// The board or its mirror image, whichever is smaller. Marks fall down, so turning the board
+    /// or flipping it upside down would be a different game.
+    fn canonical(&self) -> Self {
+        let size = self.size;
+        let mirrored = (0..size * size)
+            .map(|pos| self.cells[pos - pos % size + size - 1 - pos % size])
+            .collect();
+        let key = |cells: &Vec<Option<Player>>| -> Vec<Option<usize>> {
+            cells.iter().map(|sq| sq.map(Player::index)).collect()
+        };
+        let cells = if key(&mirrored) < key(&self.cells) {
+            mirrored
+        } else {
+            self.cells.clone()
+        };
+        Self {
+            cells,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl crate::testing::Invariants for Position {
+    fn check(&self) -> Result<(), String> {
+        use crate::tic_tac_toe::board::O;
+
+        let size = self.size;
+        // every mark sits on the bottom or on another mark
+        for pos in 0..size * (size - 1) {
+            if self.cells[pos].is_some() && self.cells[pos + size].is_none() {
+                return Err(format!("the mark on square {pos} is floating"));
+            }
+        }
+        let count = |player| self.cells.iter().filter(|&&sq| sq == Some(player)).count();
+        let (x, o) = (count(X), count(O));
+        let expected = match x.checked_sub(o) {
+            Some(0) => X,
+            Some(1) => O,
+            _ => return Err(format!("{x} Xs and {o} Os")),
+        };
+        if self.to_move != expected {
+            return Err(format!("{} to move with {x} Xs and {o} Os", self.to_move));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+//! The rules of infinite tic-tac-toe: tic-tac-toe where each player only ever has `MARKS` marks
+//! on the board. Placing another takes their oldest one off, so the board never fills up.
+//!
+//! The oldest mark is still there while its player chooses where to go, so the new one can't go
+//! on its square. A game still going after `MAX_MOVES` moves is a draw, which keeps the
+//! computer's searches finite. Moves are written like tic-tac-toe's, `b2`.
+use crate::{
+    bot::{GameResult, GameState},
+    player::Seat,
+    tic_tac_toe::board::{parse_square, square_name, Player, Win, O, SYMMETRIES, X},
+};
+
+/// How many marks each player can have on the board at once
+pub const MARKS: usize = 3;
+
+/// Moves before the game is called a draw
+pub const MAX_MOVES: u8 = 50;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// Each player's marks, oldest first
+    marks: [Vec<usize>; Player::COUNT],
+    to_move: Player,
+    /// How many moves have been made
+    moves: u8,
+}
+
+impl Position {
+    /// The empty board, with X to move first
+    pub fn new() -> Self {
+        Self {
+            marks: [vec![], vec![]],
+            to_move: X,
+            moves: 0,
+        }
+    }
+
+    pub fn get(&self, pos: usize) -> Option<Player> {
+        [X, O]
+            .into_iter()
+            .find(|player| self.marks[player.index()].contains(&pos))
+    }
+
+    /// The mark the player to move loses with their next move, once they have `MARKS` of them
+    pub fn vanishing(&self) -> Option<usize> {
+        let marks = &self.marks[self.to_move.index()];
+        (marks.len() == MARKS).then(|| marks[0])
+    }
+
+    /// How many moves are left before the game is a draw
+    pub fn moves_left(&self) -> u8 {
+        MAX_MOVES - self.moves
+    }
+
+    /// The line someone has made, and whose it is
+    pub fn line(&self) -> Option<(Win, Player)> {
+        Win::ALL.into_iter().find_map(|win| {
+            let [a, b, c] = win.squares().map(|pos| self.get(pos));
+            let player = a?;
+            (b == Some(player) && c == Some(player)).then_some((win, player))
+        })
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameState for Position {
+    type Move = usize;
+    type Player = Player;
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        if self.result().is_some() {
+            return vec![];
+        }
+        (0..9).filter(|&pos| self.get(pos).is_none()).collect()
+    }
+
+    fn apply(&mut self, pos: usize) {
+        let marks = &mut self.marks[self.to_move.index()];
+        if marks.len() == MARKS {
+            marks.remove(0);
+        }
+        marks.push(pos);
+        self.to_move = self.to_move.other();
+        self.moves += 1;
+    }
+
+    fn result(&self) -> Option<GameResult<Player>> {
+        if let Some((_, player)) = self.line() {
+            Some(GameResult::Winner(player))
+        } else if self.moves >= MAX_MOVES {
+            Some(GameResult::Draw)
+        } else {
+            None
+        }
+    }
+
+    fn move_name(&self, pos: usize) -> String {
+        square_name(pos)
+    }
+
+    fn parse_move(&self, name: &str) -> Option<usize> {
+        let pos = parse_square(name)?;
+        self.legal_moves().contains(&pos).then_some(pos)
+    }
+
+    /// The smallest of the board's rotations and reflections, which the lines are the same in,
+    /// with each player's marks kept in order of age
+    fn canonical(&self) -> Self {
+        SYMMETRIES
+            .iter()
+            .map(|from| {
+                let mut to = [0; 9];
+                for (new, &old) in from.iter().enumerate() {
+                    to[old] = new;
+                }
+                Self {
+                    marks: self
+                        .marks
+                        .clone()
+                        .map(|marks| marks.into_iter().map(|pos| to[pos]).collect()),
+                    ..self.clone()
+                }
+            })
+            .min_by(|a, b| a.marks.cmp(&b.marks))
+            .unwrap()
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl crate::testing::Invariants for Position {
+    fn check(&self) -> Result<(), String> {
+        let [x, o] = &self.marks;
+        if x.len() > MARKS || o.len() > MARKS {
+            return Err(format!("{} and {} marks", x.len(), o.len()));
+        }
+        if let Some(pos) = x.iter().find(|pos| o.contains(pos)) {
+            return Err(format!("both players have a mark on {}", square_name(*pos)));
+        }
+        let expected = if self.moves.is_multiple_of(2) { X } else { O };
+        if self.to_move != expected {
+            return Err(format!(
+                "{} to move after {} moves",
+                self.to_move, self.moves
+            ));
+        }
+        // nobody has lost a mark until both have all of theirs
+        let placed = usize::from(self.moves);
+        let expected = [placed.div_ceil(2), placed / 2].map(|count| count.min(MARKS));
+        if [x.len(), o.len()] != expected {
+            return Err(format!(
+                "{} and {} marks after {placed} moves",
+                x.len(),
+                o.len()
+            ));
+        }
+        Ok(())
+    }
+}
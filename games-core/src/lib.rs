@@ -0,0 +1,32 @@
+//! The games themselves, their computer players, and what's saved between runs, without anything
+//! to do with drawing them. The terminal frontend is in `games-tui`.
+pub mod achievements;
+pub mod annotations;
+pub mod bot;
+pub mod date;
+pub mod dirs;
+pub mod error;
+pub mod event_log;
+pub mod export;
+pub mod gravity;
+pub mod infinite;
+pub mod locale;
+pub mod migrate;
+pub mod notakto;
+pub mod pentago;
+pub mod player;
+pub mod plugin;
+pub mod quantum;
+pub mod rating;
+pub mod rng;
+pub mod select;
+pub mod sos;
+pub mod stats;
+#[cfg(feature = "test-util")]
+pub mod testing;
+pub mod three_mens_morris;
+pub mod tic_tac_toe;
+pub mod time_control;
+pub mod today;
+pub mod tournament;
+pub mod tutorial;
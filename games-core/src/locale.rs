@@ -0,0 +1,72 @@
+//! Translations of the text shown in games and the menu, chosen with `language` in the settings
+//! file.
+//!
+//! Text is written in English in the code, wrapped in `tr` (or `trf` when it has `{}` to fill
+//! in). Each other language has a file in `locales/`, built into the program, where each line is
+//! the English and its translation separated by a tab. Anything missing from a translation is
+//! shown in English.
+use std::{collections::HashMap, fmt, sync::OnceLock};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Language {
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::Spanish];
+
+    /// Used in the settings file
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+        }
+    }
+
+    pub fn from_str(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.name() == name)
+    }
+
+    /// The translations from English, or `None` for English itself
+    fn translations(self) -> Option<&'static str> {
+        match self {
+            Self::English => None,
+            Self::Spanish => Some(include_str!("../locales/es.tsv")),
+        }
+    }
+}
+
+/// The current language's translations. Empty for English, or before `set_language`.
+static TRANSLATIONS: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+
+/// Show text in `language` from now on. Only the first call does anything.
+pub fn set_language(language: Language) {
+    TRANSLATIONS.get_or_init(|| {
+        let lines = language.translations().unwrap_or("").lines();
+        lines.filter_map(|line| line.split_once('\t')).collect()
+    });
+}
+
+/// `english` in the current language
+pub fn tr(english: &'static str) -> &'static str {
+    TRANSLATIONS
+        .get()
+        .and_then(|translations| translations.get(english))
+        .unwrap_or(&english)
+}
+
+/// `english` in the current language, with each `{}` replaced by the next of `args`
+pub fn trf(english: &'static str, args: &[&dyn fmt::Display]) -> String {
+    let mut parts = tr(english).split("{}");
+    let mut text = parts.next().unwrap_or("").to_owned();
+    for (idx, part) in parts.enumerate() {
+        if let Some(arg) = args.get(idx) {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
@@ -0,0 +1,113 @@
+//! Versions of the files kept in the data directory, so a newer program can read files written
+//! by an older one, and an older one doesn't spoil files written by a newer one.
+//!
+//! Each file starts with a line saying what it is and which version of its format it's in,
+//! like `# games stats v2`. Files from before there were versions have no such line, and are
+//! version 1.
+//!
+//! Reading a file from an older version brings it up to date: it's changed one version at a
+//! time, and saved in the current version, after a copy of the original is kept next to it as
+//! `<file>.v<version>.bak`. A file from a newer version is an error, and is never written to.
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+/// One kind of file and how its format has changed
+pub struct Format {
+    /// What the file is, as written in its first line
+    pub name: &'static str,
+    /// How to turn the contents of each version into the next: the first turns version 1 into
+    /// version 2, and so on. The contents don't include the first line.
+    pub migrations: &'static [fn(&str) -> String],
+}
+
+/// Version 2 only added the first line, so there's nothing else to change
+pub fn add_version_line(contents: &str) -> String {
+    contents.to_owned()
+}
+
+impl Format {
+    /// The version files are written in
+    pub fn version(&self) -> usize {
+        self.migrations.len() + 1
+    }
+
+    /// The line files of this format start with
+    fn header(&self) -> String {
+        format!("# games {} v{}", self.name, self.version())
+    }
+
+    /// The contents of the file at `path` in the current version, without the first line, or
+    /// `None` if there's no file. An older file is brought up to date on the way.
+    pub fn read(&self, path: &Path) -> io::Result<Option<String>> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let (version, mut body) = match contents.split_once('\n') {
+            Some((first, rest)) => match self.parse_header(first) {
+                Some(version) => (version, rest.to_owned()),
+                None => (1, contents.clone()),
+            },
+            None => match self.parse_header(&contents) {
+                Some(version) => (version, String::new()),
+                None => (1, contents.clone()),
+            },
+        };
+        if version > self.version() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} is from a newer version of the games (v{version}), so it's been left \
+                     alone",
+                    path.display()
+                ),
+            ));
+        }
+        if version < self.version() {
+            for migrate in &self.migrations[version - 1..] {
+                body = migrate(&body);
+            }
+            // keep the original, in case something has gone wrong, before replacing it. If
+            // either can't be written this time, the file is still read correctly and tried
+            // again next time.
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let backup = path.with_file_name(format!("{name}.v{version}.bak"));
+            if !backup.exists() && fs::write(&backup, &contents).is_ok() {
+                let _ = self.write(path, &body);
+            }
+        }
+        Ok(Some(body))
+    }
+
+    /// Replace the file at `path` with `body`, in the current version
+    pub fn write(&self, path: &Path, body: &str) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, format!("{}\n{body}", self.header()))
+    }
+
+    /// Add `line` to the end of the file at `path`, bringing the file up to date first if it's
+    /// from an older version
+    pub fn append(&self, path: &Path, line: &str) -> io::Result<()> {
+        if self.read(path)?.is_none() {
+            self.write(path, "")?;
+        }
+        let mut file = OpenOptions::new().append(true).open(path)?;
+        writeln!(file, "{line}")
+    }
+
+    /// The version in `line` if it's the first line of a file of this format
+    fn parse_header(&self, line: &str) -> Option<usize> {
+        let rest = line.trim().strip_prefix("# games ")?;
+        let (name, version) = rest.rsplit_once(' ')?;
+        if name != self.name {
+            return None;
+        }
+        version.strip_prefix('v')?.parse().ok()
+    }
+}
@@ -0,0 +1,145 @@
+//! The rules of Notakto: tic-tac-toe on several boards at once, where both players play X.
+//!
+//! A board with three in a row is dead, and nobody can play on it again. Whoever kills the last
+//! live board loses, so there are no draws: a full board always has a line.
+//!
+//! A move is written with its board, counting from 1, then its square, like `2:b2`.
+use crate::{
+    bot::{GameResult, GameState},
+    tic_tac_toe::board::{parse_square, square_name, Player, Win, SYMMETRIES, X},
+};
+
+/// How many boards there are
+pub const BOARDS: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Move {
+    pub board: usize,
+    pub pos: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// The squares with an X on each board, a bit for each
+    boards: [u16; BOARDS],
+    to_move: Player,
+}
+
+impl Position {
+    /// Empty boards, with X, the first player, to move
+    pub fn new() -> Self {
+        Self {
+            boards: [0; BOARDS],
+            to_move: X,
+        }
+    }
+
+    /// Whether `board` has an X on `pos`
+    pub fn get(&self, board: usize, pos: usize) -> bool {
+        self.boards[board] & 1 << pos != 0
+    }
+
+    /// The line that killed `board`, if it's dead
+    pub fn line(&self, board: usize) -> Option<Win> {
+        Win::ALL
+            .into_iter()
+            .find(|win| win.squares().iter().all(|&pos| self.get(board, pos)))
+    }
+
+    pub fn is_dead(&self, board: usize) -> bool {
+        self.line(board).is_some()
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameState for Position {
+    type Move = Move;
+    type Player = Player;
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        (0..BOARDS)
+            .filter(|&board| !self.is_dead(board))
+            .flat_map(|board| {
+                (0..9)
+                    .filter(move |&pos| !self.get(board, pos))
+                    .map(move |pos| Move { board, pos })
+            })
+            .collect()
+    }
+
+    fn apply(&mut self, mv: Move) {
+        self.boards[mv.board] |= 1 << mv.pos;
+        self.to_move = self.to_move.other();
+    }
+
+    fn result(&self) -> Option<GameResult<Player>> {
+        // the last move killed the last board, so whoever made it lost
+        (0..BOARDS)
+            .all(|board| self.is_dead(board))
+            .then_some(GameResult::Winner(self.to_move))
+    }
+
+    fn move_name(&self, mv: Move) -> String {
+        format!("{}:{}", mv.board + 1, square_name(mv.pos))
+    }
+
+    fn parse_move(&self, name: &str) -> Option<Move> {
+        let (board, pos) = name.split_once(':')?;
+        let board = board.parse::<usize>().ok()?.checked_sub(1)?;
+        let mv = Move {
+            board,
+            pos: parse_square(pos)?,
+        };
+        self.legal_moves().contains(&mv).then_some(mv)
+    }
+
+    /// Each board as the smallest of its rotations and reflections, and the boards in order,
+    /// since it doesn't matter which is which
+    fn canonical(&self) -> Self {
+        let mut boards = self.boards.map(|board| {
+            SYMMETRIES
+                .iter()
+                .map(|from| {
+                    (0..9)
+                        .filter(|&pos| board & 1 << from[pos] != 0)
+                        .fold(0, |bits, pos| bits | 1 << pos)
+                })
+                .min()
+                .unwrap()
+        });
+        boards.sort_unstable();
+        Self {
+            boards,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl crate::testing::Invariants for Position {
+    fn check(&self) -> Result<(), String> {
+        // both players play X, so the number of them says whose turn it is
+        let marks: u32 = self.boards.iter().map(|board| board.count_ones()).sum();
+        let expected = if marks.is_multiple_of(2) {
+            X
+        } else {
+            X.other()
+        };
+        if self.to_move != expected {
+            return Err(format!("{} to move after {marks} marks", self.to_move));
+        }
+        if let Some(board) = self.boards.iter().position(|&board| board >= 1 << 9) {
+            return Err(format!("board {} has marks off it", board + 1));
+        }
+        Ok(())
+    }
+}
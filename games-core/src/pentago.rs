@@ -0,0 +1,281 @@
+//! The rules of Pentago: five in a row on a 6x6 board made of four 3x3 quadrants, where a turn
+//! is placing a marble and then turning one of the quadrants a quarter turn, either way.
+//!
+//! Only the board after the turn counts, so a line that turning breaks doesn't win and one it
+//! makes does. Lines for both players at once, or a full board without any, is a draw.
+//!
+//! Squares are named like tic-tac-toe's, from `a1` in the top left to `f6`, and quadrants are
+//! numbered 1 to 4 across and then down from the top left. A move is its square, then the
+//! quadrant and `R` for clockwise or `L` for anticlockwise, like `c3/2R`.
+use crate::{
+    bot::{GameResult, GameState},
+    player::Seat,
+    tic_tac_toe::board::{Player, O, X},
+};
+
+/// How many squares across and down the board is
+pub const SIZE: usize = 6;
+
+/// How many quadrants there are, each a 3x3 corner of the board
+pub const QUADRANTS: usize = 4;
+
+/// How long a line has to be to win
+pub const LINE: usize = 5;
+
+/// The directions a line can go in from its first square
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (-1, 1)];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Rotation {
+    Clockwise,
+    Anticlockwise,
+}
+
+impl Rotation {
+    pub const ALL: [Self; 2] = [Self::Clockwise, Self::Anticlockwise];
+
+    /// How it's written in a move, `R` for right or `L` for left
+    pub fn name(self) -> char {
+        match self {
+            Self::Clockwise => 'R',
+            Self::Anticlockwise => 'L',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|rotation| rotation.name() == c.to_ascii_uppercase())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Move {
+    /// Where the marble goes
+    pub pos: usize,
+    /// The quadrant turned afterwards
+    pub quadrant: usize,
+    pub rotation: Rotation,
+}
+
+/// The quadrant `pos` is in
+pub fn quadrant(pos: usize) -> usize {
+    (pos / SIZE / 3) * 2 + pos % SIZE / 3
+}
+
+/// The squares around the edge of `quadrant`, clockwise from its top left corner. Turning it a
+/// quarter turn moves each of their marbles two along, and its middle stays where it is.
+pub fn ring(quadrant: usize) -> [usize; 8] {
+    let (left, top) = (quadrant % 2 * 3, quadrant / 2 * 3);
+    // numbered like a tic-tac-toe board's squares
+    [0, 1, 2, 5, 8, 7, 6, 3].map(|sq| (top + sq / 3) * SIZE + left + sq % 3)
+}
+
+/// The name of the square at `pos`, like `c3`
+pub fn square_name(pos: usize) -> String {
+    let column = (b'a' + (pos % SIZE) as u8) as char;
+    format!("{column}{}", pos / SIZE + 1)
+}
+
+/// The inverse of `square_name`. `None` for a square off the board.
+pub fn parse_square(name: &str) -> Option<usize> {
+    let mut chars = name.chars();
+    let column = (chars.next()? as usize).checked_sub('a' as usize)?;
+    let row = chars.as_str().parse::<usize>().ok()?.checked_sub(1)?;
+    (column < SIZE && row < SIZE).then_some(row * SIZE + column)
+}
+
+/// The line of `LINE` squares from `pos` in `direction`, if it fits on the board
+fn line_from(pos: usize, (dx, dy): (isize, isize)) -> Option<[usize; LINE]> {
+    let steps = LINE as isize - 1;
+    let (x, y) = (pos % SIZE, pos / SIZE);
+    let end_x = x.checked_add_signed(dx * steps)?;
+    let end_y = y.checked_add_signed(dy * steps)?;
+    if end_x >= SIZE || end_y >= SIZE {
+        return None;
+    }
+    Some(std::array::from_fn(|step| {
+        let step = step as isize;
+        (y as isize + dy * step) as usize * SIZE + (x as isize + dx * step) as usize
+    }))
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// Each square's marble, if it has one
+    squares: [Option<Player>; SIZE * SIZE],
+    to_move: Player,
+}
+
+impl Position {
+    /// The empty board, with X to move first
+    pub fn new() -> Self {
+        Self {
+            squares: [None; SIZE * SIZE],
+            to_move: X,
+        }
+    }
+
+    pub fn get(&self, pos: usize) -> Option<Player> {
+        self.squares[pos]
+    }
+
+    /// Each line of `LINE` marbles the same color, and whose they are. A line of six is two.
+    pub fn lines(&self) -> Vec<([usize; LINE], Player)> {
+        (0..SIZE * SIZE)
+            .flat_map(|pos| {
+                DIRECTIONS
+                    .into_iter()
+                    .filter_map(move |dir| line_from(pos, dir))
+            })
+            .filter_map(|line| {
+                let player = self.squares[line[0]]?;
+                line.iter()
+                    .all(|&pos| self.squares[pos] == Some(player))
+                    .then_some((line, player))
+            })
+            .collect()
+    }
+
+    /// Turn `quadrant` a quarter turn
+    fn rotate(&mut self, quadrant: usize, rotation: Rotation) {
+        let ring = ring(quadrant);
+        let before = ring.map(|pos| self.squares[pos]);
+        let shift = match rotation {
+            Rotation::Clockwise => 2,
+            Rotation::Anticlockwise => ring.len() - 2,
+        };
+        for (i, marble) in before.into_iter().enumerate() {
+            self.squares[ring[(i + shift) % ring.len()]] = marble;
+        }
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameState for Position {
+    type Move = Move;
+    type Player = Player;
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        if self.result().is_some() {
+            return vec![];
+        }
+        (0..SIZE * SIZE)
+            .filter(|&pos| self.squares[pos].is_none())
+            .flat_map(|pos| {
+                (0..QUADRANTS).flat_map(move |quadrant| {
+                    Rotation::ALL.map(|rotation| Move {
+                        pos,
+                        quadrant,
+                        rotation,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    fn apply(&mut self, mv: Move) {
+        self.squares[mv.pos] = Some(self.to_move);
+        self.rotate(mv.quadrant, mv.rotation);
+        self.to_move = self.to_move.other();
+    }
+
+    fn result(&self) -> Option<GameResult<Player>> {
+        let lines = self.lines();
+        let has_line = |player| lines.iter().any(|&(_, owner)| owner == player);
+        match (has_line(X), has_line(O)) {
+            (true, true) => Some(GameResult::Draw),
+            (true, false) => Some(GameResult::Winner(X)),
+            (false, true) => Some(GameResult::Winner(O)),
+            (false, false) if self.squares.iter().all(Option::is_some) => Some(GameResult::Draw),
+            (false, false) => None,
+        }
+    }
+
+    fn move_name(&self, mv: Move) -> String {
+        format!(
+            "{}/{}{}",
+            square_name(mv.pos),
+            mv.quadrant + 1,
+            mv.rotation.name()
+        )
+    }
+
+    fn parse_move(&self, name: &str) -> Option<Move> {
+        let (square, turn) = name.split_once('/')?;
+        let mut chars = turn.chars();
+        let quadrant = (chars.next()?.to_digit(10)? as usize).checked_sub(1)?;
+        let rotation = Rotation::from_char(chars.next()?)?;
+        if chars.next().is_some() {
+            return None;
+        }
+        let mv = Move {
+            pos: parse_square(square)?,
+            quadrant,
+            rotation,
+        };
+        self.legal_moves().contains(&mv).then_some(mv)
+    }
+
+    /// The smallest of the board's rotations and reflections, which take quadrants to quadrants
+    /// and lines to lines
+    fn canonical(&self) -> Self {
+        let last = SIZE - 1;
+        let squares = (0..8)
+            .map(|symmetry| {
+                std::array::from_fn(|pos| {
+                    let (mut x, mut y) = (pos % SIZE, pos / SIZE);
+                    if symmetry >= 4 {
+                        x = last - x;
+                    }
+                    for _ in 0..symmetry % 4 {
+                        (x, y) = (last - y, x);
+                    }
+                    self.squares[y * SIZE + x]
+                })
+            })
+            .min_by_key(|squares: &[Option<Player>; SIZE * SIZE]| {
+                squares.map(|sq| sq.map(Player::index))
+            })
+            .unwrap();
+        Self {
+            squares,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl crate::testing::Invariants for Position {
+    fn check(&self) -> Result<(), String> {
+        // turning never adds or takes away marbles, so X has as many as O or one more
+        let count = |player| {
+            self.squares
+                .iter()
+                .filter(|&&sq| sq == Some(player))
+                .count()
+        };
+        let (x, o) = (count(X), count(O));
+        let expected = match x.checked_sub(o) {
+            Some(0) => X,
+            Some(1) => O,
+            _ => return Err(format!("{x} X marbles and {o} O marbles")),
+        };
+        if self.to_move != expected {
+            return Err(format!(
+                "{} to move with {x} X marbles and {o} O marbles",
+                self.to_move
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+//! Who's playing, for games with any number of players.
+//!
+//! Players are numbered from 0 in turn order. The theme gives each number a style, so games
+//! don't have to choose colors themselves.
+
+/// One of a game's players
+pub trait Seat: Copy + PartialEq + Send + Sync {
+    /// How many players the game has
+    const COUNT: usize;
+
+    /// 0 for whoever plays first, up to `COUNT - 1`
+    fn index(self) -> usize;
+    /// The player numbered `index`, which must be less than `COUNT`
+    fn from_index(index: usize) -> Self;
+    /// The letter or symbol the player is shown as, like `X`
+    fn glyph(self) -> char;
+
+    /// Whoever plays after this player
+    fn next(self) -> Self {
+        Self::from_index((self.index() + 1) % Self::COUNT)
+    }
+}
@@ -0,0 +1,247 @@
+//! Games from outside the program, so new ones can be added without rebuilding it.
+//!
+//! A plugin is an executable in the `plugins` directory of the data directory, named after its
+//! game, like `connect-four`. It can be written in any language: it's asked about the rules
+//! over its standard input and output, one line at a time.
+//!
+//! The plugin starts by writing `board <columns> <rows>`. It is then sent the moves made so far,
+//! like `position b2 a1`, and answers with
+//!
+//! ```text
+//! cells .o./.x./...
+//! next 1
+//! legal a1 c1 a2 c2 a3 b3 c3
+//! result none
+//! end
+//! ```
+//!
+//! `cells` is each row from the top, separated by `/`, with `.` for an empty square and any
+//! other character drawn as it is. `next` is whose turn it is, 1 or 2. A move is the square
+//! played, like tic-tac-toe's: a letter for the column and a number for the row, from the top
+//! left. `result` is `none` while the game is still going, `draw`, or the winner's number.
+//! Each line has to come within `REPLY_TIMEOUT`, so a plugin that hangs can't freeze the game.
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use crate::{bot::GameResult, dirs, error::GamesError};
+
+/// Most columns a board can have, one for each letter
+pub const MAX_COLUMNS: usize = 26;
+
+/// How long a plugin has to send each line of an answer
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Plugin {
+    pub name: String,
+    path: PathBuf,
+}
+
+/// Every plugin in the plugins directory, by name
+pub fn discover() -> Vec<Plugin> {
+    let Some(dir) = dirs::data_dir().map(|dir| dir.join("plugins")) else {
+        return vec![];
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut plugins: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_executable(path))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?.to_owned();
+            Some(Plugin { name, path })
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    plugins
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+impl Plugin {
+    /// Run the plugin, ready to be asked about positions
+    pub fn start(&self) -> Result<Session, GamesError> {
+        let mut child = Command::new(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|source| GamesError::File {
+                path: self.path.clone(),
+                source,
+            })?;
+        // both were asked for above
+        let input = child.stdin.take().unwrap();
+        let output = child.stdout.take().unwrap();
+        let (line_tx, lines) = mpsc::channel();
+        // read on a thread of its own, so waiting for a line can time out. It stops once the
+        // plugin does, which dropping the session makes sure of.
+        thread::spawn(move || {
+            for line in BufReader::new(output).lines() {
+                if line_tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut session = Session {
+            name: self.name.clone(),
+            child,
+            input,
+            lines,
+            columns: 0,
+            rows: 0,
+        };
+        let line = session.read_line()?;
+        let size = line
+            .strip_prefix("board ")
+            .and_then(|size| size.split_once(' '))
+            .and_then(|(columns, rows)| Some((columns.parse().ok()?, rows.parse().ok()?)));
+        match size {
+            Some((columns @ 1..=MAX_COLUMNS, rows @ 1..)) => {
+                session.columns = columns;
+                session.rows = rows;
+                Ok(session)
+            }
+            _ => Err(session.unexpected(&line, "a board size")),
+        }
+    }
+}
+
+/// A running plugin
+pub struct Session {
+    name: String,
+    child: Child,
+    input: ChildStdin,
+    /// Each line the plugin writes, as it writes it
+    lines: Receiver<io::Result<String>>,
+    pub columns: usize,
+    pub rows: usize,
+}
+
+/// What a plugin said about a position
+pub struct Position {
+    /// Each square, row by row from the top left. `None` if it's empty.
+    pub cells: Vec<Option<char>>,
+    /// Whose turn it is, counting from 0
+    pub next: usize,
+    /// The names of the squares that can be played
+    pub legal: Vec<String>,
+    pub result: Option<GameResult<usize>>,
+}
+
+impl Session {
+    /// The position after `moves`, from the start
+    pub fn position(&mut self, moves: &[String]) -> Result<Position, GamesError> {
+        let mut request = String::from("position");
+        for mv in moves {
+            request.push(' ');
+            request.push_str(mv);
+        }
+        writeln!(self.input, "{request}")?;
+        self.input.flush()?;
+        let mut cells = None;
+        let mut next = None;
+        let mut legal = None;
+        let mut result = None;
+        loop {
+            let line = self.read_line()?;
+            let (key, value) = line.split_once(' ').unwrap_or((&line, ""));
+            match key {
+                "cells" => {
+                    let rows: Vec<_> = value.split('/').collect();
+                    let fits = |row: &&str| row.chars().count() == self.columns && row.is_ascii();
+                    if rows.len() != self.rows || !rows.iter().all(fits) {
+                        return Err(self.unexpected(&line, "a row for each row of the board"));
+                    }
+                    let squares = rows.concat();
+                    cells = Some(squares.chars().map(|c| (c != '.').then_some(c)).collect());
+                }
+                "next" => match value {
+                    "1" => next = Some(0),
+                    "2" => next = Some(1),
+                    _ => return Err(self.unexpected(&line, "whose turn it is")),
+                },
+                "legal" => legal = Some(value.split_whitespace().map(str::to_owned).collect()),
+                "result" => match value {
+                    "none" => result = Some(None),
+                    "draw" => result = Some(Some(GameResult::Draw)),
+                    "1" => result = Some(Some(GameResult::Winner(0))),
+                    "2" => result = Some(Some(GameResult::Winner(1))),
+                    _ => return Err(self.unexpected(&line, "a result")),
+                },
+                "end" => break,
+                _ => return Err(self.unexpected(&line, "a description of the position")),
+            }
+        }
+        match (cells, next, legal, result) {
+            (Some(cells), Some(next), Some(legal), Some(result)) => Ok(Position {
+                cells,
+                next,
+                legal,
+                result,
+            }),
+            _ => Err(GamesError::Protocol(format!(
+                "{} left something out of a position",
+                self.name
+            ))),
+        }
+    }
+
+    fn read_line(&mut self) -> Result<String, GamesError> {
+        match self.lines.recv_timeout(REPLY_TIMEOUT) {
+            Ok(line) => Ok(line?.trim().to_owned()),
+            Err(RecvTimeoutError::Timeout) => Err(GamesError::Protocol(format!(
+                "{} took too long to answer",
+                self.name
+            ))),
+            Err(RecvTimeoutError::Disconnected) => {
+                Err(GamesError::Protocol(format!("{} stopped", self.name)))
+            }
+        }
+    }
+
+    fn unexpected(&self, line: &str, expected: &str) -> GamesError {
+        GamesError::Protocol(format!(
+            "{} sent \"{line}\" instead of {expected}",
+            self.name
+        ))
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// The name of a square in moves, like `b3`
+pub fn square_name(column: usize, row: usize) -> String {
+    format!("{}{}", (b'a' + column as u8) as char, row + 1)
+}
+
+/// The column and row of a square named like `b3`, counting from 0, on a board `columns` wide
+pub fn parse_square(name: &str, columns: usize) -> Option<(usize, usize)> {
+    let mut chars = name.chars();
+    let letter = chars.next().filter(char::is_ascii_lowercase)?;
+    let column = (letter as u8 - b'a') as usize;
+    let row = chars.as_str().parse::<usize>().ok()?.checked_sub(1)?;
+    (column < columns).then_some((column, row))
+}
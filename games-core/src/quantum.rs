@@ -0,0 +1,315 @@
+//! The rules of quantum tic-tac-toe: each move is a "spooky" mark in two squares at once,
+//! entangling them, until the marks make a cycle and collapse into ordinary ones.
+//!
+//! The spooky marks are the edges of a graph on the squares. A mark that closes a cycle leaves
+//! the other player to choose which of its two squares it ends up in; that decides the rest,
+//! since every other mark touching a square that's taken moves to its other square. Then that
+//! player makes their own move. Once a single square is left, the last move is an ordinary
+//! mark there.
+//!
+//! A collapse can give both players a line at once. Then the line finished sooner wins: the one
+//! whose last mark went down on the earlier turn.
+//!
+//! Moves are written `a1+b2` for a spooky mark, `=a1` for choosing where the mark that closed a
+//! cycle goes, and `b2` for the last, ordinary mark.
+use crate::{
+    bot::{GameResult, GameState},
+    player::Seat,
+    tic_tac_toe::board::{parse_square, square_name, Player, Win, SYMMETRIES, X},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Move {
+    /// A spooky mark in both squares, smallest first
+    Spooky(usize, usize),
+    /// Where the mark that closed a cycle goes
+    Collapse(usize),
+    /// An ordinary mark in the only square left
+    Classical(usize),
+}
+
+/// A player's mark, and the turn it was made on
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mark {
+    pub player: Player,
+    pub turn: u8,
+}
+
+/// A spooky mark, not yet collapsed into one of its squares
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Spooky {
+    pub mark: Mark,
+    /// Its squares, smallest first
+    pub squares: [usize; 2],
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// The ordinary mark in each square, once there is one
+    classical: [Option<Mark>; 9],
+    /// The spooky marks, oldest first
+    spooky: Vec<Spooky>,
+    to_move: Player,
+    /// The turn the next mark is made on, from 1
+    turn: u8,
+    /// Whether the last spooky mark closed a cycle, which the player to move has to collapse
+    collapsing: bool,
+}
+
+impl Position {
+    /// The empty board, with X to move first
+    pub fn new() -> Self {
+        Self {
+            classical: [None; 9],
+            spooky: vec![],
+            to_move: X,
+            turn: 1,
+            collapsing: false,
+        }
+    }
+
+    /// The ordinary mark in `pos`, if it has one
+    pub fn classical(&self, pos: usize) -> Option<Mark> {
+        self.classical[pos]
+    }
+
+    /// The spooky marks in `pos`, oldest first
+    pub fn spooky(&self, pos: usize) -> impl Iterator<Item = Mark> + '_ {
+        self.spooky
+            .iter()
+            .filter(move |spooky| spooky.squares.contains(&pos))
+            .map(|spooky| spooky.mark)
+    }
+
+    /// The spooky mark that closed a cycle, while it's waiting to be collapsed
+    pub fn collapsing(&self) -> Option<Spooky> {
+        self.collapsing.then(|| *self.spooky.last().unwrap())
+    }
+
+    /// The turn the next mark is made on, from 1
+    pub fn turn(&self) -> u8 {
+        self.turn
+    }
+
+    /// The squares without an ordinary mark
+    fn open(&self) -> Vec<usize> {
+        (0..9)
+            .filter(|&pos| self.classical[pos].is_none())
+            .collect()
+    }
+
+    /// Whether spooky marks already join `a` to `b`
+    fn connected(&self, a: usize, b: usize) -> bool {
+        let mut seen = [false; 9];
+        let mut stack = vec![a];
+        seen[a] = true;
+        while let Some(pos) = stack.pop() {
+            if pos == b {
+                return true;
+            }
+            for spooky in self.spooky.iter().filter(|s| s.squares.contains(&pos)) {
+                for next in spooky.squares {
+                    if !seen[next] {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Put the last spooky mark in `pos`, and every mark entangled with it where that leaves it
+    fn collapse(&mut self, pos: usize) {
+        let last = self.spooky.pop().unwrap();
+        let mut placed = vec![(last.mark, pos)];
+        while let Some((mark, pos)) = placed.pop() {
+            self.classical[pos] = Some(mark);
+            let (moved, kept) = self
+                .spooky
+                .iter()
+                .partition::<Vec<_>, _>(|spooky| spooky.squares.contains(&pos));
+            self.spooky = kept;
+            for spooky in moved {
+                let [a, b] = spooky.squares;
+                placed.push((spooky.mark, if a == pos { b } else { a }));
+            }
+        }
+        self.collapsing = false;
+    }
+
+    /// The line that wins, and whose it is: if both players have one, the one finished sooner
+    pub fn line(&self) -> Option<(Win, Player)> {
+        Win::ALL
+            .into_iter()
+            .filter_map(|win| {
+                let [a, b, c] = win.squares().map(|pos| self.classical[pos]);
+                let (a, b, c) = (a?, b?, c?);
+                let finished = a.turn.max(b.turn).max(c.turn);
+                (a.player == b.player && b.player == c.player).then_some((finished, win, a.player))
+            })
+            .min_by_key(|&(finished, _, _)| finished)
+            .map(|(_, win, player)| (win, player))
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameState for Position {
+    type Move = Move;
+    type Player = Player;
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        if let Some(spooky) = self.collapsing() {
+            return spooky.squares.map(Move::Collapse).to_vec();
+        }
+        if self.line().is_some() {
+            return vec![];
+        }
+        match *self.open() {
+            [] => vec![],
+            [pos] => vec![Move::Classical(pos)],
+            ref open => open
+                .iter()
+                .enumerate()
+                .flat_map(|(i, &a)| open[i + 1..].iter().map(move |&b| Move::Spooky(a, b)))
+                .collect(),
+        }
+    }
+
+    fn apply(&mut self, mv: Move) {
+        let mark = Mark {
+            player: self.to_move,
+            turn: self.turn,
+        };
+        match mv {
+            Move::Spooky(a, b) => {
+                self.collapsing = self.connected(a, b);
+                self.spooky.push(Spooky {
+                    mark,
+                    squares: [a, b],
+                });
+            }
+            Move::Collapse(pos) => {
+                // the player who chose then makes their own move
+                self.collapse(pos);
+                return;
+            }
+            Move::Classical(pos) => self.classical[pos] = Some(mark),
+        }
+        self.turn += 1;
+        self.to_move = self.to_move.other();
+    }
+
+    fn result(&self) -> Option<GameResult<Player>> {
+        if self.collapsing {
+            return None;
+        }
+        if let Some((_, player)) = self.line() {
+            Some(GameResult::Winner(player))
+        } else if self.open().is_empty() {
+            Some(GameResult::Draw)
+        } else {
+            None
+        }
+    }
+
+    fn move_name(&self, mv: Move) -> String {
+        match mv {
+            Move::Spooky(a, b) => format!("{}+{}", square_name(a), square_name(b)),
+            Move::Collapse(pos) => format!("={}", square_name(pos)),
+            Move::Classical(pos) => square_name(pos),
+        }
+    }
+
+    fn parse_move(&self, name: &str) -> Option<Move> {
+        let mv = if let Some((a, b)) = name.split_once('+') {
+            let (a, b) = (parse_square(a)?, parse_square(b)?);
+            Move::Spooky(a.min(b), a.max(b))
+        } else if let Some(pos) = name.strip_prefix('=') {
+            Move::Collapse(parse_square(pos)?)
+        } else {
+            Move::Classical(parse_square(name)?)
+        };
+        self.legal_moves().contains(&mv).then_some(mv)
+    }
+
+    /// The smallest of the board's rotations and reflections, which the lines are the same in
+    fn canonical(&self) -> Self {
+        SYMMETRIES
+            .iter()
+            .map(|from| {
+                let mut to = [0; 9];
+                for (new, &old) in from.iter().enumerate() {
+                    to[old] = new;
+                }
+                let spooky = self.spooky.iter().map(|spooky| {
+                    let [a, b] = spooky.squares.map(|pos| to[pos]);
+                    Spooky {
+                        squares: [a.min(b), a.max(b)],
+                        ..*spooky
+                    }
+                });
+                Self {
+                    classical: from.map(|pos| self.classical[pos]),
+                    spooky: spooky.collect(),
+                    ..self.clone()
+                }
+            })
+            .min_by_key(|position| {
+                let classical = position
+                    .classical
+                    .map(|mark| mark.map(|mark| (mark.player.index(), mark.turn)));
+                let spooky: Vec<_> = position.spooky.iter().map(|s| s.squares).collect();
+                (classical, spooky)
+            })
+            .unwrap()
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl crate::testing::Invariants for Position {
+    fn check(&self) -> Result<(), String> {
+        let marks = self.classical.iter().flatten().count() + self.spooky.len();
+        if marks != self.turn as usize - 1 {
+            return Err(format!("{marks} marks by turn {}", self.turn));
+        }
+        for spooky in &self.spooky {
+            let [a, b] = spooky.squares;
+            if a >= b {
+                return Err(format!(
+                    "spooky mark in {} and {}",
+                    square_name(a),
+                    square_name(b)
+                ));
+            }
+            if self.classical[a].is_some() || self.classical[b].is_some() {
+                return Err(format!(
+                    "spooky mark {} left over a collapse",
+                    spooky.mark.turn
+                ));
+            }
+        }
+        // a cycle is collapsed straight away, so only the last mark can close one
+        let earlier = Self {
+            spooky: self.spooky[..self.spooky.len().saturating_sub(1)].to_vec(),
+            ..self.clone()
+        };
+        let last_closes = self
+            .spooky
+            .last()
+            .is_some_and(|last| earlier.connected(last.squares[0], last.squares[1]));
+        if last_closes != self.collapsing {
+            return Err("a cycle and whether it's being collapsed don't agree".to_owned());
+        }
+        Ok(())
+    }
+}
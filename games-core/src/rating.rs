@@ -0,0 +1,205 @@
+//! Elo ratings for everyone who has played, computer players included, kept in `ratings.tsv` in
+//! the data directory.
+//!
+//! Everyone starts at 1200, separately for each game. After a game each pair of players moves
+//! towards the result between them: a win beats everyone else, and everyone else draws with each
+//! other. After the version line (see [`migrate`]), each line of the file is one player:
+//!
+//! ```text
+//! <game>  <name>  <rating>  <wins>  <draws>  <losses>
+//! ```
+//!
+//! If the file is missing, the ratings are worked out again from the stats file.
+use std::{collections::HashMap, fmt::Write as _, io, path::PathBuf};
+
+use crate::{
+    dirs,
+    migrate::{self, Format},
+    stats::{self, Record},
+};
+
+const FORMAT: Format = Format {
+    name: "ratings",
+    migrations: &[migrate::add_version_line],
+};
+
+/// Everyone's rating before their first game
+const START: f64 = 1200.;
+
+/// How far one game can move a rating
+const K: f64 = 32.;
+
+#[derive(Clone, Copy)]
+pub struct Rating {
+    pub elo: f64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Rating {
+    const NEW: Self = Self {
+        elo: START,
+        wins: 0,
+        draws: 0,
+        losses: 0,
+    };
+
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    /// The score expected against someone rated `other`: 1 for a certain win, 0 for a certain loss
+    fn expected(&self, other: f64) -> f64 {
+        1. / (1. + 10f64.powf((other - self.elo) / 400.))
+    }
+}
+
+#[derive(Default)]
+pub struct Ratings {
+    /// By game, then player name
+    ratings: HashMap<String, HashMap<String, Rating>>,
+}
+
+impl Ratings {
+    /// The ratings after the games in `records`, oldest first
+    pub fn from_records(records: &[Record]) -> Self {
+        let mut ratings = Self::default();
+        for record in records {
+            ratings.add(record);
+        }
+        ratings
+    }
+
+    /// Update the ratings of the players in `record`. Games with fewer than two players, like
+    /// puzzles, don't count.
+    pub fn add(&mut self, record: &Record) {
+        let players = &record.players;
+        if players.len() < 2 {
+            return;
+        }
+        let game = self.ratings.entry(record.game.clone()).or_default();
+        let before: Vec<_> = players
+            .iter()
+            .map(|name| game.get(name).copied().unwrap_or(Rating::NEW))
+            .collect();
+        // playing several people at once counts for as much as playing one
+        let k = K / (players.len() - 1) as f64;
+        for (idx, name) in players.iter().enumerate() {
+            let mut change = 0.;
+            for (other_idx, other) in players.iter().enumerate() {
+                if other_idx == idx || other == name {
+                    continue;
+                }
+                let score = match &record.winner {
+                    Some(winner) if winner == name => 1.,
+                    Some(winner) if winner == other => 0.,
+                    _ => 0.5,
+                };
+                change += k * (score - before[idx].expected(before[other_idx].elo));
+            }
+            let rating = game.entry(name.clone()).or_insert(Rating::NEW);
+            rating.elo += change;
+            match &record.winner {
+                None => rating.draws += 1,
+                Some(winner) if winner == name => rating.wins += 1,
+                Some(_) => rating.losses += 1,
+            }
+        }
+    }
+
+    /// Every game anyone has played, in alphabetical order
+    pub fn games(&self) -> Vec<&str> {
+        let mut games: Vec<_> = self.ratings.keys().map(String::as_str).collect();
+        games.sort_unstable();
+        games
+    }
+
+    /// Everyone who has played `game`, best first
+    pub fn leaderboard(&self, game: &str) -> Vec<(&str, Rating)> {
+        let mut players: Vec<_> = self
+            .ratings
+            .get(game)
+            .into_iter()
+            .flatten()
+            .map(|(name, rating)| (&**name, *rating))
+            .collect();
+        players.sort_by(|(a_name, a), (b_name, b)| {
+            b.elo.total_cmp(&a.elo).then_with(|| a_name.cmp(b_name))
+        });
+        players
+    }
+
+    /// Load the ratings file, or work the ratings out from the stats file if there isn't one
+    pub fn load() -> io::Result<Self> {
+        match Self::load_file()? {
+            Some(ratings) => Ok(ratings),
+            None => Ok(Self::from_records(&stats::load()?)),
+        }
+    }
+
+    /// The ratings in the ratings file, if there is one. Lines that can't be parsed are skipped.
+    fn load_file() -> io::Result<Option<Self>> {
+        let Some(contents) = FORMAT.read(&path()?)? else {
+            return Ok(None);
+        };
+        let mut ratings = Self::default();
+        for line in contents.lines() {
+            let Some((game, name, rating)) = parse_line(line) else {
+                continue;
+            };
+            ratings
+                .ratings
+                .entry(game.to_owned())
+                .or_default()
+                .insert(name.to_owned(), rating);
+        }
+        Ok(Some(ratings))
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let mut contents = String::new();
+        for game in self.games() {
+            for (name, rating) in self.leaderboard(game) {
+                // writing to a string can't fail
+                writeln!(
+                    contents,
+                    "{game}\t{name}\t{:.1}\t{}\t{}\t{}",
+                    rating.elo, rating.wins, rating.draws, rating.losses
+                )
+                .unwrap();
+            }
+        }
+        FORMAT.write(&path()?, &contents)
+    }
+}
+
+/// Update the saved ratings with a finished game, which should already be in the stats file
+pub fn record(record: &Record) -> io::Result<()> {
+    let ratings = match Ratings::load_file()? {
+        Some(mut ratings) => {
+            ratings.add(record);
+            ratings
+        }
+        // the stats file has every game, this one included
+        None => Ratings::from_records(&stats::load()?),
+    };
+    ratings.save()
+}
+
+fn parse_line(line: &str) -> Option<(&str, &str, Rating)> {
+    let mut fields = line.split('\t');
+    let game = fields.next()?;
+    let name = fields.next()?;
+    let rating = Rating {
+        elo: fields.next()?.parse().ok()?,
+        wins: fields.next()?.parse().ok()?,
+        draws: fields.next()?.parse().ok()?,
+        losses: fields.next()?.parse().ok()?,
+    };
+    Some((game, name, rating))
+}
+
+fn path() -> io::Result<PathBuf> {
+    dirs::data_file("ratings.tsv")
+}
@@ -0,0 +1,69 @@
+//! Choosing a move by pressing on squares, with a click or the place key.
+//!
+//! Where pieces move, the first press picks a piece up and the second puts it down, and the
+//! squares it can go to are highlighted in between. Where pieces are only placed, one press is
+//! enough. Either way the game only has to say which squares each legal move uses.
+
+/// A move, as the squares it uses
+pub trait SquareMove: Copy {
+    /// Where the piece moves from, or `None` if it's placed from off the board
+    fn from(self) -> Option<usize>;
+    /// Where the piece ends up
+    fn to(self) -> usize;
+}
+
+/// Placing a piece, for games where that's all a move is
+impl SquareMove for usize {
+    fn from(self) -> Option<usize> {
+        None
+    }
+
+    fn to(self) -> usize {
+        self
+    }
+}
+
+/// The piece picked up by the first press, if any
+#[derive(Default)]
+pub struct Selection {
+    selected: Option<usize>,
+}
+
+impl Selection {
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Where the selected piece can go
+    pub fn targets<M: SquareMove>(&self, moves: &[M]) -> Vec<usize> {
+        let Some(selected) = self.selected else {
+            return vec![];
+        };
+        moves
+            .iter()
+            .filter(|mv| mv.from() == Some(selected))
+            .map(|mv| mv.to())
+            .collect()
+    }
+
+    /// Press on `square`, returning the move to make if that finishes one. Otherwise this picks
+    /// up the piece there if it can move, or puts back the one already selected.
+    pub fn press<M: SquareMove>(&mut self, square: usize, moves: &[M]) -> Option<M> {
+        let selected = self.selected.take();
+        let finished = moves
+            .iter()
+            .find(|mv| mv.to() == square && (mv.from().is_none() || mv.from() == selected));
+        if let Some(mv) = finished {
+            return Some(*mv);
+        }
+        if selected != Some(square) && moves.iter().any(|mv| mv.from() == Some(square)) {
+            self.selected = Some(square);
+        }
+        None
+    }
+
+    /// Put back the selected piece
+    pub fn cancel(&mut self) {
+        self.selected = None;
+    }
+}
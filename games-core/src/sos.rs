@@ -0,0 +1,249 @@
+//! The rules of SOS, the pencil game: players take turns writing an S or an O in an empty
+//! square, scoring a point for each S-O-S they complete in a line, across, down or diagonally.
+//! Completing one earns another turn. Once the grid is full, the higher score wins.
+//!
+//! The grid can be any size from `MIN_SIZE` to `MAX_SIZE` each way. A square is named like
+//! tic-tac-toe's, its column's letter and then its row, and a move is the letter in front, like
+//! `Sb2`.
+use std::cmp::Ordering;
+
+use crate::{
+    bot::{GameResult, GameState},
+    player::Seat,
+    tic_tac_toe::board::{Player, O, X},
+};
+
+/// The narrowest or shortest grid, the smallest that fits an S-O-S
+pub const MIN_SIZE: usize = 3;
+
+/// The widest or tallest grid, wide enough for a good long game and still narrow enough to fit
+pub const MAX_SIZE: usize = 12;
+
+/// The directions a line can go in from its middle, each with its opposite left out
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Letter {
+    S,
+    O,
+}
+
+impl Letter {
+    pub const ALL: [Self; 2] = [Self::S, Self::O];
+
+    pub fn name(self) -> char {
+        match self {
+            Self::S => 'S',
+            Self::O => 'O',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|letter| letter.name() == c.to_ascii_uppercase())
+    }
+
+    /// The other letter
+    pub fn next(self) -> Self {
+        match self {
+            Self::S => Self::O,
+            Self::O => Self::S,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Move {
+    pub pos: usize,
+    pub letter: Letter,
+}
+
+/// A grid size like `7x5`, columns first, checked against `MIN_SIZE` and `MAX_SIZE`
+pub fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let (width, height) = value
+        .split_once('x')
+        .and_then(|(width, height)| Some((width.trim().parse().ok()?, height.trim().parse().ok()?)))
+        .ok_or_else(|| format!("expected a size like \"5x5\", found \"{value}\""))?;
+    let fits = |size| (MIN_SIZE..=MAX_SIZE).contains(&size);
+    if !fits(width) || !fits(height) {
+        return Err(format!(
+            "the grid has to be from {MIN_SIZE} to {MAX_SIZE} squares each way, not {value}"
+        ));
+    }
+    Ok((width, height))
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    width: usize,
+    height: usize,
+    cells: Vec<Option<Letter>>,
+    /// Each S-O-S made so far, its squares from one end to the other, and who made it
+    made: Vec<([usize; 3], Player)>,
+    scores: [usize; Player::COUNT],
+    to_move: Player,
+}
+
+impl Position {
+    /// An empty grid `width` squares across and `height` down, with X to move first
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![None; width * height],
+            made: vec![],
+            scores: [0; Player::COUNT],
+            to_move: X,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, pos: usize) -> Option<Letter> {
+        self.cells[pos]
+    }
+
+    /// Each S-O-S made so far, its squares from one end to the other, and who made it
+    pub fn made(&self) -> &[([usize; 3], Player)] {
+        &self.made
+    }
+
+    /// How many S-O-Ss `player` has made
+    pub fn score(&self, player: Player) -> usize {
+        self.scores[player.index()]
+    }
+
+    /// The square `steps` along `direction` from `pos`, if it's on the grid
+    fn step(&self, pos: usize, (dx, dy): (isize, isize), steps: isize) -> Option<usize> {
+        let x = (pos % self.width).checked_add_signed(dx * steps)?;
+        let y = (pos / self.width).checked_add_signed(dy * steps)?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    /// The S-O-Ss that `mv` would complete
+    fn completes(&self, mv: Move) -> Vec<[usize; 3]> {
+        let is =
+            |pos: Option<usize>, letter| pos.is_some_and(|pos| self.cells[pos] == Some(letter));
+        let pos = mv.pos;
+        match mv.letter {
+            // the middle, with an S either side
+            Letter::O => DIRECTIONS
+                .into_iter()
+                .filter_map(|direction| {
+                    let before = self.step(pos, direction, -1);
+                    let after = self.step(pos, direction, 1);
+                    (is(before, Letter::S) && is(after, Letter::S))
+                        .then(|| [before.unwrap(), pos, after.unwrap()])
+                })
+                .collect(),
+            // an end, with the rest of the line going either way from it
+            Letter::S => DIRECTIONS
+                .into_iter()
+                .flat_map(|direction| [(direction, 1), (direction, -1)])
+                .filter_map(|(direction, way)| {
+                    let middle = self.step(pos, direction, way);
+                    let end = self.step(pos, direction, 2 * way);
+                    (is(middle, Letter::O) && is(end, Letter::S))
+                        .then(|| [pos, middle.unwrap(), end.unwrap()])
+                })
+                .collect(),
+        }
+    }
+
+    /// The name of the square at `pos`, like `b2`
+    pub fn square_name(&self, pos: usize) -> String {
+        let column = (b'a' + (pos % self.width) as u8) as char;
+        format!("{column}{}", pos / self.width + 1)
+    }
+
+    /// The inverse of `square_name`. `None` for a square off the grid.
+    pub fn parse_square(&self, name: &str) -> Option<usize> {
+        let mut chars = name.chars();
+        let column = (chars.next()? as usize).checked_sub('a' as usize)?;
+        let row = chars.as_str().parse::<usize>().ok()?.checked_sub(1)?;
+        (column < self.width && row < self.height).then_some(row * self.width + column)
+    }
+}
+
+impl GameState for Position {
+    type Move = Move;
+    type Player = Player;
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        (0..self.cells.len())
+            .filter(|&pos| self.cells[pos].is_none())
+            .flat_map(|pos| Letter::ALL.map(|letter| Move { pos, letter }))
+            .collect()
+    }
+
+    fn apply(&mut self, mv: Move) {
+        let made = self.completes(mv);
+        self.cells[mv.pos] = Some(mv.letter);
+        self.scores[self.to_move.index()] += made.len();
+        let player = self.to_move;
+        self.made
+            .extend(made.iter().map(|&squares| (squares, player)));
+        // making one earns another turn
+        if made.is_empty() {
+            self.to_move = self.to_move.other();
+        }
+    }
+
+    fn result(&self) -> Option<GameResult<Player>> {
+        if self.cells.iter().any(Option::is_none) {
+            return None;
+        }
+        let [x, o] = self.scores;
+        Some(match x.cmp(&o) {
+            Ordering::Greater => GameResult::Winner(X),
+            Ordering::Less => GameResult::Winner(O),
+            Ordering::Equal => GameResult::Draw,
+        })
+    }
+
+    fn move_name(&self, mv: Move) -> String {
+        format!("{}{}", mv.letter.name(), self.square_name(mv.pos))
+    }
+
+    fn parse_move(&self, name: &str) -> Option<Move> {
+        let mut chars = name.chars();
+        let letter = Letter::from_char(chars.next()?)?;
+        let mv = Move {
+            pos: self.parse_square(chars.as_str())?,
+            letter,
+        };
+        self.legal_moves().contains(&mv).then_some(mv)
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl crate::testing::Invariants for Position {
+    fn check(&self) -> Result<(), String> {
+        if self.scores.iter().sum::<usize>() != self.made.len() {
+            return Err(format!(
+                "scores of {:?} for {} S-O-Ss",
+                self.scores,
+                self.made.len()
+            ));
+        }
+        for &(squares, _) in &self.made {
+            let letters = squares.map(|pos| self.cells[pos]);
+            if letters != [Some(Letter::S), Some(Letter::O), Some(Letter::S)] {
+                let names = squares.map(|pos| self.square_name(pos));
+                return Err(format!("{} isn't an S-O-S", names.join(" ")));
+            }
+        }
+        Ok(())
+    }
+}
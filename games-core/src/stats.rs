@@ -0,0 +1,158 @@
+//! A record of every finished game, kept in `stats.tsv` in the data directory.
+//!
+//! After the version line (see [`migrate`]), each line is one game, with tab-separated fields:
+//!
+//! ```text
+//! <finished, unix seconds>  <game>  <players, comma separated>  <winner, or "draw">  <duration, seconds>  <moves>  <setup>
+//! ```
+//!
+//! The moves are in the game's notation, separated by spaces. Puzzles, and games recorded before
+//! moves were, have none. The setup is anything on the board before the first move, like a
+//! handicap, and is usually empty.
+use std::{
+    io,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    date, dirs,
+    migrate::{self, Format},
+};
+
+const FORMAT: Format = Format {
+    name: "stats",
+    migrations: &[migrate::add_version_line],
+};
+
+pub struct Record {
+    /// When the game finished
+    pub finished: SystemTime,
+    /// Name of the game, e.g. "tic-tac-toe"
+    pub game: String,
+    /// Names of the players, in turn order
+    pub players: Vec<String>,
+    /// `None` for a draw
+    pub winner: Option<String>,
+    pub duration: Duration,
+    /// Every move, in the game's notation
+    pub moves: Vec<String>,
+    /// What was on the board before the first move, in the game's notation. Empty for a normal
+    /// start.
+    pub setup: String,
+}
+
+impl Record {
+    pub fn new(
+        game: &str,
+        players: Vec<String>,
+        winner: Option<String>,
+        duration: Duration,
+        moves: Vec<String>,
+    ) -> Self {
+        Self {
+            finished: SystemTime::now(),
+            game: game.to_owned(),
+            players,
+            winner,
+            duration,
+            moves,
+            setup: String::new(),
+        }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let finished = UNIX_EPOCH + Duration::from_secs(fields.next()?.parse().ok()?);
+        let game = fields.next()?.to_owned();
+        let players = fields
+            .next()?
+            .split(',')
+            .filter(|name| !name.is_empty())
+            .map(str::to_owned)
+            .collect();
+        let winner = match fields.next()? {
+            "draw" => None,
+            winner => Some(winner.to_owned()),
+        };
+        let duration = Duration::from_secs_f64(fields.next()?.parse().ok()?);
+        let moves = fields
+            .next()
+            .unwrap_or("")
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect();
+        let setup = fields.next().unwrap_or("").to_owned();
+        Some(Self {
+            finished,
+            game,
+            players,
+            winner,
+            duration,
+            moves,
+            setup,
+        })
+    }
+}
+
+/// Append a finished game to the stats file.
+pub fn record(record: &Record) -> io::Result<()> {
+    let finished = record
+        .finished
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let line = format!(
+        "{finished}\t{}\t{}\t{}\t{:.1}\t{}\t{}",
+        record.game,
+        record.players.join(","),
+        record.winner.as_deref().unwrap_or("draw"),
+        record.duration.as_secs_f64(),
+        record.moves.join(" "),
+        record.setup
+    );
+    FORMAT.append(&path()?, &line)
+}
+
+/// All recorded games, oldest first. Lines that can't be parsed are skipped.
+pub fn load() -> io::Result<Vec<Record>> {
+    let contents = FORMAT.read(&path()?)?.unwrap_or_default();
+    Ok(contents.lines().filter_map(Record::parse).collect())
+}
+
+/// How many days in a row `game` has been played, up to today.
+///
+/// A streak that reached yesterday still counts, since there's still time to play today.
+pub fn daily_streak(records: &[Record], game: &str) -> usize {
+    streak(records, |record| record.game == game)
+}
+
+/// How many days in a row, up to today, have a game that `counts`. Like `daily_streak`, one that
+/// reached yesterday still counts.
+pub fn streak(records: &[Record], counts: impl Fn(&Record) -> bool) -> usize {
+    let mut days: Vec<_> = records
+        .iter()
+        .filter(|record| counts(record))
+        .map(|record| date::day_of(record.finished))
+        .collect();
+    days.sort_unstable();
+    days.dedup();
+    let today = date::today();
+    let mut day = match days.last() {
+        Some(&last) if last == today || last + 1 == today => last,
+        _ => return 0,
+    };
+    let mut streak = 0;
+    for &played in days.iter().rev() {
+        if played != day {
+            break;
+        }
+        streak += 1;
+        day = day.wrapping_sub(1);
+    }
+    streak
+}
+
+fn path() -> io::Result<PathBuf> {
+    dirs::data_file("stats.tsv")
+}
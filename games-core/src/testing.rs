@@ -0,0 +1,66 @@
+//! Random games, for checking a game's rules hold whatever is played.
+//!
+//! Only built with the `test-util` feature. `./games check <game>` plays random games and checks
+//! every position on the way, with the checks that apply to any game and the game's own
+//! `Invariants`.
+use crate::{bot::GameState, rng::Rng};
+
+/// Things that should be true of every position a game can reach
+pub trait Invariants: GameState {
+    /// Say what's wrong with this position, if anything
+    fn check(&self) -> Result<(), String>;
+}
+
+/// The moves of a random game from `start`, to the end
+pub fn random_playout<G: GameState>(start: &G, rng: &mut Rng) -> Vec<G::Move> {
+    let mut state = start.clone();
+    let mut moves = vec![];
+    loop {
+        let legal = state.legal_moves();
+        if legal.is_empty() {
+            return moves;
+        }
+        let mv = legal[rng.below(legal.len())];
+        state.apply(mv);
+        moves.push(mv);
+    }
+}
+
+/// Check every position in `playouts` random games from `start`, which are the same for the same
+/// `seed`. Returns the first problem found, with the moves that led to it.
+pub fn check_playouts<G: Invariants>(start: &G, playouts: usize, seed: u64) -> Result<(), String> {
+    let mut rng = Rng::new(seed);
+    for _ in 0..playouts {
+        let mut state = start.clone();
+        let mut names = vec![];
+        for mv in random_playout(start, &mut rng)
+            .into_iter()
+            .map(Some)
+            .chain([None])
+        {
+            if let Err(msg) = check_rules(&state).and_then(|()| state.check()) {
+                return Err(format!("after \"{}\": {msg}", names.join(" ")));
+            }
+            if let Some(mv) = mv {
+                names.push(state.move_name(mv));
+                state.apply(mv);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The checks that apply to any game
+fn check_rules<G: GameState>(state: &G) -> Result<(), String> {
+    let moves = state.legal_moves();
+    if moves.is_empty() != state.result().is_some() {
+        return Err("the game should be over exactly when there are no legal moves".to_owned());
+    }
+    for mv in moves {
+        let name = state.move_name(mv);
+        if state.parse_move(&name) != Some(mv) {
+            return Err(format!("move {name} doesn't parse back to itself"));
+        }
+    }
+    Ok(())
+}
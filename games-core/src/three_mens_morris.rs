@@ -0,0 +1,231 @@
+//! The rules of Three Men's Morris: tic-tac-toe where each player only has three pieces. Once
+//! they're all on the board, a turn is moving one of your own to an empty neighbouring square,
+//! until someone has three in a line.
+//!
+//! Squares are neighbours when they're next to each other on one of the board's lines, so the
+//! center is next to every other square and each corner is next to the center and the two edges
+//! beside it. A player with nowhere to move loses, and a game still going after `MAX_MOVES`
+//! moves is a draw, which keeps the computer's searches finite.
+//!
+//! Placing a piece is written like tic-tac-toe's `b2`, and moving one like `a1-b2`.
+use crate::{
+    bot::{GameResult, GameState},
+    player::Seat,
+    select::SquareMove,
+    tic_tac_toe::board::{parse_square, square_name, Player, Win, SYMMETRIES, X},
+};
+
+/// How many pieces each player has
+pub const PIECES: usize = 3;
+
+/// Moves after the pieces are placed before the game is called a draw
+pub const MAX_MOVES: u8 = 30;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Move {
+    /// Where the piece comes from, or `None` while it's being placed
+    pub from: Option<usize>,
+    pub to: usize,
+}
+
+impl SquareMove for Move {
+    fn from(self) -> Option<usize> {
+        self.from
+    }
+
+    fn to(self) -> usize {
+        self.to
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// Each square's piece, if it has one
+    squares: [Option<Player>; 9],
+    to_move: Player,
+    /// How many pieces have been moved, rather than placed
+    moved: u8,
+}
+
+impl Position {
+    /// The empty board, with X to place first
+    pub fn new() -> Self {
+        Self {
+            squares: [None; 9],
+            to_move: X,
+            moved: 0,
+        }
+    }
+
+    pub fn get(&self, pos: usize) -> Option<Player> {
+        self.squares[pos]
+    }
+
+    /// How many of `player`'s pieces are still to be placed
+    pub fn in_hand(&self, player: Player) -> usize {
+        PIECES
+            - self
+                .squares
+                .iter()
+                .filter(|&&sq| sq == Some(player))
+                .count()
+    }
+
+    /// How many moves are left before the game is a draw, once the pieces are placed
+    pub fn moves_left(&self) -> u8 {
+        MAX_MOVES - self.moved
+    }
+
+    /// The line someone has made, and whose it is
+    pub fn line(&self) -> Option<(Win, Player)> {
+        Win::ALL.into_iter().find_map(|win| {
+            let [a, b, c] = win.squares().map(|pos| self.squares[pos]);
+            let player = a?;
+            (b == Some(player) && c == Some(player)).then_some((win, player))
+        })
+    }
+
+    /// Every move for the player to move, whether or not someone has already won
+    fn moves(&self) -> Vec<Move> {
+        let empty = (0..9).filter(|&pos| self.squares[pos].is_none());
+        if self.in_hand(self.to_move) > 0 {
+            return empty.map(|to| Move { from: None, to }).collect();
+        }
+        (0..9)
+            .filter(|&from| self.squares[from] == Some(self.to_move))
+            .flat_map(|from| {
+                neighbours(from)
+                    .filter(|&to| self.squares[to].is_none())
+                    .map(move |to| Move {
+                        from: Some(from),
+                        to,
+                    })
+            })
+            .collect()
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The squares next to `pos` along one of the lines
+pub fn neighbours(pos: usize) -> impl Iterator<Item = usize> {
+    let mut squares: Vec<_> = Win::ALL
+        .into_iter()
+        .flat_map(|win| {
+            let [a, b, c] = win.squares();
+            match pos {
+                _ if pos == a || pos == c => vec![b],
+                _ if pos == b => vec![a, c],
+                _ => vec![],
+            }
+        })
+        .collect();
+    squares.sort_unstable();
+    squares.dedup();
+    squares.into_iter()
+}
+
+impl GameState for Position {
+    type Move = Move;
+    type Player = Player;
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        if self.result().is_some() {
+            return vec![];
+        }
+        self.moves()
+    }
+
+    fn apply(&mut self, mv: Move) {
+        if let Some(from) = mv.from {
+            self.squares[from] = None;
+            self.moved += 1;
+        }
+        self.squares[mv.to] = Some(self.to_move);
+        self.to_move = self.to_move.other();
+    }
+
+    fn result(&self) -> Option<GameResult<Player>> {
+        if let Some((_, player)) = self.line() {
+            Some(GameResult::Winner(player))
+        } else if self.moves().is_empty() {
+            Some(GameResult::Winner(self.to_move.other()))
+        } else if self.moved >= MAX_MOVES {
+            Some(GameResult::Draw)
+        } else {
+            None
+        }
+    }
+
+    fn move_name(&self, mv: Move) -> String {
+        match mv.from {
+            Some(from) => format!("{}-{}", square_name(from), square_name(mv.to)),
+            None => square_name(mv.to),
+        }
+    }
+
+    fn parse_move(&self, name: &str) -> Option<Move> {
+        let mv = match name.split_once('-') {
+            Some((from, to)) => Move {
+                from: Some(parse_square(from)?),
+                to: parse_square(to)?,
+            },
+            None => Move {
+                from: None,
+                to: parse_square(name)?,
+            },
+        };
+        self.legal_moves().contains(&mv).then_some(mv)
+    }
+
+    /// The smallest of the board's rotations and reflections, which the lines and neighbours
+    /// are the same in
+    fn canonical(&self) -> Self {
+        let squares = SYMMETRIES
+            .iter()
+            .map(|from| from.map(|pos| self.squares[pos]))
+            .min_by_key(|squares| squares.map(|sq| sq.map(Player::index)))
+            .unwrap();
+        Self {
+            squares,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl crate::testing::Invariants for Position {
+    fn check(&self) -> Result<(), String> {
+        use crate::tic_tac_toe::board::O;
+
+        for player in [X, O] {
+            if self
+                .squares
+                .iter()
+                .filter(|&&sq| sq == Some(player))
+                .count()
+                > PIECES
+            {
+                return Err(format!("{player} has more than {PIECES} pieces"));
+            }
+        }
+        // X places first, so O never has more on the board
+        let placed = |player| PIECES - self.in_hand(player);
+        let expected = if placed(X) > placed(O) { O } else { X };
+        if self.in_hand(X) + self.in_hand(O) > 0 && self.to_move != expected {
+            return Err(format!("{} to move while placing", self.to_move));
+        }
+        if self.moved > 0 && self.in_hand(X) + self.in_hand(O) > 0 {
+            return Err("a piece moved before they were all placed".to_owned());
+        }
+        Ok(())
+    }
+}
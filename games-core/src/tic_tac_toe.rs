@@ -0,0 +1,5 @@
+//! The rules of tic-tac-toe, how the computer judges a position, and the daily puzzles.
+pub mod board;
+pub mod eval;
+pub mod image;
+pub mod puzzle;
@@ -0,0 +1,658 @@
+//! The rules of tic-tac-toe, independent of how the game is drawn.
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    bot::{GameResult, GameState},
+    locale::tr,
+    player::Seat,
+    rng::Rng,
+};
+
+#[derive(Clone, Copy)]
+pub enum Win {
+    LeftCol,
+    MidCol,
+    RightCol,
+    TopRow,
+    MidRow,
+    BottomRow,
+    TLBRDiag,
+    TRBLDiag,
+}
+use Win::*;
+
+impl Win {
+    pub const ALL: [Self; 8] = [
+        LeftCol, MidCol, RightCol, TopRow, MidRow, BottomRow, TLBRDiag, TRBLDiag,
+    ];
+
+    /// The squares making up the line
+    pub fn squares(&self) -> [usize; 3] {
+        match self {
+            LeftCol => [0, 3, 6],
+            MidCol => [1, 4, 7],
+            RightCol => [2, 5, 8],
+            TopRow => [0, 1, 2],
+            MidRow => [3, 4, 5],
+            BottomRow => [6, 7, 8],
+            TLBRDiag => [0, 4, 8],
+            TRBLDiag => [2, 4, 6],
+        }
+    }
+}
+
+/// Where each player has played, as bitmasks with bit `idx` set for square `idx`
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Board {
+    x: u16,
+    o: u16,
+    /// Squares nobody can play in, from a handicap
+    blocked: u16,
+    /// Marks placed before the game started, from a handicap. They don't count towards whose
+    /// turn it is.
+    given: u16,
+}
+
+/// Masks for every line, in the order `check_win` looks for them: center lines first
+const LINES: [(Win, u16); 8] = [
+    (MidCol, 0b010_010_010),
+    (MidRow, 0b000_111_000),
+    (TLBRDiag, 0b100_010_001),
+    (TRBLDiag, 0b001_010_100),
+    (TopRow, 0b000_000_111),
+    (LeftCol, 0b001_001_001),
+    (BottomRow, 0b111_000_000),
+    (RightCol, 0b100_100_100),
+];
+const FULL: u16 = 0b111_111_111;
+
+/// The 8 ways of rotating and reflecting the board, as which square each square's contents come
+/// from
+pub(crate) const SYMMETRIES: [[usize; 9]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8],
+    [6, 3, 0, 7, 4, 1, 8, 5, 2],
+    [8, 7, 6, 5, 4, 3, 2, 1, 0],
+    [2, 5, 8, 1, 4, 7, 0, 3, 6],
+    [2, 1, 0, 5, 4, 3, 8, 7, 6],
+    [6, 7, 8, 3, 4, 5, 0, 1, 2],
+    [0, 3, 6, 1, 4, 7, 2, 5, 8],
+    [8, 5, 2, 7, 4, 1, 6, 3, 0],
+];
+
+impl Board {
+    pub fn new() -> Self {
+        Self {
+            x: 0,
+            o: 0,
+            blocked: 0,
+            given: 0,
+        }
+    }
+
+    /// The board a game with `handicap` starts from
+    pub fn with_handicap(handicap: &Handicap) -> Result<Self, HandicapError> {
+        let mut board = Self::new();
+        let groups = [
+            (&handicap.x, Some(X)),
+            (&handicap.o, Some(O)),
+            (&handicap.blocked, None),
+        ];
+        for (squares, player) in groups {
+            for &pos in squares {
+                if pos >= 9 {
+                    return Err(HandicapError::OutOfRange);
+                }
+                if (board.x | board.o | board.blocked) & 1 << pos != 0 {
+                    return Err(HandicapError::Overlap(pos));
+                }
+                match player {
+                    Some(player) => {
+                        board.set(pos, Some(player));
+                        board.given |= 1 << pos;
+                    }
+                    None => board.blocked |= 1 << pos,
+                }
+            }
+        }
+        if board.check_win().is_some() {
+            return Err(HandicapError::Won);
+        }
+        if board.is_full() {
+            return Err(HandicapError::Full);
+        }
+        Ok(board)
+    }
+
+    /// Whether nobody can play at `idx`
+    pub fn is_blocked(&self, idx: usize) -> bool {
+        self.blocked & 1 << idx != 0
+    }
+
+    /// The same board rotated or reflected, with square `idx` taken from square `from[idx]`
+    pub fn permuted(&self, from: &[usize; 9]) -> Self {
+        let permute = |mask: u16| {
+            (0..9)
+                .filter(|&idx| mask & 1 << from[idx] != 0)
+                .fold(0, |permuted, idx| permuted | 1 << idx)
+        };
+        Self {
+            x: permute(self.x),
+            o: permute(self.o),
+            blocked: permute(self.blocked),
+            given: permute(self.given),
+        }
+    }
+
+    /// Every square that's the same as `pos` after a rotation or reflection that leaves the board
+    /// as it is, `pos` included
+    pub fn equivalent_squares(&self, pos: usize) -> Vec<usize> {
+        let mut squares: Vec<_> = SYMMETRIES
+            .iter()
+            .filter(|from| self.permuted(from) == *self)
+            .map(|from| from[pos])
+            .collect();
+        squares.sort_unstable();
+        squares.dedup();
+        squares
+    }
+
+    pub fn get(&self, idx: usize) -> Square {
+        if self.x & 1 << idx != 0 {
+            Some(X)
+        } else if self.o & 1 << idx != 0 {
+            Some(O)
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, idx: usize, square: Square) {
+        self.x &= !(1 << idx);
+        self.o &= !(1 << idx);
+        self.given &= !(1 << idx);
+        match square {
+            Some(X) => self.x |= 1 << idx,
+            Some(O) => self.o |= 1 << idx,
+            None => (),
+        }
+    }
+
+    /// Every square, in index order
+    pub fn squares(&self) -> impl Iterator<Item = Square> + '_ {
+        (0..9).map(|idx| self.get(idx))
+    }
+
+    pub fn check_win(&self) -> Option<(Win, Player)> {
+        for (win, mask) in LINES {
+            if self.x & mask == mask {
+                return Some((win, X));
+            }
+            if self.o & mask == mask {
+                return Some((win, O));
+            }
+        }
+        None
+    }
+
+    /// Like `check_win`, but only looks at lines through `pos`, so is cheaper when `pos` was the
+    /// last move and the game wasn't already won
+    pub fn check_win_from(&self, pos: usize) -> Option<(Win, Player)> {
+        let player = self.get(pos)?;
+        let marks = match player {
+            X => self.x,
+            O => self.o,
+        };
+        LINES
+            .into_iter()
+            .find(|(_, mask)| mask & 1 << pos != 0 && marks & mask == *mask)
+            .map(|(win, _)| (win, player))
+    }
+
+    /// Indices of the squares that can still be played in
+    pub fn empty_squares(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..9).filter(|idx| (self.x | self.o | self.blocked) & 1 << idx == 0)
+    }
+
+    /// The number of marks placed during the game, not counting any from a handicap
+    pub fn count(&self) -> usize {
+        ((self.x | self.o) & !self.given).count_ones() as usize
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.x | self.o | self.blocked == FULL
+    }
+
+    /// Place `player`'s mark at `pos`, if that's a legal move
+    pub fn apply_move(&mut self, pos: usize, player: Player) -> Result<(), MoveError> {
+        if pos >= 9 {
+            return Err(MoveError::OutOfRange);
+        }
+        if self.check_win().is_some() || self.is_full() {
+            return Err(MoveError::GameOver);
+        }
+        if self.get(pos).is_some() {
+            return Err(MoveError::Occupied);
+        }
+        if self.is_blocked(pos) {
+            return Err(MoveError::Blocked);
+        }
+        // whoever went first, nobody can get two marks ahead
+        let (mine, theirs) = match player {
+            X => (self.x & !self.given, self.o & !self.given),
+            O => (self.o & !self.given, self.x & !self.given),
+        };
+        if mine.count_ones() > theirs.count_ones() {
+            return Err(MoveError::WrongTurn);
+        }
+        self.set(pos, Some(player));
+        Ok(())
+    }
+}
+
+/// Why a mark can't be placed
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MoveError {
+    /// There's no square with that index
+    OutOfRange,
+    Occupied,
+    /// A handicap stops anyone playing there
+    Blocked,
+    /// Someone has already won, or the board is full
+    GameOver,
+    /// The other player has to move first
+    WrongTurn,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(tr(match self {
+            Self::OutOfRange => "there's no such square",
+            Self::Occupied => "that square is taken",
+            Self::Blocked => "that square is blocked",
+            Self::GameOver => "the game is over",
+            Self::WrongTurn => "it's the other player's turn",
+        }))
+    }
+}
+
+/// The fewest squares `Handicap::with_random_blocks` blocks
+pub const MIN_RANDOM_BLOCKS: usize = 1;
+
+/// The most squares `Handicap::with_random_blocks` blocks
+pub const MAX_RANDOM_BLOCKS: usize = 3;
+
+/// Marks and blocked squares on the board before a game starts, to even out a game between
+/// players of different strengths. Written like `x:b2 blocked:a1,c3`.
+#[derive(Clone, Default, PartialEq)]
+pub struct Handicap {
+    pub x: Vec<usize>,
+    pub o: Vec<usize>,
+    pub blocked: Vec<usize>,
+}
+
+impl Handicap {
+    pub fn from_str(value: &str) -> Result<Self, String> {
+        let mut handicap = Self::default();
+        for group in value.split_whitespace() {
+            let Some((what, squares)) = group.split_once(':') else {
+                return Err(format!("expected something like x:b2, found \"{group}\""));
+            };
+            let list = match what {
+                "x" => &mut handicap.x,
+                "o" => &mut handicap.o,
+                "blocked" => &mut handicap.blocked,
+                _ => return Err(format!("expected x, o or blocked, found \"{what}\"")),
+            };
+            for name in squares.split(',').filter(|name| !name.is_empty()) {
+                list.push(parse_square(name).ok_or_else(|| format!("{name} isn't a square"))?);
+            }
+        }
+        Ok(handicap)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty() && self.o.is_empty() && self.blocked.is_empty()
+    }
+
+    /// This handicap with `MIN_RANDOM_BLOCKS` to `MAX_RANDOM_BLOCKS` more squares blocked,
+    /// picked by `rng` from the empty ones. There's always a line left without a blocked square
+    /// in it, and an empty square to play in, so fewer are blocked if that's all that fits.
+    pub fn with_random_blocks(&self, rng: &mut Rng) -> Self {
+        let mut handicap = self.clone();
+        let count = rng.below(MAX_RANDOM_BLOCKS - MIN_RANDOM_BLOCKS + 1) + MIN_RANDOM_BLOCKS;
+        for _ in 0..count {
+            let taken: Vec<usize> = [&handicap.x, &handicap.o, &handicap.blocked]
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+            let blocked = handicap.blocked.iter().fold(0, |mask, pos| mask | 1 << pos);
+            let choices: Vec<usize> = (0..9)
+                .filter(|pos| !taken.contains(pos))
+                .filter(|pos| {
+                    let blocked = blocked | 1 << pos;
+                    LINES.iter().any(|&(_, line)| line & blocked == 0)
+                })
+                .collect();
+            if choices.is_empty() || taken.len() + 1 >= 9 {
+                break;
+            }
+            handicap.blocked.push(choices[rng.below(choices.len())]);
+        }
+        handicap.blocked.sort_unstable();
+        handicap
+    }
+}
+
+/// The inverse of `Handicap::from_str`
+impl fmt::Display for Handicap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let groups = [("x", &self.x), ("o", &self.o), ("blocked", &self.blocked)];
+        let mut first = true;
+        for (what, squares) in groups {
+            if squares.is_empty() {
+                continue;
+            }
+            if !first {
+                f.write_str(" ")?;
+            }
+            first = false;
+            let names: Vec<_> = squares.iter().map(|&pos| square_name(pos)).collect();
+            write!(f, "{what}:{}", names.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a handicap can't be used
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HandicapError {
+    /// There's no square with that index
+    OutOfRange,
+    /// The same square is used twice
+    Overlap(usize),
+    /// The handicap already has a line
+    Won,
+    /// There's nowhere left to play
+    Full,
+}
+
+impl fmt::Display for HandicapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfRange => f.write_str("there's no such square"),
+            Self::Overlap(pos) => write!(f, "{} is used more than once", square_name(*pos)),
+            Self::Won => f.write_str("it already has a line"),
+            Self::Full => f.write_str("it leaves nowhere to play"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Player {
+    X,
+    O,
+}
+pub use Player::*;
+
+impl Player {
+    pub fn other(self) -> Self {
+        self.next()
+    }
+
+    pub fn toggle(&mut self) {
+        *self = self.next();
+    }
+}
+
+/// Three lines like `X.O`, with `.` for empty squares and `#` for blocked ones, and no newline at
+/// the end
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..3 {
+            if row > 0 {
+                f.write_str("\n")?;
+            }
+            for col in 0..3 {
+                match self.get(row * 3 + col) {
+                    Some(player) => write!(f, "{player}")?,
+                    None if self.is_blocked(row * 3 + col) => f.write_str("#")?,
+                    None => f.write_str(".")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Seat for Player {
+    const COUNT: usize = 2;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        [X, O][index]
+    }
+
+    fn glyph(self) -> char {
+        match self {
+            X => 'X',
+            O => 'O',
+        }
+    }
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.glyph())
+    }
+}
+
+pub type Square = Option<Player>;
+
+/// A board and whose turn it is, which is everything needed to carry on the game
+#[derive(Clone)]
+pub struct Position {
+    pub board: Board,
+    pub to_move: Player,
+    /// The square the last mark went in, so only its lines need checking for a win. `None` when
+    /// it isn't known, and then every line is checked.
+    last_move: Option<usize>,
+}
+
+impl Position {
+    /// The start of a game
+    pub fn new() -> Self {
+        Self::from_board(Board::new(), X)
+    }
+
+    /// `board` with `to_move` to play next
+    pub fn from_board(board: Board, to_move: Player) -> Self {
+        Self {
+            board,
+            to_move,
+            last_move: None,
+        }
+    }
+
+    fn check_win(&self) -> Option<(Win, Player)> {
+        match self.last_move {
+            Some(pos) => self.board.check_win_from(pos),
+            None => self.board.check_win(),
+        }
+    }
+}
+
+/// Positions are the same whichever move got them there
+impl PartialEq for Position {
+    fn eq(&self, other: &Self) -> bool {
+        self.board == other.board && self.to_move == other.to_move
+    }
+}
+
+impl Eq for Position {}
+
+impl Hash for Position {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.board.hash(state);
+        self.to_move.hash(state);
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The board on one line, with its rows separated by `/`, like `X.O/.X./...`
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.board.to_string().replace('\n', "/"))
+    }
+}
+
+impl GameState for Position {
+    /// The square to play in
+    type Move = usize;
+    type Player = Player;
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<usize> {
+        if self.check_win().is_some() {
+            return vec![];
+        }
+        self.board.empty_squares().collect()
+    }
+
+    fn apply(&mut self, pos: usize) {
+        self.board.set(pos, Some(self.to_move));
+        self.to_move.toggle();
+        self.last_move = Some(pos);
+    }
+
+    fn result(&self) -> Option<GameResult<Player>> {
+        if let Some((_, winner)) = self.check_win() {
+            Some(GameResult::Winner(winner))
+        } else if self.board.is_full() {
+            Some(GameResult::Draw)
+        } else {
+            None
+        }
+    }
+
+    fn move_name(&self, pos: usize) -> String {
+        square_name(pos)
+    }
+
+    fn parse_move(&self, name: &str) -> Option<usize> {
+        parse_square(name).filter(|pos| self.legal_moves().contains(pos))
+    }
+
+    /// The smallest of the board's rotations and reflections
+    fn canonical(&self) -> Self {
+        let board = SYMMETRIES
+            .iter()
+            .map(|from| self.board.permuted(from))
+            .min_by_key(|board| (board.x, board.o, board.blocked, board.given))
+            .unwrap();
+        Self::from_board(board, self.to_move)
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl crate::testing::Invariants for Position {
+    fn check(&self) -> Result<(), String> {
+        let winners: Vec<_> = LINES
+            .into_iter()
+            .filter_map(|(win, _)| {
+                let [a, b, c] = win.squares().map(|idx| self.board.get(idx));
+                (a.is_some() && a == b && b == c).then_some(a)
+            })
+            .collect();
+        if winners.windows(2).any(|pair| pair[0] != pair[1]) {
+            return Err("both players have a line".to_owned());
+        }
+        // marks from a handicap don't count
+        let given = self.board.given;
+        let (xs, os) = (
+            (self.board.x & !given).count_ones(),
+            (self.board.o & !given).count_ones(),
+        );
+        if xs.abs_diff(os) > 1 {
+            return Err(format!("X has {xs} marks and O has {os}"));
+        }
+        // the player to move can't be ahead, whoever went first
+        let (mine, theirs) = match self.to_move {
+            X => (xs, os),
+            O => (os, xs),
+        };
+        if mine > theirs {
+            return Err(format!("{} to move with more marks", self.to_move));
+        }
+        Ok(())
+    }
+}
+
+/// Parses a square name like `b2`: columns are `a` to `c` from the left, and rows `1` to `3`
+/// from the top.
+pub fn parse_square(name: &str) -> Option<usize> {
+    let mut chars = name.chars();
+    let col = match chars.next()?.to_ascii_lowercase() {
+        ch @ 'a'..='c' => ch as usize - 'a' as usize,
+        _ => return None,
+    };
+    let row = match chars.next()? {
+        ch @ '1'..='3' => ch as usize - '1' as usize,
+        _ => return None,
+    };
+    chars.next().is_none().then_some(row * 3 + col)
+}
+
+/// `squares` in words, with the ones that are the same by symmetry put together, like "any
+/// corner or b2" rather than "a1, c1, a3, c3 or b2"
+pub fn describe_squares(board: &Board, squares: &[usize]) -> String {
+    let or = tr(" or ");
+    let empty = (0..9)
+        .filter(|&pos| board.get(pos).is_none() && !board.is_blocked(pos))
+        .count();
+    if squares.len() > 1 && squares.len() == empty {
+        return tr("any square").to_owned();
+    }
+    let mut described = vec![];
+    let mut parts = vec![];
+    for &pos in squares {
+        if described.contains(&pos) {
+            continue;
+        }
+        let same: Vec<_> = board
+            .equivalent_squares(pos)
+            .into_iter()
+            .filter(|pos| squares.contains(pos))
+            .collect();
+        described.extend(&same);
+        parts.push(match &same[..] {
+            [0, 2, 6, 8] => tr("any corner").to_owned(),
+            [1, 3, 5, 7] => tr("any edge").to_owned(),
+            _ => {
+                let names: Vec<_> = same.iter().map(|&pos| square_name(pos)).collect();
+                names.join(or)
+            }
+        });
+    }
+    parts.join(or)
+}
+
+/// The inverse of `parse_square`
+pub fn square_name(idx: usize) -> String {
+    let col = (b'a' + (idx % 3) as u8) as char;
+    format!("{col}{}", idx / 3 + 1)
+}
@@ -0,0 +1,63 @@
+//! Heuristics for the `AlphaBeta` bot, chosen by name with the `evaluation` setting.
+use super::board::{Position, Win};
+use crate::bot::Evaluator;
+
+/// The evaluation called `name`
+pub fn feature(name: &str) -> Option<Box<dyn Evaluator<Position>>> {
+    match name {
+        "lines" => Some(Box::new(Lines)),
+        "center" => Some(Box::new(Center)),
+        "corners" => Some(Box::new(Corners)),
+        _ => None,
+    }
+}
+
+/// Lines that only one player has marked, which they could still complete
+struct Lines;
+
+impl Evaluator<Position> for Lines {
+    fn evaluate(&self, state: &Position) -> f64 {
+        /// What a line is worth by how many marks are in it: two in a row is a threat
+        const WORTH: [f64; 4] = [0., 1., 3., 0.];
+        let me = state.to_move;
+        Win::ALL
+            .iter()
+            .map(|line| {
+                let squares = line.squares().map(|idx| state.board.get(idx));
+                let mine = squares.iter().filter(|sq| **sq == Some(me)).count();
+                let theirs = squares.iter().filter(|sq| **sq == Some(me.other())).count();
+                match (mine, theirs) {
+                    (mine, 0) => WORTH[mine],
+                    (0, theirs) => -WORTH[theirs],
+                    _ => 0.,
+                }
+            })
+            .sum()
+    }
+}
+
+/// 1 for holding the center, which is in the most lines, and -1 if the opponent does
+struct Center;
+
+impl Evaluator<Position> for Center {
+    fn evaluate(&self, state: &Position) -> f64 {
+        match state.board.get(4) {
+            Some(player) if player == state.to_move => 1.,
+            Some(_) => -1.,
+            None => 0.,
+        }
+    }
+}
+
+/// How many more corners the player to move has than the opponent
+struct Corners;
+
+impl Evaluator<Position> for Corners {
+    fn evaluate(&self, state: &Position) -> f64 {
+        [0, 2, 6, 8]
+            .into_iter()
+            .filter_map(|idx| state.board.get(idx))
+            .map(|player| if player == state.to_move { 1. } else { -1. })
+            .sum()
+    }
+}
@@ -0,0 +1,122 @@
+//! Drawing a board as an SVG picture, to share a game outside the terminal.
+//!
+//! The picture is the board as it was drawn on screen, with crosses and noughts, blocked squares
+//! shaded, the winning line struck through, and a caption underneath.
+use core::fmt::Write;
+
+use super::board::{Board, Player, Win, O, X};
+
+/// How big each square is, in pixels
+const SQUARE: usize = 100;
+/// Room around the board, and for the caption under it
+const MARGIN: usize = 20;
+const CAPTION: usize = 40;
+
+/// Colors for X, O and the winning line
+const X_COLOR: &str = "#d0342c";
+const O_COLOR: &str = "#2a6fdb";
+const WIN_COLOR: &str = "#e8a317";
+
+/// `board` as an SVG document, with `win` struck through and `caption` underneath
+pub fn svg(board: &Board, win: Option<Win>, caption: &str) -> String {
+    let size = 3 * SQUARE;
+    let (width, height) = (size + 2 * MARGIN, size + 2 * MARGIN + CAPTION);
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    )
+    .unwrap();
+    writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+    for pos in 0..9 {
+        let (x, y) = corner(pos);
+        if board.is_blocked(pos) {
+            writeln!(
+                svg,
+                r##"<rect x="{x}" y="{y}" width="{SQUARE}" height="{SQUARE}" fill="#bbbbbb"/>"##
+            )
+            .unwrap();
+        }
+        if let Some(player) = board.get(pos) {
+            mark(&mut svg, pos, player);
+        }
+    }
+    // the grid, over the shading
+    for idx in 1..3 {
+        let at = MARGIN + idx * SQUARE;
+        let (start, end) = (MARGIN, MARGIN + size);
+        writeln!(
+            svg,
+            r#"<line x1="{at}" y1="{start}" x2="{at}" y2="{end}" stroke="black" stroke-width="4"/>"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<line x1="{start}" y1="{at}" x2="{end}" y2="{at}" stroke="black" stroke-width="4"/>"#
+        )
+        .unwrap();
+    }
+    if let Some(win) = win {
+        let squares = win.squares();
+        let ((x1, y1), (x2, y2)) = (center(squares[0]), center(squares[2]));
+        writeln!(
+            svg,
+            r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="{WIN_COLOR}" stroke-width="12" stroke-linecap="round"/>"#
+        )
+        .unwrap();
+    }
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-family="sans-serif" font-size="24" text-anchor="middle">{}</text>"#,
+        width / 2,
+        size + 2 * MARGIN + CAPTION / 2,
+        escape(caption)
+    )
+    .unwrap();
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Draw `player`'s mark in square `pos`
+fn mark(svg: &mut String, pos: usize, player: Player) {
+    let (x, y) = center(pos);
+    // a little in from the edges of the square
+    let reach = SQUARE * 3 / 10;
+    match player {
+        X => {
+            let lines = [
+                (x - reach, y - reach, x + reach, y + reach),
+                (x - reach, y + reach, x + reach, y - reach),
+            ];
+            for (x1, y1, x2, y2) in lines {
+                writeln!(
+                    svg,
+                    r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="{X_COLOR}" stroke-width="10" stroke-linecap="round"/>"#
+                )
+                .unwrap();
+            }
+        }
+        O => writeln!(
+            svg,
+            r#"<circle cx="{x}" cy="{y}" r="{reach}" fill="none" stroke="{O_COLOR}" stroke-width="10"/>"#
+        )
+        .unwrap(),
+    }
+}
+
+/// The top left of square `pos`
+fn corner(pos: usize) -> (usize, usize) {
+    (MARGIN + pos % 3 * SQUARE, MARGIN + pos / 3 * SQUARE)
+}
+
+fn center(pos: usize) -> (usize, usize) {
+    let (x, y) = corner(pos);
+    (x + SQUARE / 2, y + SQUARE / 2)
+}
+
+/// `text` with the characters that mean something in XML replaced
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
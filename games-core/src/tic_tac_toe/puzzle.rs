@@ -0,0 +1,233 @@
+//! Positions where the player to move can force a win, and has to find out how.
+//!
+//! As well as the daily puzzle, puzzles can be loaded from pack files in the `puzzles`
+//! directory (in the data directory). A pack is any `.txt` file, listing puzzles like this:
+//!
+//! ```text
+//! # comments start with #
+//! [Fork the corners]
+//! objective = X to play and force a win
+//! board = X.O/.O./X..
+//! solution = a2
+//! ```
+//!
+//! The board is given row by row from the top, using `X`, `O` and `.` for an empty square.
+//! Whose move it is follows from the number of marks, as X always starts. Squares are named
+//! like `b2` (see `board::parse_square`), and `solution` may list several, separated by commas.
+use std::{fs, io, path::Path};
+
+use super::board::{self, Board, Player, Position, O, X};
+use crate::{
+    bot::{Outcome, Solver},
+    rng::Rng,
+};
+
+#[derive(Clone)]
+pub struct Puzzle {
+    pub title: String,
+    pub board: Board,
+    pub to_move: Player,
+    /// What the player is asked to do
+    pub objective: String,
+    /// Every square that solves the puzzle
+    pub solutions: Vec<usize>,
+}
+
+impl Puzzle {
+    /// The puzzle for `day` (see `crate::date`), which is the same for everyone
+    pub fn daily(day: u64) -> Self {
+        let mut rng = Rng::new(day);
+        let mut solver = Solver::new();
+        loop {
+            if let Some(mut puzzle) = Self::random(&mut rng, &mut solver) {
+                puzzle.title = format!("Daily puzzle {}", crate::date::format(day));
+                return puzzle;
+            }
+        }
+    }
+
+    /// Play some random moves, and see if they led to a good puzzle
+    fn random(rng: &mut Rng, solver: &mut Solver<Position>) -> Option<Self> {
+        let mut board = Board::new();
+        let mut to_move = X;
+        for _ in 0..2 + rng.below(4) {
+            let empty: Vec<_> = board.empty_squares().collect();
+            let pos = empty[rng.below(empty.len())];
+            board.set(pos, Some(to_move));
+            to_move.toggle();
+            if board.check_win_from(pos).is_some() {
+                return None;
+            }
+        }
+        // completing a line is too easy
+        let immediate_win = board.empty_squares().any(|pos| {
+            let mut next = board.clone();
+            next.set(pos, Some(to_move));
+            next.check_win_from(pos).is_some()
+        });
+        if immediate_win {
+            return None;
+        }
+        let position = Position::from_board(board.clone(), to_move);
+        let solutions: Vec<_> = board
+            .empty_squares()
+            .filter(|pos| solver.outcome_of(&position, *pos) == Outcome::Win)
+            .collect();
+        // there has to be a winning move, but not every move should win
+        if solutions.is_empty() || solutions.len() == board.empty_squares().count() {
+            return None;
+        }
+        Some(Self {
+            title: String::new(),
+            board,
+            to_move,
+            objective: format!("{to_move} to play and force a win"),
+            solutions,
+        })
+    }
+}
+
+/// Load every puzzle from the packs in `dir`, sorted by file name.
+///
+/// Problems with individual puzzles are returned as messages rather than stopping the rest from
+/// loading.
+pub fn load_packs(dir: &Path) -> (Vec<Puzzle>, Vec<String>) {
+    let (mut puzzles, mut errors) = (vec![], vec![]);
+    let mut paths: Vec<_> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+        Err(e) => return (puzzles, vec![format!("{}: {e}", dir.display())]),
+    };
+    paths.sort();
+    for path in paths {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match fs::read_to_string(&path) {
+            Ok(contents) => parse_pack(&contents, &name, &mut puzzles, &mut errors),
+            Err(e) => errors.push(format!("{name}: {e}")),
+        }
+    }
+    (puzzles, errors)
+}
+
+/// The fields of a puzzle that has been started but not finished
+#[derive(Default)]
+struct Partial {
+    title: String,
+    /// Line the puzzle started on, for errors
+    line: usize,
+    objective: Option<String>,
+    board: Option<Board>,
+    solutions: Option<Vec<usize>>,
+}
+
+impl Partial {
+    fn finish(self, pack: &str) -> Result<Puzzle, String> {
+        let missing = |field| format!("{pack}:{}: puzzle is missing `{field}`", self.line);
+        let board = self.board.ok_or_else(|| missing("board"))?;
+        let solutions = self.solutions.ok_or_else(|| missing("solution"))?;
+        let [xs, os] = [X, O].map(|player| {
+            let squares = board.squares();
+            squares.filter(|sq| *sq == Some(player)).count()
+        });
+        let to_move = match xs.checked_sub(os) {
+            Some(0) => X,
+            Some(1) => O,
+            _ => return Err(format!("{pack}:{}: impossible position", self.line)),
+        };
+        if let Some(pos) = solutions.iter().find(|pos| board.get(**pos).is_some()) {
+            let name = board::square_name(*pos);
+            return Err(format!("{pack}:{}: solution {name} is taken", self.line));
+        }
+        Ok(Puzzle {
+            title: format!("{} - {}", pack.trim_end_matches(".txt"), self.title),
+            objective: self
+                .objective
+                .unwrap_or_else(|| format!("{to_move} to play and win")),
+            board,
+            to_move,
+            solutions,
+        })
+    }
+}
+
+fn parse_pack(contents: &str, pack: &str, puzzles: &mut Vec<Puzzle>, errors: &mut Vec<String>) {
+    let mut partial: Option<Partial> = None;
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(title) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            if let Some(partial) = partial.take() {
+                puzzles.extend(partial.finish(pack).map_err(|e| errors.push(e)).ok());
+            }
+            partial = Some(Partial {
+                title: title.trim().to_owned(),
+                line: idx + 1,
+                ..Partial::default()
+            });
+            continue;
+        }
+        let result = match &mut partial {
+            Some(partial) => parse_field(partial, line),
+            None => Err("expected a `[title]` before the first puzzle".to_owned()),
+        };
+        if let Err(msg) = result {
+            errors.push(format!("{pack}:{}: {msg}", idx + 1));
+        }
+    }
+    if let Some(partial) = partial {
+        puzzles.extend(partial.finish(pack).map_err(|e| errors.push(e)).ok());
+    }
+}
+
+fn parse_field(partial: &mut Partial, line: &str) -> Result<(), String> {
+    let Some((key, value)) = line.split_once('=') else {
+        return Err(format!("expected `key = value`, found \"{line}\""));
+    };
+    let (key, value) = (key.trim(), value.trim());
+    match key {
+        "objective" => partial.objective = Some(value.to_owned()),
+        "board" => partial.board = Some(parse_board(value)?),
+        "solution" => {
+            let solutions = value
+                .split(',')
+                .map(|name| {
+                    board::parse_square(name.trim())
+                        .ok_or_else(|| format!("\"{}\" isn't a square", name.trim()))
+                })
+                .collect::<Result<_, _>>()?;
+            partial.solutions = Some(solutions);
+        }
+        _ => return Err(format!("unknown field \"{key}\"")),
+    }
+    Ok(())
+}
+
+fn parse_board(value: &str) -> Result<Board, String> {
+    let mut board = Board::new();
+    let mut squares = value.chars().filter(|ch| *ch != '/');
+    for idx in 0..9 {
+        let square = match squares.next() {
+            Some('X' | 'x') => Some(X),
+            Some('O' | 'o') => Some(O),
+            Some('.') => None,
+            _ => {
+                return Err(format!(
+                    "expected 9 squares of X, O or ., found \"{value}\""
+                ))
+            }
+        };
+        board.set(idx, square);
+    }
+    if squares.next().is_some() {
+        return Err(format!("too many squares in \"{value}\""));
+    }
+    Ok(board)
+}
@@ -0,0 +1,177 @@
+//! Chess clocks, for any turn-based game.
+//!
+//! A game keeps a `Clock`, calls `switch` after each move, and ends the game when `flagged`
+//! says whose time has run out.
+use std::time::{Duration, Instant};
+
+use crate::player::Seat;
+
+/// How much time players get, set with `time_control` in the settings file
+#[derive(Clone, Copy, PartialEq)]
+pub enum TimeControl {
+    /// No clock
+    None,
+    /// The same time for every move, with nothing carried over (`"move 10s"`)
+    PerMove(Duration),
+    /// Time for the whole game, plus `increment` after each move (`"fischer 5m 3s"`)
+    Fischer { base: Duration, increment: Duration },
+    /// Time for the whole game (`"total 5m"`)
+    Total(Duration),
+}
+
+impl TimeControl {
+    pub fn from_str(value: &str) -> Option<Self> {
+        let words: Vec<_> = value.split_whitespace().collect();
+        match words[..] {
+            ["none"] => Some(Self::None),
+            ["move", time] => Some(Self::PerMove(parse_duration(time)?)),
+            ["fischer", base, increment] => Some(Self::Fischer {
+                base: parse_duration(base)?,
+                increment: parse_duration(increment)?,
+            }),
+            ["total", time] => Some(Self::Total(parse_duration(time)?)),
+            _ => None,
+        }
+    }
+
+    /// Each player's time before their first move
+    fn start(self) -> Duration {
+        match self {
+            Self::None => Duration::ZERO,
+            Self::PerMove(time) | Self::Total(time) => time,
+            Self::Fischer { base, .. } => base,
+        }
+    }
+}
+
+/// What happens to a player whose time runs out, set with `on_timeout` in the settings file
+#[derive(Clone, Copy, PartialEq)]
+pub enum OnTimeout {
+    /// They lose the game
+    Forfeit,
+    /// A random legal move is played for them, and the game goes on
+    RandomMove,
+}
+
+impl OnTimeout {
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "forfeit" => Some(Self::Forfeit),
+            "random" => Some(Self::RandomMove),
+            _ => None,
+        }
+    }
+}
+
+/// Parses times like `500ms`, `30s` or `5m`
+fn parse_duration(value: &str) -> Option<Duration> {
+    if let Some(ms) = value.strip_suffix("ms") {
+        return Some(Duration::from_millis(ms.parse().ok()?));
+    }
+    if let Some(secs) = value.strip_suffix('s') {
+        return Some(Duration::from_secs(secs.parse().ok()?));
+    }
+    let mins: u64 = value.strip_suffix('m')?.parse().ok()?;
+    Some(Duration::from_secs(mins * 60))
+}
+
+pub struct Clock<P> {
+    control: TimeControl,
+    /// Each player's time, not counting the turn in progress
+    left: Vec<Duration>,
+    /// Whose turn it is and when it started, until the clock is stopped
+    turn: Option<(P, Instant)>,
+}
+
+impl<P: Seat> Clock<P> {
+    /// A clock that starts running for `first` straight away
+    pub fn new(control: TimeControl, first: P) -> Self {
+        Self {
+            control,
+            left: vec![control.start(); P::COUNT],
+            turn: Some((first, Instant::now())),
+        }
+    }
+
+    /// End the turn in progress and start `next`'s
+    pub fn switch(&mut self, next: P) {
+        self.stop();
+        self.turn = Some((next, Instant::now()));
+    }
+
+    /// End the turn in progress, for when the game is over
+    pub fn stop(&mut self) {
+        let Some((player, started)) = self.turn.take() else {
+            return;
+        };
+        let left = &mut self.left[player.index()];
+        match self.control {
+            TimeControl::None => (),
+            TimeControl::PerMove(time) => *left = time,
+            TimeControl::Fischer { increment, .. } => {
+                *left = left.saturating_sub(started.elapsed()) + increment
+            }
+            TimeControl::Total(_) => *left = left.saturating_sub(started.elapsed()),
+        }
+    }
+
+    /// Don't count the last `gap` against the turn in progress, for a game that was put aside
+    pub fn skip(&mut self, gap: Duration) {
+        if let Some((_, started)) = &mut self.turn {
+            *started += gap;
+        }
+    }
+
+    /// The time `player` has left right now, or `None` without a clock
+    pub fn left(&self, player: P) -> Option<Duration> {
+        if self.control == TimeControl::None {
+            return None;
+        }
+        let left = self.left[player.index()];
+        Some(match self.turn {
+            Some((current, started)) if current == player => left.saturating_sub(started.elapsed()),
+            _ => left,
+        })
+    }
+
+    /// The player whose time has run out, if any
+    pub fn flagged(&self) -> Option<P> {
+        let (player, _) = self.turn?;
+        (self.left(player)? == Duration::ZERO).then_some(player)
+    }
+
+    /// The time left for the move in progress, with a `PerMove` control, where each move is
+    /// against its own countdown
+    pub fn shot_clock(&self) -> Option<Duration> {
+        let TimeControl::PerMove(_) = self.control else {
+            return None;
+        };
+        let (player, _) = self.turn?;
+        self.left(player)
+    }
+
+    /// Everyone's time, like `X 4:59  O 5:00`, or `None` without a clock
+    pub fn label(&self) -> Option<String> {
+        let times: Option<Vec<_>> = (0..P::COUNT)
+            .map(P::from_index)
+            .map(|player| {
+                Some(format!(
+                    "{} {}",
+                    player.glyph(),
+                    format_time(self.left(player)?)
+                ))
+            })
+            .collect();
+        Some(times?.join("  "))
+    }
+}
+
+/// `m:ss`, with tenths of a second once there are less than 10 seconds left
+pub fn format_time(time: Duration) -> String {
+    if time < Duration::from_secs(10) {
+        let tenths = time.as_millis() / 100;
+        return format!("{}.{}", tenths / 10, tenths % 10);
+    }
+    let secs = time.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
@@ -0,0 +1,30 @@
+//! The game of the day: each day, the next of the installed games in turn, with a streak for
+//! playing it on its day.
+use crate::{
+    date,
+    stats::{self, Record},
+};
+
+/// Today's game out of `games`, or `None` if there aren't any
+pub fn game<'a>(games: &[&'a str]) -> Option<&'a str> {
+    game_on(games, date::today())
+}
+
+/// The game of the day on `day`
+fn game_on<'a>(games: &[&'a str], day: u64) -> Option<&'a str> {
+    if games.is_empty() {
+        return None;
+    }
+    Some(games[(day % games.len() as u64) as usize])
+}
+
+/// How many days in a row, up to today, the game of the day has been played. One that reached
+/// yesterday still counts, since there's still time to play today.
+///
+/// Each day's game is worked out from the games installed now, so installing or removing one
+/// can break a streak.
+pub fn streak(records: &[Record], games: &[&str]) -> usize {
+    stats::streak(records, |record| {
+        game_on(games, date::day_of(record.finished)) == Some(record.game.as_str())
+    })
+}
@@ -0,0 +1,235 @@
+//! Tournaments between people taking turns at the same computer, started from the menu.
+//!
+//! A round robin has everyone play everyone else once, and the most points wins: 1 for a win
+//! and a half for a draw. A knockout pairs players off each round, with the winners going
+//! through to the next until one is left. Knockout games that are drawn are played again, with
+//! the players swapping sides.
+//!
+//! (`games tournament` is something else: the kinds of computer player against each other.)
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    RoundRobin,
+    Knockout,
+}
+
+impl Format {
+    pub const ALL: [Self; 2] = [Self::RoundRobin, Self::Knockout];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RoundRobin => "round robin",
+            Self::Knockout => "knockout",
+        }
+    }
+
+    /// The format after this one in `ALL`, going round to the start
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|&format| format == self).unwrap();
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+/// Two players due to meet, by index into the tournament's players, X first. `None` is a bye.
+#[derive(Clone, Copy)]
+pub struct Pairing {
+    pub players: [Option<usize>; 2],
+    /// `Some(None)` for a draw
+    pub result: Option<Option<usize>>,
+}
+
+impl Pairing {
+    fn new(x: Option<usize>, o: Option<usize>) -> Self {
+        // a player with a bye goes through without playing
+        let result = match (x, o) {
+            (Some(_), Some(_)) => None,
+            (player, None) | (None, player) => Some(player),
+        };
+        Self {
+            players: [x, o],
+            result,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Standing {
+    pub player: usize,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Standing {
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.
+    }
+}
+
+pub struct Tournament {
+    pub format: Format,
+    pub players: Vec<String>,
+    /// Every round so far. Knockout rounds are added as the one before finishes.
+    pub rounds: Vec<Vec<Pairing>>,
+}
+
+impl Tournament {
+    /// `players` must have at least two different names
+    pub fn new(format: Format, players: Vec<String>) -> Result<Self, String> {
+        if players.len() < 2 {
+            return Err("a tournament needs at least two players".to_owned());
+        }
+        for (idx, name) in players.iter().enumerate() {
+            if players[..idx].contains(name) {
+                return Err(format!("{name} is entered twice"));
+            }
+        }
+        let entrants: Vec<_> = (0..players.len()).map(Some).collect();
+        let rounds = match format {
+            Format::RoundRobin => round_robin(players.len()),
+            Format::Knockout => vec![knockout_round(&entrants)],
+        };
+        Ok(Self {
+            format,
+            players,
+            rounds,
+        })
+    }
+
+    /// The round and index of the next game to play, or `None` once the tournament is over
+    pub fn next_game(&self) -> Option<(usize, usize)> {
+        self.rounds
+            .iter()
+            .enumerate()
+            .find_map(|(round, pairings)| {
+                let idx = pairings
+                    .iter()
+                    .position(|pairing| pairing.result.is_none())?;
+                Some((round, idx))
+            })
+    }
+
+    /// The names of the players in a pairing, X first
+    pub fn names(&self, round: usize, idx: usize) -> [String; 2] {
+        self.rounds[round][idx].players.map(|player| match player {
+            Some(player) => self.players[player].clone(),
+            None => "bye".to_owned(),
+        })
+    }
+
+    /// Record the result of a game, where `winner` is 0 for X and 1 for O, and `None` for a draw
+    pub fn record(&mut self, round: usize, idx: usize, winner: Option<usize>) {
+        let pairing = &mut self.rounds[round][idx];
+        let winner = winner.map(|side| pairing.players[side].expect("byes aren't played"));
+        match (self.format, winner) {
+            // someone has to go through, so play again the other way round
+            (Format::Knockout, None) => pairing.players.reverse(),
+            _ => pairing.result = Some(winner),
+        }
+        if self.format == Format::Knockout && self.next_game().is_none() {
+            let through: Vec<_> = self.rounds[round]
+                .iter()
+                .map(|pairing| pairing.result.flatten())
+                .collect();
+            if through.len() > 1 {
+                self.rounds.push(knockout_round(&through));
+            }
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.next_game().is_none()
+    }
+
+    /// Everyone's results, best first. Byes don't count.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<_> = (0..self.players.len())
+            .map(|player| Standing {
+                player,
+                ..Standing::default()
+            })
+            .collect();
+        for pairing in self.rounds.iter().flatten() {
+            let ([Some(x), Some(o)], Some(winner)) = (pairing.players, pairing.result) else {
+                continue;
+            };
+            for player in [x, o] {
+                let standing = &mut standings[player];
+                match winner {
+                    None => standing.draws += 1,
+                    Some(winner) if winner == player => standing.wins += 1,
+                    Some(_) => standing.losses += 1,
+                }
+            }
+        }
+        // stable, so ties stay in the order the players were entered
+        standings.sort_by(|a, b| b.points().total_cmp(&a.points()));
+        standings
+    }
+
+    /// Whoever won, once the tournament is over. A round robin can be tied.
+    pub fn winners(&self) -> Vec<usize> {
+        if !self.is_over() {
+            return vec![];
+        }
+        match self.format {
+            Format::RoundRobin => {
+                let standings = self.standings();
+                let best = standings[0].points();
+                standings
+                    .iter()
+                    .take_while(|standing| standing.points() == best)
+                    .map(|standing| standing.player)
+                    .collect()
+            }
+            Format::Knockout => {
+                let last = &self.rounds[self.rounds.len() - 1];
+                last.iter()
+                    .filter_map(|pairing| pairing.result.flatten())
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Everyone plays everyone else once, using the circle method: one player stays put and the rest
+/// rotate round them
+fn round_robin(players: usize) -> Vec<Vec<Pairing>> {
+    let mut seats: Vec<_> = (0..players).map(Some).collect();
+    if players % 2 == 1 {
+        seats.push(None);
+    }
+    let count = seats.len();
+    (0..count - 1)
+        .map(|round| {
+            let pairings = (0..count / 2)
+                .map(|idx| {
+                    let (a, b) = (seats[idx], seats[count - 1 - idx]);
+                    // swap sides each round, so the player that stays put doesn't always go first
+                    if (round + idx) % 2 == 0 {
+                        Pairing::new(a, b)
+                    } else {
+                        Pairing::new(b, a)
+                    }
+                })
+                // a player with the bye just sits the round out
+                .filter(|pairing| pairing.players.iter().all(Option::is_some))
+                .collect();
+            seats[1..].rotate_right(1);
+            pairings
+        })
+        .collect()
+}
+
+/// Pair the first of `entrants` with the last, the second with the second to last, and so on,
+/// with byes for the first few if there aren't a power of two
+fn knockout_round(entrants: &[Option<usize>]) -> Vec<Pairing> {
+    let count = entrants.len().next_power_of_two();
+    (0..count / 2)
+        .map(|idx| {
+            let first = entrants[idx];
+            let second = entrants.get(count - 1 - idx).copied().flatten();
+            Pairing::new(first, second)
+        })
+        .collect()
+}
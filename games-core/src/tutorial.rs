@@ -0,0 +1,39 @@
+//! Step-by-step tutorials.
+//!
+//! A tutorial is just data: each game provides a list of `Step`s, and the game's UI shows the
+//! current prompt and only advances once the player does what it asks.
+
+/// One prompt of a tutorial
+pub struct Step {
+    /// Shown alongside the board
+    pub prompt: &'static str,
+    /// The square the player must play to advance, or `None` to advance by pressing space
+    pub expect: Option<usize>,
+    /// The opponent's scripted reply once the expected move has been made
+    pub reply: Option<usize>,
+}
+
+pub struct Tutorial {
+    steps: &'static [Step],
+    current: usize,
+}
+
+impl Tutorial {
+    pub fn new(steps: &'static [Step]) -> Self {
+        Self { steps, current: 0 }
+    }
+
+    /// The step the player is on, or `None` once they have finished
+    pub fn step(&self) -> Option<&'static Step> {
+        self.steps.get(self.current)
+    }
+
+    pub fn advance(&mut self) {
+        self.current += 1;
+    }
+
+    /// `(current step, total steps)`, both counting from 1
+    pub fn progress(&self) -> (usize, usize) {
+        (self.current + 1, self.steps.len())
+    }
+}
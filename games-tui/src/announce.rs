@@ -0,0 +1,39 @@
+//! Describing the game in words as it changes, for screen readers and braille displays.
+//!
+//! With `screen_reader = true` in the settings, games show the description on a line of its own,
+//! with the terminal's cursor on it. `announce` also sends each new description out of the
+//! program as it's made.
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Announce {
+    /// Only in the game's own description line
+    Off,
+    /// As a desktop notification, with the OSC 9 escape sequence on stdout. Terminals that don't
+    /// support it ignore it.
+    Osc,
+}
+
+impl Announce {
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "off" => Some(Self::Off),
+            "osc" => Some(Self::Osc),
+            _ => None,
+        }
+    }
+
+    /// Send `text` wherever this says
+    pub fn send(self, text: &str) -> io::Result<()> {
+        match self {
+            Self::Off => Ok(()),
+            Self::Osc => {
+                // a control character would end the sequence early
+                let text: String = text.chars().filter(|ch| !ch.is_control()).collect();
+                let mut stdout = io::stdout();
+                write!(stdout, "\x1b]9;{text}\x07")?;
+                stdout.flush()
+            }
+        }
+    }
+}
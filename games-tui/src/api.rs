@@ -0,0 +1,349 @@
+//! A small HTTP server for `./games serve-api`, so other programs, like bots, can play the games
+//! without the terminal.
+//!
+//! It only listens on this computer, and answers one request at a time, each with a JSON object.
+//! It sends no CORS headers, so web pages from elsewhere can't use it through the browser. Only
+//! the latest `MAX_GAMES` games are kept, finished ones being forgotten first.
+//! Moves and game names are sent as plain text, not JSON:
+//!
+//! ```text
+//! POST /games                     body "tic-tac-toe"   starts a game, answering with its state
+//! GET  /games/<id>                                      the game's state
+//! POST /games/<id>/moves          body "b2"             makes a move, answering with the new state
+//! GET  /games/<id>/suggestion                           the move the computer would make
+//! ```
+//!
+//! A game's state looks like:
+//!
+//! ```text
+//! {"id":1,"game":"tic-tac-toe","to_move":"O","moves":["b2"],"legal_moves":["a1","a2",...],
+//!  "result":null}
+//! ```
+//!
+//! where the result is the winner's mark, or `"draw"`, once the game is over. Anything that goes
+//! wrong is answered with an error status and `{"error":"..."}`.
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    time::{Duration, Instant},
+};
+
+use games_core::{
+    bot::{Bot, Budget, GameResult, GameState, Mcts},
+    error::GamesError,
+    event_log::{quote, quote_list},
+    player::Seat,
+};
+
+use crate::{
+    gravity_start, infinite, notakto, pentago, quantum, sos_start, three_mens_morris, tic_tac_toe,
+    Game,
+};
+
+/// The most a request body may be. Game names and moves are only ever a few bytes.
+const MAX_BODY: usize = 1024;
+
+/// The most the request line and headers together may be
+const MAX_HEAD: usize = 8 * 1024;
+
+/// The most headers a request may have
+const MAX_HEADERS: usize = 32;
+
+/// How long a client has to send its whole request, and to take the answer
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many games are kept at once
+const MAX_GAMES: usize = 100;
+
+/// Answer requests on `port` until the program is stopped
+pub fn serve(port: u16, budget: Budget) -> Result<(), GamesError> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).map_err(GamesError::Network)?;
+    println!("listening on http://localhost:{port}");
+    let mut server = Server::new(budget);
+    for stream in listener.incoming() {
+        let stream = stream.map_err(GamesError::Network)?;
+        // one client going wrong shouldn't stop the others
+        if let Err(e) = server.answer(stream) {
+            eprintln!("games: {}", GamesError::Network(e));
+        }
+    }
+    Ok(())
+}
+
+struct Server {
+    /// Every game started, by id
+    games: BTreeMap<usize, Box<dyn Session>>,
+    next_id: usize,
+    bot: Mcts,
+}
+
+impl Server {
+    fn new(budget: Budget) -> Self {
+        Self {
+            games: BTreeMap::new(),
+            next_id: 1,
+            bot: Mcts::new(budget),
+        }
+    }
+
+    /// Read one request from `stream` and send the answer back
+    fn answer(&mut self, stream: TcpStream) -> io::Result<()> {
+        // a client that stops halfway would otherwise hold up everyone else
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let (status, body) = match read_request(&stream) {
+            Ok(request) => self.handle(&request.method, &request.path, request.body.trim()),
+            Err(answer) => answer,
+        };
+        write!(
+            &stream,
+            "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+            reason(status),
+            body.len()
+        )?;
+        (&stream).flush()
+    }
+
+    /// Forget games until there's room for another, finished ones first and then the oldest
+    fn make_room(&mut self) {
+        while self.games.len() >= MAX_GAMES {
+            let id = self
+                .games
+                .iter()
+                .find(|(_, session)| session.is_over())
+                .or_else(|| self.games.first_key_value())
+                .map(|(&id, _)| id);
+            if let Some(id) = id {
+                self.games.remove(&id);
+            }
+        }
+    }
+
+    /// The status and JSON to answer `method` on `path` with
+    fn handle(&mut self, method: &str, path: &str, body: &str) -> (u16, String) {
+        let parts: Vec<_> = path.trim_matches('/').split('/').collect();
+        match (method, &parts[..]) {
+            ("POST", ["games"]) => {
+                let Some(game) = Game::from_str(body) else {
+                    return (404, error(&format!("game \"{body}\" not found")));
+                };
+                let id = self.next_id;
+                self.next_id += 1;
+                let session = session(game);
+                let state = session.to_json(id);
+                self.make_room();
+                self.games.insert(id, session);
+                (201, state)
+            }
+            (_, ["games", id, rest @ ..]) => {
+                let Some((id, session)) = id
+                    .parse()
+                    .ok()
+                    .and_then(|id| Some((id, self.games.get_mut(&id)?)))
+                else {
+                    return (404, error(&format!("no game {id}")));
+                };
+                match (method, rest) {
+                    ("GET", []) => (200, session.to_json(id)),
+                    ("POST", ["moves"]) => match session.play(body) {
+                        Ok(()) => (200, session.to_json(id)),
+                        Err(msg) => (400, error(&msg)),
+                    },
+                    ("GET", ["suggestion"]) => match session.suggest(&mut self.bot) {
+                        Some(mv) => (200, format!("{{\"move\":{}}}", quote(&mv))),
+                        None => (400, error("the game is over")),
+                    },
+                    _ => (404, error("not found")),
+                }
+            }
+            _ => (404, error("not found")),
+        }
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+/// `stream`, with all the reads from it together having to be done by `until`
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    until: Instant,
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        let mut stream = self.stream;
+        stream.set_read_timeout(Some(left))?;
+        stream.read(buf)
+    }
+}
+
+/// The request coming in on `stream`, or the status and JSON to answer if it can't be read
+fn read_request(stream: &TcpStream) -> Result<Request, (u16, String)> {
+    let deadline = Deadline {
+        stream,
+        until: Instant::now() + TIMEOUT,
+    };
+    // however long a line a client sends, no more than this is kept
+    let mut reader = BufReader::new(deadline.take((MAX_HEAD + MAX_BODY) as u64));
+    let too_big = || (431, error("the request's headers are too big"));
+    let mut request_line = String::new();
+    let mut head = reader.read_line(&mut request_line).map_err(read_error)?;
+    let mut words = request_line.split_whitespace();
+    let method = words.next().unwrap_or("").to_owned();
+    let path = words.next().unwrap_or("").to_owned();
+    let mut length = None;
+    for headers in 0.. {
+        let mut header = String::new();
+        head += reader.read_line(&mut header).map_err(read_error)?;
+        if head > MAX_HEAD || headers > MAX_HEADERS {
+            return Err(too_big());
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                let value = value.trim().parse();
+                length =
+                    Some(value.map_err(|_| (400, error("the Content-Length isn't a number")))?);
+            }
+        }
+    }
+    let length = match length {
+        Some(length) => length,
+        None if method == "POST" => return Err((400, error("the request has no Content-Length"))),
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err((413, error("the request is too big")));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(read_error)?;
+    Ok(Request {
+        method,
+        path,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+/// The answer to a request that couldn't be read because of `e`
+fn read_error(e: io::Error) -> (u16, String) {
+    match e.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            (408, error("the request took too long"))
+        }
+        _ => (400, error("could not read the request")),
+    }
+}
+
+/// One game being played, whichever game it is
+trait Session: Send {
+    /// Make the move written `name`
+    fn play(&mut self, name: &str) -> Result<(), String>;
+    /// The move `bot` would make, written down, unless the game is over
+    fn suggest(&self, bot: &mut Mcts) -> Option<String>;
+    fn is_over(&self) -> bool;
+    fn to_json(&self, id: usize) -> String;
+}
+
+struct Played<G> {
+    game: Game,
+    position: G,
+    /// Every move so far, written down
+    moves: Vec<String>,
+}
+
+impl<G: GameState> Session for Played<G> {
+    fn play(&mut self, name: &str) -> Result<(), String> {
+        if self.position.result().is_some() {
+            return Err("the game is over".to_owned());
+        }
+        let mv = self
+            .position
+            .parse_move(name)
+            .ok_or_else(|| format!("\"{name}\" isn't a legal move"))?;
+        self.moves.push(self.position.move_name(mv));
+        self.position.apply(mv);
+        Ok(())
+    }
+
+    fn suggest(&self, bot: &mut Mcts) -> Option<String> {
+        let mv = bot.choose_move(&self.position)?;
+        Some(self.position.move_name(mv))
+    }
+
+    fn is_over(&self) -> bool {
+        self.position.result().is_some()
+    }
+
+    fn to_json(&self, id: usize) -> String {
+        let glyph = |player: G::Player| quote(&player.glyph().to_string());
+        let result = match self.position.result() {
+            Some(GameResult::Winner(player)) => glyph(player),
+            Some(GameResult::Draw) => quote("draw"),
+            None => "null".to_owned(),
+        };
+        let legal: Vec<_> = self
+            .position
+            .legal_moves()
+            .into_iter()
+            .map(|mv| self.position.move_name(mv))
+            .collect();
+        format!(
+            "{{\"id\":{id},\"game\":{},\"to_move\":{},\"moves\":{},\"legal_moves\":{},\
+             \"result\":{result}}}",
+            quote(self.game.name()),
+            glyph(self.position.current_player()),
+            quote_list(&self.moves),
+            quote_list(&legal)
+        )
+    }
+}
+
+/// A new game of `game`, from its usual start
+fn session(game: Game) -> Box<dyn Session> {
+    fn played<G: GameState + 'static>(game: Game, position: G) -> Box<dyn Session> {
+        Box::new(Played {
+            game,
+            position,
+            moves: vec![],
+        })
+    }
+    match game {
+        Game::TicTacToe => played(game, tic_tac_toe::Position::new()),
+        Game::ThreeMensMorris => played(game, three_mens_morris::Position::new()),
+        Game::Quantum => played(game, quantum::Position::new()),
+        Game::Notakto => played(game, notakto::Position::new()),
+        Game::Sos => played(game, sos_start()),
+        Game::Pentago => played(game, pentago::Position::new()),
+        Game::Gravity => played(game, gravity_start()),
+        Game::Infinite => played(game, infinite::Position::new()),
+    }
+}
+
+fn error(msg: &str) -> String {
+    format!("{{\"error\":{}}}", quote(msg))
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        431 => "Request Header Fields Too Large",
+        413 => "Content Too Large",
+        _ => "",
+    }
+}
@@ -0,0 +1,434 @@
+//! The screen every game but tic-tac-toe is played on: the board in a box, the result under it
+//! and the status bar along the bottom, between two people or against the computer.
+//!
+//! A game only says what its board looks like and what its keys do, by implementing
+//! `BoardGame`. Starting new games, the computer's moves, the command line and saving the stats
+//! are the same for all of them, in `Screen`.
+use std::time::Instant;
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    layout::{
+        Constraint::{Fill, Length},
+        Layout,
+    },
+    text::Line,
+    widgets::{block::Block, Padding, Paragraph},
+    Frame, Terminal,
+};
+
+use games_core::tic_tac_toe::board::{Player, O, X};
+
+use crate::{
+    bot::{GameResult, GameState, Mcts, Worker},
+    commands::{CommandLine, Host, Registry},
+    events::{self, AppEvent, Events},
+    grid::{Direction, GridCursor},
+    keys::Action,
+    locale::{tr, trf},
+    overlay::Overlay,
+    player::Seat,
+    rating,
+    settings::Settings,
+    stats::{self, Record},
+    status_bar::{self, StatusBar},
+    theme::{ColorMode, Theme},
+    tic_tac_toe::Mode,
+};
+
+/// A move in `G`
+pub type MoveOf<G> = <<G as BoardGame>::Position as GameState>::Move;
+
+/// A game played on the shared screen. The type itself holds whatever the game keeps on top of
+/// its position, like the cursor.
+pub trait BoardGame: Sized {
+    type Position: GameState<Player = Player> + 'static;
+
+    /// The name games are saved under
+    const NAME: &'static str;
+    /// The space around the board inside its box
+    const PADDING: Padding = Padding::uniform(1);
+
+    /// Shown over the board
+    fn title() -> &'static str;
+    /// The position each game starts from
+    fn start(settings: &Settings) -> Self::Position;
+    fn new(settings: &Settings) -> Self;
+    /// Forget anything left from the last game, like a piece picked up
+    fn reset(&mut self) {}
+    /// Do what `code` is for while the game is being played, `action` being what the key map
+    /// says it is. Returns the move it finishes, if any, or why it can't be made.
+    ///
+    /// Quitting and starting the next game are already taken care of, and `Action::Place` is
+    /// ignored while the computer is thinking.
+    fn input(
+        &mut self,
+        table: &Table<Self>,
+        code: KeyCode,
+        action: Option<Action>,
+    ) -> Result<Option<MoveOf<Self>>, String>;
+    /// After either player made `mv` from `before`, which `table` has the result of. Returns
+    /// anything worth telling the players.
+    fn moved(
+        &mut self,
+        _table: &Table<Self>,
+        _mv: MoveOf<Self>,
+        _before: &Self::Position,
+    ) -> Option<String> {
+        None
+    }
+    /// Whether the computer should hold its move back for now, like while the last one is
+    /// still animating
+    fn busy(&self, _table: &Table<Self>) -> bool {
+        false
+    }
+    /// The board, with the cursor on it
+    fn board(&self, table: &Table<Self>) -> Vec<Line<'static>>;
+    /// The keys that do something right now, and what they do, while the game is being played.
+    /// Quitting goes on the end.
+    fn legend(&self, table: &Table<Self>) -> Vec<(String, &'static str)>;
+    /// The status bar after the score: whose turn it is, with `Table::turn`, and anything else
+    /// worth knowing about the game
+    fn status(&self, table: &Table<Self>, status: StatusBar) -> StatusBar {
+        table.turn(status)
+    }
+    /// What `player` is called in a game between two people
+    fn player_name(player: Player) -> String {
+        player.to_string()
+    }
+}
+
+/// The parts of the screen every game has, that the game can look at
+pub struct Table<G: BoardGame> {
+    pub settings: Settings,
+    pub theme: Theme,
+    pub position: G::Position,
+    /// Whether the computer is playing O
+    pub computer: bool,
+    /// When this game started
+    pub started: Instant,
+}
+
+impl<G: BoardGame> Table<G> {
+    pub fn over(&self) -> bool {
+        self.position.result().is_some()
+    }
+
+    /// Whether it's the computer's turn
+    pub fn computer_to_move(&self) -> bool {
+        self.computer && self.position.current_player() == O
+    }
+
+    pub fn name(&self, player: Player) -> String {
+        match (player, self.computer) {
+            (X, true) => "You".to_owned(),
+            (O, true) => "Computer".to_owned(),
+            (player, false) => G::player_name(player),
+        }
+    }
+
+    /// Add whose turn it is to `status`, in their color, unless the game is over
+    pub fn turn(&self, status: StatusBar) -> StatusBar {
+        if self.over() {
+            return status;
+        }
+        let next = self.position.current_player();
+        let to_play = trf("{} to play", &[&self.name(next)]);
+        status.turn(to_play, self.theme.turn(next.index()))
+    }
+}
+
+/// Move `cursor` one square, if `action` is one of the arrow keys
+pub fn step(cursor: &mut GridCursor, action: Option<Action>) {
+    let direction = match action {
+        Some(Action::Left) => Direction::Left,
+        Some(Action::Right) => Direction::Right,
+        Some(Action::Up) => Direction::Up,
+        Some(Action::Down) => Direction::Down,
+        _ => return,
+    };
+    cursor.step(direction, false);
+}
+
+pub struct Screen<G: BoardGame> {
+    pub table: Table<G>,
+    pub game: G,
+    /// Every move so far, by name, for the stats
+    moves: Vec<String>,
+    /// The computer, playing O, if it's playing
+    bot: Option<Worker<G::Position>>,
+    message: Option<String>,
+    /// Games won by X and by O
+    scores: [usize; Player::COUNT],
+    /// A command being typed after `:`
+    command_line: CommandLine,
+    /// Set by `:quit`
+    quit: bool,
+    /// Whether to save stats. Off for snapshots.
+    save: bool,
+}
+
+/// Play `G` until the players quit: two people taking turns, or one against the computer with
+/// `Mode::VsComputer`
+pub fn run<G: BoardGame>(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    mode: &Mode,
+) -> std::io::Result<()> {
+    let mut screen = Screen::<G>::new(settings, matches!(mode, Mode::VsComputer));
+    let mut events = Events::new(settings.tick_rate);
+    loop {
+        terminal.draw(|frame| screen.draw(frame))?;
+        let key = match events.next()? {
+            AppEvent::Input(Event::Key(key)) => key,
+            AppEvent::Input(_) => continue,
+            AppEvent::Tick | AppEvent::Wake => {
+                screen.bot_move();
+                continue;
+            }
+        };
+        // holding a key down only repeats moving the cursor
+        let held_move = key.kind == KeyEventKind::Repeat && screen.is_move(key.code);
+        if !(key.kind == KeyEventKind::Press || held_move) {
+            continue;
+        }
+        if screen.handle_key(key.code) {
+            return Ok(());
+        }
+    }
+}
+
+impl<G: BoardGame> Screen<G> {
+    fn new(settings: &Settings, computer: bool) -> Self {
+        let bot = computer.then(|| Worker::spawn(Mcts::new(settings.bot_budget), events::wake));
+        Self {
+            table: Table {
+                settings: settings.clone(),
+                theme: Theme::new(settings),
+                position: G::start(settings),
+                computer,
+                started: Instant::now(),
+            },
+            game: G::new(settings),
+            moves: vec![],
+            bot,
+            message: None,
+            scores: [0; Player::COUNT],
+            command_line: CommandLine::default(),
+            quit: false,
+            save: true,
+        }
+    }
+
+    /// Two people playing with `settings`, without saving anything, for snapshots
+    pub fn for_snapshot(settings: &Settings) -> Self {
+        let mut screen = Self::new(settings, false);
+        screen.save = false;
+        screen
+    }
+
+    /// Press the key for `action`
+    pub fn press(&mut self, action: Action) {
+        self.handle_key(self.table.settings.keys.key(action));
+    }
+
+    /// The screen drawn at `width` by `height`
+    pub fn render(&self, width: u16, height: u16) -> Buffer {
+        // drawing into memory can't fail
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| self.draw(frame)).unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    /// Do what `code` is for. Returns whether to stop playing.
+    /// Whether `code` moves the cursor, rather than being typed at the command line
+    fn is_move(&self, code: KeyCode) -> bool {
+        !self.command_line.is_open()
+            && matches!(
+                self.table.settings.keys.action(code),
+                Some(Action::Left | Action::Right | Action::Up | Action::Down)
+            )
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        self.message = None;
+        if self.command_line.is_open() {
+            if let Some(line) = self.command_line.input(code) {
+                self.message = Registry::new().run(self, &line).unwrap_or_else(Some);
+            }
+            return self.quit;
+        }
+        if code == KeyCode::Char(':') {
+            self.command_line.open();
+            return false;
+        }
+        let action = self.table.settings.keys.action(code);
+        match action {
+            Some(Action::Quit) => return true,
+            Some(Action::Place) if self.table.over() => {
+                self.new_game();
+                return false;
+            }
+            Some(Action::Place) if self.table.computer_to_move() => return false,
+            _ => (),
+        }
+        match self.game.input(&self.table, code, action) {
+            Ok(Some(mv)) => self.play(mv),
+            Ok(None) => (),
+            Err(message) => self.message = Some(message),
+        }
+        false
+    }
+
+    fn new_game(&mut self) {
+        self.table.position = G::start(&self.table.settings);
+        self.table.started = Instant::now();
+        self.moves.clear();
+        self.game.reset();
+        // a move the computer is still thinking about would be for the old game
+        if self.bot.is_some() {
+            self.bot = Some(Worker::spawn(
+                Mcts::new(self.table.settings.bot_budget),
+                events::wake,
+            ));
+        }
+    }
+
+    fn play(&mut self, mv: MoveOf<G>) {
+        let before = self.table.position.clone();
+        self.moves.push(before.move_name(mv));
+        self.table.position.apply(mv);
+        if let Some(message) = self.game.moved(&self.table, mv, &before) {
+            self.message = Some(message);
+        }
+        match self.table.position.result() {
+            Some(result) => self.finish(result),
+            None => self.bot_move(),
+        }
+    }
+
+    /// Let the computer move, if it's its turn and the game isn't holding it back
+    fn bot_move(&mut self) {
+        if !self.table.computer_to_move() || self.table.over() {
+            return;
+        }
+        let bot = self.bot.as_mut().unwrap();
+        if !bot.is_thinking() {
+            bot.think(self.table.position.clone());
+        }
+        if self.game.busy(&self.table) {
+            return;
+        }
+        // where the computer moves again, like after an S-O-S, `play` asks for the next move
+        if let Some(mv) = bot.poll() {
+            self.play(mv);
+        }
+    }
+
+    /// Count and record the game that just finished
+    fn finish(&mut self, result: GameResult<Player>) {
+        let winner = match result {
+            GameResult::Winner(winner) => {
+                self.scores[winner.index()] += 1;
+                Some(self.table.name(winner))
+            }
+            GameResult::Draw => None,
+        };
+        if !self.save {
+            return;
+        }
+        let players = vec![self.table.name(X), self.table.name(O)];
+        let duration = self.table.started.elapsed();
+        let record = Record::new(G::NAME, players, winner, duration, self.moves.clone());
+        // like tic-tac-toe's, the stats aren't worth stopping the game for
+        match stats::record(&record) {
+            Ok(()) => {
+                let _ = rating::record(&record);
+            }
+            Err(e) => self.message = Some(trf("could not save the game: {}", &[&e])),
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let table = &self.table;
+        let lines = self.game.board(table);
+        let title = G::title();
+        let legend = self.legend();
+        let board_width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+        let width = board_width
+            .max(legend.chars().count() as u16)
+            .max(title.chars().count() as u16)
+            + 4;
+        let result = table.position.result().map(|result| match result {
+            GameResult::Winner(winner) => trf("{} won!", &[&table.name(winner)]),
+            GameResult::Draw => tr("draw").to_owned(),
+        });
+        let keys = &table.settings.keys;
+        let overlay = result.as_deref().map(|result| {
+            Overlay::new(result)
+                .button(keys.symbols(&[Action::Place]), tr("next game"))
+                .button(keys.symbols(&[Action::Quit]), tr("quit"))
+        });
+        // the result goes under the board, to keep the board in sight
+        let height = lines.len() as u16 + 2 + G::PADDING.top + G::PADDING.bottom;
+        let overlay_height = overlay.as_ref().map_or(0, Overlay::height);
+        let layout = Layout::vertical([Fill(1), Length(height), Length(overlay_height), Fill(1)]);
+        let [main_area, status_area] =
+            Layout::vertical([Fill(1), Length(status_bar::HEIGHT)]).areas(frame.area());
+        let [_, area, overlay_area, _] = layout.areas(main_area);
+        let [_, area, _] = Layout::horizontal([Fill(1), Length(width), Fill(1)]).areas(area);
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(Line::raw(legend).centered())
+            .padding(G::PADDING);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+        if let Some(overlay) = overlay {
+            overlay.draw(frame, overlay_area);
+        }
+
+        let (x, o) = (table.name(X), table.name(O));
+        let status =
+            StatusBar::new().score(&[(&x, self.scores[X.index()]), (&o, self.scores[O.index()])]);
+        let mut status = self
+            .game
+            .status(table, status)
+            .part(self.command_line.label().unwrap_or_default());
+        if let Some(message) = &self.message {
+            status = status.message(message);
+        }
+        status.draw(frame, status_area);
+    }
+
+    fn legend(&self) -> String {
+        if self.table.over() {
+            // the result's overlay shows what to do next
+            return String::new();
+        }
+        let mut legend = self.game.legend(&self.table);
+        let keys = &self.table.settings.keys;
+        legend.push((keys.symbols(&[Action::Quit]), tr("quit")));
+        legend
+            .into_iter()
+            .map(|(keys, what)| format!("{keys} {what}"))
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+impl<G: BoardGame> Host for Screen<G> {
+    fn restart(&mut self) -> Result<(), String> {
+        self.new_game();
+        Ok(())
+    }
+
+    fn quit(&mut self) {
+        self.quit = true;
+    }
+
+    fn set_color_mode(&mut self, mode: ColorMode) {
+        self.table.settings.color_mode = mode;
+        self.table.theme = Theme::new(&self.table.settings);
+    }
+}
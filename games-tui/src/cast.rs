@@ -0,0 +1,114 @@
+//! Screens saved as an asciicast, the recording format asciinema plays, to share a game as a
+//! video without a screen-capture tool.
+//!
+//! The file is a JSON header with the terminal size, then a line for each screen with when it
+//! appears and the escape codes that draw it, colors included.
+use std::{fmt::Write, time::Duration};
+
+use games_core::event_log::quote;
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier, Style},
+};
+
+/// Each screen of `frames` in turn, `interval` apart, as an asciicast v2 file
+pub fn encode(frames: &[Buffer], interval: Duration, title: &str) -> String {
+    let (width, height) = frames
+        .first()
+        .map_or((80, 24), |frame| (frame.area.width, frame.area.height));
+    let mut cast = format!(
+        "{{\"version\": 2, \"width\": {width}, \"height\": {height}, \"title\": {}}}\n",
+        quote(title)
+    );
+    let mut at = Duration::ZERO;
+    for frame in frames {
+        writeln!(
+            cast,
+            "[{:.3}, \"o\", {}]",
+            at.as_secs_f64(),
+            quote(&draw(frame))
+        )
+        .unwrap();
+        at += interval;
+    }
+    // nothing new, so the last screen stays up as long as the others
+    writeln!(cast, "[{:.3}, \"o\", \"\"]", at.as_secs_f64()).unwrap();
+    cast
+}
+
+/// Escape codes that clear the terminal and draw `buffer` on it
+fn draw(buffer: &Buffer) -> String {
+    let mut out = String::from("\x1b[H\x1b[2J");
+    for y in 0..buffer.area.height {
+        if y > 0 {
+            out.push_str("\r\n");
+        }
+        let mut current = None;
+        for x in 0..buffer.area.width {
+            let cell = &buffer[(x, y)];
+            let style = cell.style();
+            if current != Some(style) {
+                out.push_str(&sgr(style));
+                current = Some(style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+/// The escape code that switches to `style`, from whatever was before
+fn sgr(style: Style) -> String {
+    let mut codes = vec!["0".to_owned()];
+    let modifiers = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    for (modifier, code) in modifiers {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_owned());
+        }
+    }
+    if let Some(fg) = style.fg.and_then(|color| color_code(color, false)) {
+        codes.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|color| color_code(color, true)) {
+        codes.push(bg);
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// The SGR parameters for `color`, as a background if `bg`. `None` for the terminal's own color.
+fn color_code(color: Color, bg: bool) -> Option<String> {
+    let base = if bg { 40 } else { 30 };
+    let named = |idx: u8, bright: bool| {
+        let code = base + idx + if bright { 60 } else { 0 };
+        Some(code.to_string())
+    };
+    match color {
+        Color::Reset => None,
+        Color::Black => named(0, false),
+        Color::Red => named(1, false),
+        Color::Green => named(2, false),
+        Color::Yellow => named(3, false),
+        Color::Blue => named(4, false),
+        Color::Magenta => named(5, false),
+        Color::Cyan => named(6, false),
+        Color::Gray => named(7, false),
+        Color::DarkGray => named(0, true),
+        Color::LightRed => named(1, true),
+        Color::LightGreen => named(2, true),
+        Color::LightYellow => named(3, true),
+        Color::LightBlue => named(4, true),
+        Color::LightMagenta => named(5, true),
+        Color::LightCyan => named(6, true),
+        Color::White => named(7, true),
+        Color::Indexed(idx) => Some(format!("{};5;{idx}", base + 8)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{r};{g};{b}", base + 8)),
+    }
+}
@@ -0,0 +1,71 @@
+//! Copying text to the clipboard: the system's, with a tool like `wl-copy`, `xclip` or `pbcopy`,
+//! or otherwise through the terminal, so it works over ssh too.
+//!
+//! The terminal gets the text with the OSC 52 escape sequence. Terminals that don't support it
+//! ignore it. Over ssh this is always used, as the system clipboard would be the remote one.
+use std::{
+    env,
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+/// Programs that copy their input to the system clipboard, in the order they're tried, each with
+/// its arguments and the variable that has to be set for it to work, if any
+const TOOLS: [(&str, &[&str], Option<&str>); 5] = [
+    ("wl-copy", &[], Some("WAYLAND_DISPLAY")),
+    ("xclip", &["-selection", "clipboard"], Some("DISPLAY")),
+    ("xsel", &["--clipboard", "--input"], Some("DISPLAY")),
+    ("pbcopy", &[], None),
+    // WSL
+    ("clip.exe", &[], None),
+];
+
+pub fn copy(text: &str) -> io::Result<()> {
+    let remote = env::var_os("SSH_CONNECTION").is_some() || env::var_os("SSH_TTY").is_some();
+    if !remote && TOOLS.iter().any(|tool| copy_with(tool, text)) {
+        return Ok(());
+    }
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+/// Whether `tool` is there and copied `text`
+fn copy_with(&(program, args, needs): &(&str, &[&str], Option<&str>), text: &str) -> bool {
+    if needs.is_some_and(|var| env::var_os(var).is_none()) {
+        return false;
+    }
+    // nothing it prints can go to the screen
+    let child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return false;
+    };
+    let written = child
+        .stdin
+        .take()
+        .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    child.wait().is_ok_and(|status| status.success()) && written
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (idx, &byte)| {
+            n | u32::from(byte) << (16 - 8 * idx)
+        });
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * idx) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,136 @@
+//! The command line, opened with `:` during a game like vim's, for things there's no key for,
+//! like `:restart` or `:theme colorblind`. Every game has the commands in `Registry::new`, and
+//! can add its own on top, like tic-tac-toe's `:ai hard`.
+use crossterm::event::KeyCode;
+
+use crate::{locale::trf, theme::ColorMode};
+
+/// Longest command that can be typed
+const MAX_LEN: usize = 40;
+
+/// What the commands every game has need from it
+pub trait Host {
+    /// Abandon the game in progress for a new one, or say why it can't be
+    fn restart(&mut self) -> Result<(), String>;
+    fn quit(&mut self);
+    /// Draw in `mode` from now on, until the game ends
+    fn set_color_mode(&mut self, mode: ColorMode);
+}
+
+pub struct Command<G> {
+    pub name: &'static str,
+    /// What goes after the name, shown when it's used wrongly, like `<slot>`
+    pub usage: &'static str,
+    /// How many words go after the name
+    pub args: usize,
+    /// Run it with the words after the name. Returns anything worth saying about how it went.
+    pub run: fn(&mut G, &[&str]) -> Result<Option<String>, String>,
+}
+
+pub struct Registry<G> {
+    commands: Vec<Command<G>>,
+}
+
+impl<G: Host> Registry<G> {
+    /// The commands every game has
+    pub fn new() -> Self {
+        Self {
+            commands: vec![
+                Command {
+                    name: "restart",
+                    usage: "",
+                    args: 0,
+                    run: |game, _| game.restart().map(|()| None),
+                },
+                Command {
+                    name: "theme",
+                    usage: "<default | high-contrast | colorblind>",
+                    args: 1,
+                    run: theme,
+                },
+                Command {
+                    name: "quit",
+                    usage: "",
+                    args: 0,
+                    run: |game, _| {
+                        game.quit();
+                        Ok(None)
+                    },
+                },
+            ],
+        }
+    }
+
+    /// Add one of the game's own commands
+    pub fn with(mut self, command: Command<G>) -> Self {
+        self.commands.push(command);
+        self
+    }
+
+    /// Run `line`, which was typed after the `:`. Returns anything worth saying about how it
+    /// went, or why it couldn't be run.
+    pub fn run(&self, game: &mut G, line: &str) -> Result<Option<String>, String> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            return Ok(None);
+        };
+        if name == "help" {
+            let names: Vec<_> = self.commands.iter().map(|command| command.name).collect();
+            return Ok(Some(trf("commands: {}", &[&names.join(", ")])));
+        }
+        let command = self
+            .commands
+            .iter()
+            .find(|command| command.name == name)
+            .ok_or_else(|| trf("unknown command \"{}\", try :help", &[&name]))?;
+        if args.len() != command.args {
+            let usage = format!(":{} {}", command.name, command.usage);
+            return Err(trf("usage: {}", &[&usage.trim_end()]));
+        }
+        (command.run)(game, args)
+    }
+}
+
+fn theme(game: &mut impl Host, args: &[&str]) -> Result<Option<String>, String> {
+    let mode =
+        ColorMode::from_str(args[0]).ok_or_else(|| trf("unknown color mode {}", &[&args[0]]))?;
+    game.set_color_mode(mode);
+    Ok(None)
+}
+
+/// The command being typed, if the command line is open
+#[derive(Default)]
+pub struct CommandLine {
+    typed: Option<String>,
+}
+
+impl CommandLine {
+    pub fn is_open(&self) -> bool {
+        self.typed.is_some()
+    }
+
+    pub fn open(&mut self) {
+        self.typed = Some(String::new());
+    }
+
+    /// Type `code` into the command line. Returns the command once enter is pressed, which
+    /// closes the line, as do escape and backspace with nothing typed.
+    pub fn input(&mut self, code: KeyCode) -> Option<String> {
+        let typed = self.typed.as_mut()?;
+        match code {
+            KeyCode::Char(ch) if typed.chars().count() < MAX_LEN => typed.push(ch),
+            // backspace with nothing left closes the line, like vim's
+            KeyCode::Backspace if typed.pop().is_none() => self.typed = None,
+            KeyCode::Esc => self.typed = None,
+            KeyCode::Enter => return self.typed.take(),
+            _ => (),
+        }
+        None
+    }
+
+    /// What's been typed so far, for the status bar
+    pub fn label(&self) -> Option<String> {
+        let typed = self.typed.as_ref()?;
+        Some(format!(":{typed}_"))
+    }
+}
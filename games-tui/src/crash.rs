@@ -0,0 +1,54 @@
+//! Saving a report when the program panics, so a bug report has something to go on.
+//!
+//! The report has the panic message, a backtrace, the state of the game being played (set with
+//! `set_state` as it changes) and the last few input events, which can be replayed with
+//! `--replay-input`. It's saved in `crashes/` in the data directory.
+use std::{
+    backtrace::Backtrace,
+    env,
+    fmt::Write as _,
+    fs, io,
+    panic::PanicHookInfo,
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{dirs, events};
+
+/// A description of the game being played, empty when there isn't one
+static STATE: Mutex<String> = Mutex::new(String::new());
+
+/// Replace the game state saved in crash reports
+pub fn set_state(state: String) {
+    *STATE.lock().unwrap_or_else(|e| e.into_inner()) = state;
+}
+
+/// Save a report for the panic described by `info`, returning where it was saved
+pub fn write_report(info: &PanicHookInfo) -> io::Result<PathBuf> {
+    let mut report = String::new();
+    let args: Vec<_> = env::args().collect();
+    // writing to a string can't fail
+    writeln!(report, "games {} crashed", env!("CARGO_PKG_VERSION")).unwrap();
+    writeln!(report, "command: {}\n", args.join(" ")).unwrap();
+    writeln!(report, "{info}\n").unwrap();
+    writeln!(report, "backtrace:\n{}", Backtrace::force_capture()).unwrap();
+    let state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    if !state.is_empty() {
+        writeln!(report, "game:\n{state}").unwrap();
+    }
+    writeln!(report, "recent input:").unwrap();
+    for line in events::recent() {
+        writeln!(report, "{line}").unwrap();
+    }
+
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let path = dirs::data_file(&format!("crashes/crash-{secs}.txt"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, report)?;
+    Ok(path)
+}
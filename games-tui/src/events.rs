@@ -0,0 +1,214 @@
+//! Where the game loop gets its events from: input from the terminal, interleaved with regular
+//! ticks for animations and timers, and wake-ups from work done in the background.
+//!
+//! Input is read on a separate thread and sent over a channel, so the loop can wait for either
+//! input or the next tick without busy-waiting. Other threads, like the computer player's, send
+//! a wake-up down the same channel with `wake` when they have something for the loop, so it
+//! doesn't have to wait for the next tick to notice.
+//!
+//! Ctrl+C, and signals asking the program to stop, come out of here as `Interrupted` errors.
+//! They can then be passed up with `?` like any other error, tidying up on the way, and the
+//! terminal is restored before exiting.
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, LazyLock, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+#[cfg(unix)]
+use signal_hook::consts::SIGHUP;
+use signal_hook::consts::{SIGINT, SIGTERM};
+
+mod script;
+
+pub use script::{format_event, read_recording, recent, record_to, replay_from};
+
+/// How often the input thread checks whether it should stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Set when a signal asks the program to stop
+static SIGNALLED: LazyLock<Arc<AtomicBool>> = LazyLock::new(Default::default);
+
+/// Where `wake` sends to: the running `Events`, if there is one
+static WAKER: Mutex<Option<Sender<Message>>> = Mutex::new(None);
+
+/// Catch the signals that would otherwise kill the program with the terminal still set up for
+/// drawing
+pub fn catch_signals() -> io::Result<()> {
+    for signal in [
+        SIGINT,
+        SIGTERM,
+        // the terminal closing
+        #[cfg(unix)]
+        SIGHUP,
+    ] {
+        signal_hook::flag::register(signal, Arc::clone(&SIGNALLED))?;
+    }
+    Ok(())
+}
+
+/// Wait for the next input, for screens that only change when a key is pressed
+pub fn read() -> io::Result<Event> {
+    loop {
+        if let Some(event) = poll(POLL_INTERVAL)? {
+            return Ok(event);
+        }
+    }
+}
+
+/// Like `read`, but giving up after `timeout`
+pub fn read_timeout(timeout: Duration) -> io::Result<Option<Event>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        match poll(left.min(POLL_INTERVAL))? {
+            None if !left.is_zero() => (),
+            event => return Ok(event),
+        }
+    }
+}
+
+/// Wait up to `timeout` for input, without a thread
+fn poll(timeout: Duration) -> io::Result<Option<Event>> {
+    if SIGNALLED.load(Ordering::Relaxed) {
+        return Err(interrupted());
+    }
+    script::wait(timeout, |timeout| {
+        if event::poll(timeout)? {
+            check_interrupt(event::read()?).map(Some)
+        } else {
+            Ok(None)
+        }
+    })
+}
+
+/// Ctrl+C is read as a key rather than sent as a signal while the terminal is in raw mode
+fn check_interrupt(event: Event) -> io::Result<Event> {
+    match event {
+        Event::Key(key)
+            if key.kind == KeyEventKind::Press
+                && key.code == KeyCode::Char('c')
+                && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            Err(interrupted())
+        }
+        event => Ok(event),
+    }
+}
+
+fn interrupted() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, "interrupted")
+}
+
+pub enum AppEvent {
+    Input(Event),
+    /// Time to advance animations and timers
+    Tick,
+    /// Something working in the background, like the computer player, has finished
+    Wake,
+}
+
+/// What comes down the channel to `Events`
+enum Message {
+    Input(io::Result<Event>),
+    Wake,
+}
+
+/// Have the running `Events` return `AppEvent::Wake` now rather than wait for the next tick or
+/// input. Does nothing if there isn't one.
+pub fn wake() {
+    if let Some(sender) = &*WAKER.lock().unwrap() {
+        let _ = sender.send(Message::Wake);
+    }
+}
+
+pub struct Events {
+    input: Receiver<Message>,
+    tick_rate: Duration,
+    next_tick: Instant,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    /// Where `wake` sent to before this was made, like the menu a game was started from, to
+    /// go back to once it's dropped
+    outer_waker: Option<Sender<Message>>,
+}
+
+impl Events {
+    pub fn new(tick_rate: Duration) -> Self {
+        let (sender, input) = mpsc::channel();
+        let outer_waker = WAKER.lock().unwrap().replace(sender.clone());
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let event = match event::poll(POLL_INTERVAL) {
+                        _ if SIGNALLED.load(Ordering::Relaxed) => Err(interrupted()),
+                        Ok(true) => event::read().and_then(check_interrupt),
+                        Ok(false) => continue,
+                        Err(e) => Err(e),
+                    };
+                    if sender.send(Message::Input(event)).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Self {
+            input,
+            tick_rate,
+            next_tick: Instant::now() + tick_rate,
+            stop,
+            thread: Some(thread),
+            outer_waker,
+        }
+    }
+
+    /// Wait for the next input, or the next tick if that comes first
+    pub fn next(&mut self) -> io::Result<AppEvent> {
+        loop {
+            let timeout = self.next_tick.saturating_duration_since(Instant::now());
+            let mut woken = false;
+            let input = script::wait(timeout, |timeout| match self.input.recv_timeout(timeout) {
+                Ok(Message::Input(event)) => event.map(Some),
+                Ok(Message::Wake) => {
+                    woken = true;
+                    Ok(None)
+                }
+                Err(RecvTimeoutError::Timeout) => Ok(None),
+                Err(RecvTimeoutError::Disconnected) => {
+                    Err(io::Error::other("input thread stopped"))
+                }
+            })?;
+            if let Some(event) = input {
+                return Ok(AppEvent::Input(event));
+            }
+            if woken {
+                return Ok(AppEvent::Wake);
+            }
+            // input ignored during a replay can end the wait early
+            if Instant::now() >= self.next_tick {
+                // don't try to catch up on ticks missed while busy
+                self.next_tick = (self.next_tick + self.tick_rate).max(Instant::now());
+                return Ok(AppEvent::Tick);
+            }
+        }
+    }
+}
+
+impl Drop for Events {
+    fn drop(&mut self) {
+        // wait for the thread, so it can't steal input meant for whatever runs next
+        *WAKER.lock().unwrap() = self.outer_waker.take();
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Recording input to a file, and replaying it, to reproduce bugs exactly.
+//!
+//! Each line of a recording is the time in milliseconds since the program started, then the
+//! event, like `1520 key space press 0`. The computer's moves and coin flips are random, so a
+//! replay can still turn out differently.
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{
+    Event, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use games_core::error::GamesError;
+
+use crate::keys;
+
+enum Script {
+    Live,
+    Record {
+        file: File,
+        started: Instant,
+    },
+    /// What's left to replay, with the time each was originally read
+    Replay {
+        events: VecDeque<(Duration, Event)>,
+        started: Instant,
+    },
+}
+
+static SCRIPT: Mutex<Script> = Mutex::new(Script::Live);
+
+/// How many events `recent` remembers
+const RECENT_LEN: usize = 50;
+
+/// The last events read, with when they were read
+static RECENT: Mutex<VecDeque<(Instant, Event)>> = Mutex::new(VecDeque::new());
+
+/// Write every event read from now on to `path`
+pub fn record_to(path: &Path) -> Result<(), GamesError> {
+    let file_error = |source| GamesError::File {
+        path: path.to_owned(),
+        source,
+    };
+    let mut file = File::create(path).map_err(file_error)?;
+    let args: Vec<_> = std::env::args().collect();
+    writeln!(file, "# {}", args.join(" ")).map_err(file_error)?;
+    *SCRIPT.lock().unwrap() = Script::Record {
+        file,
+        started: Instant::now(),
+    };
+    Ok(())
+}
+
+/// Read events from the recording at `path` instead of the terminal, until it runs out
+pub fn replay_from(path: &Path) -> Result<(), GamesError> {
+    *SCRIPT.lock().unwrap() = Script::Replay {
+        events: read_recording(path)?.into(),
+        started: Instant::now(),
+    };
+    Ok(())
+}
+
+/// The events in the recording at `path`, with the time each was read
+pub fn read_recording(path: &Path) -> Result<Vec<(Duration, Event)>, GamesError> {
+    let contents = fs::read_to_string(path).map_err(|source| GamesError::File {
+        path: path.to_owned(),
+        source,
+    })?;
+    let mut events = vec![];
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let event = parse_line(line).ok_or_else(|| GamesError::Corrupt {
+            path: path.to_owned(),
+            line: idx + 1,
+            message: format!("unknown event \"{line}\""),
+        })?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Wait up to `timeout` for an event from `live`, recording it if that's turned on. While
+/// replaying, the next recorded event is returned when it's due instead, and anything from
+/// `live` is ignored, apart from errors (so Ctrl+C still works).
+pub fn wait(
+    timeout: Duration,
+    live: impl FnOnce(Duration) -> io::Result<Option<Event>>,
+) -> io::Result<Option<Event>> {
+    let event = next(timeout, live)?;
+    if let Some(event) = &event {
+        let mut recent = RECENT.lock().unwrap();
+        if recent.len() == RECENT_LEN {
+            recent.pop_front();
+        }
+        recent.push_back((Instant::now(), event.clone()));
+    }
+    Ok(event)
+}
+
+/// The last few events read, oldest first, written like a recording but with times counted from
+/// the first of them
+pub fn recent() -> Vec<String> {
+    // this is for crash reports, so carry on even if something panicked while holding the lock
+    let recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+    let Some((first, _)) = recent.front() else {
+        return vec![];
+    };
+    recent
+        .iter()
+        .filter_map(|(at, event)| {
+            let line = format_event(event)?;
+            Some(format!("{} {line}", at.duration_since(*first).as_millis()))
+        })
+        .collect()
+}
+
+fn next(
+    timeout: Duration,
+    live: impl FnOnce(Duration) -> io::Result<Option<Event>>,
+) -> io::Result<Option<Event>> {
+    let mut script = SCRIPT.lock().unwrap();
+    match &mut *script {
+        Script::Live => live(timeout),
+        Script::Record { file, started } => {
+            let event = live(timeout)?;
+            if let Some(line) = event.as_ref().and_then(format_event) {
+                // a broken recording isn't worth stopping for
+                let _ = writeln!(file, "{} {line}", started.elapsed().as_millis());
+            }
+            Ok(event)
+        }
+        Script::Replay { events, started } => {
+            let Some((at, _)) = events.front() else {
+                // finished: back to the terminal
+                *script = Script::Live;
+                return live(timeout);
+            };
+            live(at.saturating_sub(started.elapsed()).min(timeout))?;
+            if *at <= started.elapsed() {
+                Ok(events.pop_front().map(|(_, event)| event))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// How `event` is written in a recording, without the time. `None` for events that don't matter
+/// when replaying.
+pub fn format_event(event: &Event) -> Option<String> {
+    match event {
+        Event::Key(key) => {
+            let kind = match key.kind {
+                KeyEventKind::Press => "press",
+                KeyEventKind::Repeat => "repeat",
+                KeyEventKind::Release => "release",
+            };
+            let code = keys::key_name(key.code)?;
+            Some(format!("key {code} {kind} {}", key.modifiers.bits()))
+        }
+        Event::Mouse(mouse) => {
+            let kind = match mouse.kind {
+                MouseEventKind::Down(button) => format!("down-{}", button_name(button)),
+                MouseEventKind::Up(button) => format!("up-{}", button_name(button)),
+                MouseEventKind::Drag(button) => format!("drag-{}", button_name(button)),
+                MouseEventKind::Moved => "moved".to_owned(),
+                MouseEventKind::ScrollDown => "scroll-down".to_owned(),
+                MouseEventKind::ScrollUp => "scroll-up".to_owned(),
+                MouseEventKind::ScrollLeft => "scroll-left".to_owned(),
+                MouseEventKind::ScrollRight => "scroll-right".to_owned(),
+            };
+            Some(format!(
+                "mouse {kind} {} {} {}",
+                mouse.column,
+                mouse.row,
+                mouse.modifiers.bits()
+            ))
+        }
+        Event::Resize(width, height) => Some(format!("resize {width} {height}")),
+        Event::FocusGained | Event::FocusLost | Event::Paste(_) => None,
+    }
+}
+
+fn button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "left",
+        MouseButton::Right => "right",
+        MouseButton::Middle => "middle",
+    }
+}
+
+/// The inverse of `format_event`, with its time
+fn parse_line(line: &str) -> Option<(Duration, Event)> {
+    let words: Vec<_> = line.split(' ').collect();
+    let at = Duration::from_millis(words.first()?.parse().ok()?);
+    let modifiers = |bits: &str| Some(KeyModifiers::from_bits_truncate(bits.parse().ok()?));
+    let event = match words[1..] {
+        ["key", code, kind, mods] => {
+            let kind = match kind {
+                "press" => KeyEventKind::Press,
+                "repeat" => KeyEventKind::Repeat,
+                "release" => KeyEventKind::Release,
+                _ => return None,
+            };
+            let code = keys::parse_key(code)?;
+            Event::Key(KeyEvent::new_with_kind(code, modifiers(mods)?, kind))
+        }
+        ["mouse", kind, column, row, mods] => {
+            let button = |name: &str| match name {
+                "left" => Some(MouseButton::Left),
+                "right" => Some(MouseButton::Right),
+                "middle" => Some(MouseButton::Middle),
+                _ => None,
+            };
+            let kind = match kind.split_once('-') {
+                Some(("down", name)) => MouseEventKind::Down(button(name)?),
+                Some(("up", name)) => MouseEventKind::Up(button(name)?),
+                Some(("drag", name)) => MouseEventKind::Drag(button(name)?),
+                _ => match kind {
+                    "moved" => MouseEventKind::Moved,
+                    "scroll-down" => MouseEventKind::ScrollDown,
+                    "scroll-up" => MouseEventKind::ScrollUp,
+                    "scroll-left" => MouseEventKind::ScrollLeft,
+                    "scroll-right" => MouseEventKind::ScrollRight,
+                    _ => return None,
+                },
+            };
+            Event::Mouse(MouseEvent {
+                kind,
+                column: column.parse().ok()?,
+                row: row.parse().ok()?,
+                modifiers: modifiers(mods)?,
+            })
+        }
+        ["resize", width, height] => Event::Resize(width.parse().ok()?, height.parse().ok()?),
+        _ => return None,
+    };
+    Some((at, event))
+}
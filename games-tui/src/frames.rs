@@ -0,0 +1,43 @@
+//! Counting frames, for the frame rate overlay.
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// When recent frames were drawn, and how long the last one took
+#[derive(Default)]
+pub struct FrameStats {
+    /// When each frame in the last second finished
+    drawn: VecDeque<Instant>,
+    last_draw: Duration,
+}
+
+impl FrameStats {
+    /// Count a frame that started drawing at `started` and has just finished
+    pub fn record(&mut self, started: Instant) {
+        let now = Instant::now();
+        self.last_draw = now - started;
+        self.drawn.push_back(now);
+        while self
+            .drawn
+            .front()
+            .is_some_and(|drawn| now - *drawn > Duration::from_secs(1))
+        {
+            self.drawn.pop_front();
+        }
+    }
+
+    /// How long the last frame took to draw
+    pub fn last_draw(&self) -> Duration {
+        self.last_draw
+    }
+
+    /// Like `30 fps, 1.2ms`
+    pub fn label(&self) -> String {
+        format!(
+            "{} fps, {:.1}ms",
+            self.drawn.len(),
+            self.last_draw.as_secs_f64() * 1000.
+        )
+    }
+}
@@ -0,0 +1,217 @@
+//! Gravity tic-tac-toe: players take turns dropping marks into the columns of a 3x3 or 4x4
+//! board, where they fall to the bottom, until someone has a line all the way across.
+//!
+//! The board is drawn like a plugin's, `gravity_size` from the settings. Left and right choose
+//! the column, with the mark waiting over it and the square it would land on highlighted, and
+//! the mark falls into place unless animations are off.
+use std::time::{Duration, Instant};
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+pub use games_core::gravity::{parse_size, Position};
+use games_core::tic_tac_toe::board::Player;
+
+use crate::{
+    board_game::{self, BoardGame, Screen, Table},
+    bot::GameState,
+    grid::GridCursor,
+    keys::Action,
+    locale::tr,
+    player::Seat,
+    settings::Settings,
+};
+
+/// How long a falling mark takes to pass each row
+const DROP_ROW_DURATION: Duration = Duration::from_millis(60);
+
+/// The last mark dropped, while it's falling
+struct Dropping {
+    /// The square it lands on
+    pos: usize,
+    player: Player,
+    started: Instant,
+}
+
+impl Dropping {
+    /// The row the mark has fallen to so far, on a board `size` squares across
+    fn row(&self, size: usize) -> usize {
+        let rows = self.started.elapsed().as_secs_f64() / DROP_ROW_DURATION.as_secs_f64();
+        (rows as usize).min(self.pos / size)
+    }
+
+    fn landed(&self, size: usize) -> bool {
+        self.row(size) == self.pos / size
+    }
+}
+
+pub struct Gravity {
+    /// The column to drop in, as a cursor on a grid one row high
+    cursor: GridCursor,
+    dropping: Option<Dropping>,
+}
+
+/// Every state worth keeping a snapshot of, by name, drawn at `width` by `height`
+pub fn snapshots(width: u16, height: u16) -> Vec<(&'static str, Buffer)> {
+    // each a board size and the columns dropped in, X filling column a to win
+    let states = [
+        ("playing", 3, "b b a"),
+        ("won", 3, "a b a b a"),
+        ("four", 4, "b c b c d"),
+    ];
+    states
+        .into_iter()
+        .map(|(name, size, moves)| {
+            let settings = Settings {
+                gravity_size: size,
+                animations: false,
+                ..Settings::default()
+            };
+            let mut screen = Screen::<Gravity>::for_snapshot(&settings);
+            for column in moves.split_whitespace() {
+                screen.game.cursor.pos = screen.table.position.parse_move(column).unwrap();
+                screen.press(Action::Place);
+            }
+            (name, screen.render(width, height))
+        })
+        .collect()
+}
+
+impl Gravity {
+    /// The mark to draw on `pos`, with the last one dropped partway down if it's still falling
+    fn shown(&self, table: &Table<Self>, pos: usize) -> Option<Player> {
+        let size = table.position.size();
+        match &self.dropping {
+            Some(dropping) if !dropping.landed(size) && pos % size == dropping.pos % size => {
+                let row = dropping.row(size);
+                (pos / size == row).then_some(dropping.player)
+            }
+            _ => table.position.get(pos),
+        }
+    }
+}
+
+impl BoardGame for Gravity {
+    type Position = Position;
+
+    const NAME: &'static str = "gravity-tic-tac-toe";
+
+    fn title() -> &'static str {
+        tr("Gravity tic-tac-toe")
+    }
+
+    fn start(settings: &Settings) -> Position {
+        Position::new(settings.gravity_size)
+    }
+
+    fn new(settings: &Settings) -> Self {
+        Self {
+            cursor: GridCursor::new(settings.gravity_size, 1),
+            dropping: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.dropping = None;
+    }
+
+    /// A press drops a mark in the column under the cursor
+    fn input(
+        &mut self,
+        table: &Table<Self>,
+        _code: KeyCode,
+        action: Option<Action>,
+    ) -> Result<Option<usize>, String> {
+        if action != Some(Action::Place) {
+            board_game::step(&mut self.cursor, action);
+            return Ok(None);
+        }
+        let column = self.cursor.pos;
+        if table.position.legal_moves().contains(&column) {
+            Ok(Some(column))
+        } else {
+            Err(tr("That column is full").to_owned())
+        }
+    }
+
+    fn moved(&mut self, table: &Table<Self>, column: usize, before: &Position) -> Option<String> {
+        self.dropping = table.settings.animate().then(|| Dropping {
+            pos: before.landing(column).unwrap(),
+            player: before.current_player(),
+            started: Instant::now(),
+        });
+        None
+    }
+
+    /// The computer waits for the last mark to land
+    fn busy(&self, table: &Table<Self>) -> bool {
+        let size = table.position.size();
+        self.dropping
+            .as_ref()
+            .is_some_and(|dropping| !dropping.landed(size))
+    }
+
+    fn board(&self, table: &Table<Self>) -> Vec<Line<'static>> {
+        let over = table.over();
+        let size = table.position.size();
+        let next = table.position.current_player();
+        let line = table
+            .position
+            .line()
+            .map(|(line, _)| line)
+            .unwrap_or_default();
+        let landing = table.position.landing(self.cursor.pos);
+        // the mark about to be dropped, waiting over its column
+        let mut waiting = vec![Span::raw("   ")];
+        for column in 0..size {
+            if column == self.cursor.pos && !over && !table.computer_to_move() {
+                waiting.push(Span::styled(
+                    format!(" {next} "),
+                    table.theme.player(next.index()),
+                ));
+            } else {
+                waiting.push(Span::raw("   "));
+            }
+        }
+        let letters: String = (0..size)
+            .map(|column| format!(" {} ", (b'a' + column as u8) as char))
+            .collect();
+        let mut lines = vec![Line::from(waiting), Line::raw(format!("   {letters}"))];
+        for row in 0..size {
+            let mut spans = vec![Span::raw(format!("{:>2} ", row + 1))];
+            for column in 0..size {
+                let pos = row * size + column;
+                let mark = self.shown(table, pos);
+                let mut style = match mark {
+                    Some(player) => table.theme.player(player.index()),
+                    None => Style::new().add_modifier(Modifier::DIM),
+                };
+                if line.contains(&pos) {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                if landing == Some(pos) && !over {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                let text = match mark {
+                    Some(player) => format!(" {player} "),
+                    None => " · ".to_owned(),
+                };
+                spans.push(Span::styled(text, style));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+
+    fn legend(&self, table: &Table<Self>) -> Vec<(String, &'static str)> {
+        let keys = &table.settings.keys;
+        vec![
+            (keys.symbols(&[Action::Left, Action::Right]), tr("move")),
+            (keys.symbols(&[Action::Place]), tr("drop")),
+        ]
+    }
+}
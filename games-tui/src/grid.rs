@@ -0,0 +1,98 @@
+//! A cursor on a grid of squares, like a board, moved one square at a time with the arrow keys.
+//!
+//! Squares are numbered row by row from the top left, so on a 3 by 3 board
+//!
+//! ```text
+//! 0 1 2
+//! 3 4 5
+//! 6 7 8
+//! ```
+use ratatui::layout::{Constraint::Fill, Layout, Rect};
+
+#[derive(Clone, Copy)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Clone, Copy)]
+pub struct GridCursor {
+    pub width: usize,
+    pub height: usize,
+    /// The square the cursor is on
+    pub pos: usize,
+}
+
+impl GridCursor {
+    /// A cursor in the top left corner
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pos: 0,
+        }
+    }
+
+    pub fn column(&self) -> usize {
+        self.pos % self.width
+    }
+
+    pub fn row(&self) -> usize {
+        self.pos / self.width
+    }
+
+    /// Move one square. At the edge, `wrap` goes round to the other side of the same row or
+    /// column, and otherwise the cursor stays where it is.
+    pub fn step(&mut self, direction: Direction, wrap: bool) {
+        if let Some(pos) = self.neighbour(self.pos, direction, wrap) {
+            self.pos = pos;
+        }
+    }
+
+    /// Move to the nearest square in `direction` that's `free`, jumping over the others. If
+    /// there isn't one the cursor stays where it is.
+    pub fn step_to_free(&mut self, direction: Direction, wrap: bool, free: impl Fn(usize) -> bool) {
+        let mut pos = self.pos;
+        while let Some(next) = self.neighbour(pos, direction, wrap) {
+            if next == self.pos {
+                return;
+            }
+            if free(next) {
+                self.pos = next;
+                return;
+            }
+            pos = next;
+        }
+    }
+
+    /// The square next to `pos` in `direction`, if there is one
+    fn neighbour(&self, pos: usize, direction: Direction, wrap: bool) -> Option<usize> {
+        let (column, row) = (pos % self.width, pos / self.width);
+        let (column, row) = match direction {
+            Direction::Left if column > 0 => (column - 1, row),
+            Direction::Left if wrap => (self.width - 1, row),
+            Direction::Right if column + 1 < self.width => (column + 1, row),
+            Direction::Right if wrap => (0, row),
+            Direction::Up if row > 0 => (column, row - 1),
+            Direction::Up if wrap => (column, self.height - 1),
+            Direction::Down if row + 1 < self.height => (column, row + 1),
+            Direction::Down if wrap => (column, 0),
+            _ => return None,
+        };
+        Some(row * self.width + column)
+    }
+
+    /// Where each square is when the grid is drawn in `area`, each the same size
+    pub fn square_areas(&self, area: Rect) -> Vec<Rect> {
+        let rows = Layout::vertical(vec![Fill(1); self.height]).split(area);
+        rows.iter()
+            .flat_map(|row| {
+                Layout::horizontal(vec![Fill(1); self.width])
+                    .split(*row)
+                    .to_vec()
+            })
+            .collect()
+    }
+}
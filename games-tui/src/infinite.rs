@@ -0,0 +1,166 @@
+//! Infinite tic-tac-toe: tic-tac-toe where each player only has three marks on the board, and
+//! placing a fourth takes their oldest one away.
+//!
+//! The board is drawn like a plugin's. The mark that the player to move is about to lose fades
+//! in and out, or is just dimmed when animations are off.
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+pub use games_core::infinite::Position;
+use games_core::tic_tac_toe::board::{parse_square, square_name};
+
+use crate::{
+    board_game::{self, BoardGame, Screen, Table},
+    bot::GameState,
+    grid::GridCursor,
+    keys::Action,
+    locale::{tr, trf},
+    player::Seat,
+    settings::Settings,
+    status_bar::StatusBar,
+};
+
+/// How long the mark about to vanish takes to fade out and back in
+const FADE_PERIOD: Duration = Duration::from_millis(1200);
+
+pub struct Infinite {
+    cursor: GridCursor,
+}
+
+/// Every state worth keeping a snapshot of, by name, drawn at `width` by `height`
+pub fn snapshots(width: u16, height: u16) -> Vec<(&'static str, Buffer)> {
+    // each a press on that square. After six, X's c2 is the next to go, and b2 wins down the
+    // middle without it.
+    const FULL: &str = "c2 a1 b1 c1 b3 a3";
+    let states = [
+        ("vanishing", FULL),
+        ("vanished", &format!("{FULL} a2") as &str),
+        ("won", &format!("{FULL} b2")),
+    ];
+    states
+        .into_iter()
+        .map(|(name, squares)| {
+            let settings = Settings {
+                animations: false,
+                ..Settings::default()
+            };
+            let mut screen = Screen::<Infinite>::for_snapshot(&settings);
+            for square in squares.split_whitespace() {
+                screen.game.cursor.pos = parse_square(square).unwrap();
+                screen.press(Action::Place);
+            }
+            (name, screen.render(width, height))
+        })
+        .collect()
+}
+
+/// Whether the mark about to vanish is dimmed right now: always without animations, and
+/// otherwise for half of each `FADE_PERIOD`
+fn faded(table: &Table<Infinite>) -> bool {
+    !table.settings.animate()
+        || (table.started.elapsed().as_secs_f64() / FADE_PERIOD.as_secs_f64()).fract() < 0.5
+}
+
+impl BoardGame for Infinite {
+    type Position = Position;
+
+    const NAME: &'static str = "infinite-tic-tac-toe";
+
+    fn title() -> &'static str {
+        tr("Infinite tic-tac-toe")
+    }
+
+    fn start(_settings: &Settings) -> Position {
+        Position::new()
+    }
+
+    fn new(_settings: &Settings) -> Self {
+        Self {
+            cursor: GridCursor::new(3, 3),
+        }
+    }
+
+    /// A press places a mark on the square under the cursor
+    fn input(
+        &mut self,
+        table: &Table<Self>,
+        _code: KeyCode,
+        action: Option<Action>,
+    ) -> Result<Option<usize>, String> {
+        if action != Some(Action::Place) {
+            board_game::step(&mut self.cursor, action);
+            return Ok(None);
+        }
+        let pos = self.cursor.pos;
+        if table.position.legal_moves().contains(&pos) {
+            Ok(Some(pos))
+        } else {
+            Err(tr("Can't play there").to_owned())
+        }
+    }
+
+    /// The cursor follows the computer's moves
+    fn moved(&mut self, _table: &Table<Self>, pos: usize, _before: &Position) -> Option<String> {
+        self.cursor.pos = pos;
+        None
+    }
+
+    fn board(&self, table: &Table<Self>) -> Vec<Line<'static>> {
+        let over = table.over();
+        let line = table.position.line().map(|(win, _)| win.squares());
+        let vanishing = table.position.vanishing().filter(|_| !over);
+        let mut lines = vec![Line::raw("    a  b  c ")];
+        for row in 0..3 {
+            let mut spans = vec![Span::raw(format!("{:>2} ", row + 1))];
+            for column in 0..3 {
+                let pos = row * 3 + column;
+                let mark = table.position.get(pos);
+                let mut style = match mark {
+                    Some(player) => table.theme.player(player.index()),
+                    None => Style::new().add_modifier(Modifier::DIM),
+                };
+                if vanishing == Some(pos) && faded(table) {
+                    style = style.add_modifier(Modifier::DIM);
+                }
+                if line.is_some_and(|line| line.contains(&pos)) {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                if self.cursor.pos == pos && !over {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                let text = match mark {
+                    Some(player) => format!(" {player} "),
+                    None => " · ".to_owned(),
+                };
+                spans.push(Span::styled(text, style));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+
+    fn legend(&self, table: &Table<Self>) -> Vec<(String, &'static str)> {
+        let keys = &table.settings.keys;
+        let moves = [Action::Left, Action::Up, Action::Down, Action::Right];
+        vec![
+            (keys.symbols(&moves), tr("move")),
+            (keys.symbols(&[Action::Place]), tr("place")),
+        ]
+    }
+
+    fn status(&self, table: &Table<Self>, status: StatusBar) -> StatusBar {
+        let status = table.turn(status);
+        match table.position.vanishing() {
+            Some(pos) if !table.over() => status
+                .part(trf("{} vanishes next", &[&square_name(pos)]))
+                .part(trf("draw in {} moves", &[&table.position.moves_left()])),
+            _ => status,
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Which key does what during a game, and how keys are written in the settings file.
+//!
+//! Keys come from a preset, chosen with `keys` in the settings file, and `key_<action>` settings
+//! override the preset one action at a time.
+use crossterm::event::KeyCode;
+
+use crate::locale::tr;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Left,
+    Right,
+    Up,
+    Down,
+    Place,
+    Cancel,
+    Analysis,
+    Review,
+    Yes,
+    No,
+    Debug,
+    Comment,
+    Share,
+}
+
+impl Action {
+    pub const ALL: [Self; 14] = [
+        Self::Quit,
+        Self::Left,
+        Self::Right,
+        Self::Up,
+        Self::Down,
+        Self::Place,
+        Self::Cancel,
+        Self::Analysis,
+        Self::Review,
+        Self::Yes,
+        Self::No,
+        Self::Debug,
+        Self::Comment,
+        Self::Share,
+    ];
+
+    /// Used in the settings file, as `key_<name>`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Place => "place",
+            Self::Cancel => "cancel",
+            Self::Analysis => "analysis",
+            Self::Review => "review",
+            Self::Yes => "yes",
+            Self::No => "no",
+            Self::Debug => "debug",
+            Self::Comment => "comment",
+            Self::Share => "share",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// What the action does, in the current language
+    pub fn description(self) -> &'static str {
+        tr(match self {
+            Self::Quit => "Quit",
+            Self::Left => "Move left",
+            Self::Right => "Move right",
+            Self::Up => "Move up",
+            Self::Down => "Move down",
+            Self::Place => "Place a mark",
+            Self::Cancel => "Put a piece back",
+            Self::Analysis => "Show analysis",
+            Self::Review => "Review the game",
+            Self::Yes => "Answer yes",
+            Self::No => "Answer no",
+            Self::Debug => "Show debug info",
+            Self::Comment => "Comment on a move",
+            Self::Share => "Copy the result to share",
+        })
+    }
+
+    /// The key for the action in the default preset
+    fn default_key(self) -> KeyCode {
+        match self {
+            Self::Quit => KeyCode::Char('q'),
+            Self::Left => KeyCode::Left,
+            Self::Right => KeyCode::Right,
+            Self::Up => KeyCode::Up,
+            Self::Down => KeyCode::Down,
+            Self::Place => KeyCode::Char(' '),
+            Self::Cancel => KeyCode::Esc,
+            Self::Analysis => KeyCode::Char('a'),
+            Self::Review => KeyCode::Char('r'),
+            Self::Yes => KeyCode::Char('y'),
+            Self::No => KeyCode::Char('n'),
+            Self::Debug => KeyCode::F(12),
+            Self::Comment => KeyCode::Char('c'),
+            Self::Share => KeyCode::Char('s'),
+        }
+    }
+}
+
+/// A whole set of keys to start from
+#[derive(Clone, Copy, PartialEq)]
+pub enum Preset {
+    /// Arrow keys to move
+    Default,
+    /// `h`, `j`, `k` and `l` to move
+    Vim,
+    /// Everything under the left hand, with `w`, `a`, `s` and `d` to move
+    LeftHanded,
+}
+
+impl Preset {
+    pub const ALL: [Self; 3] = [Self::Default, Self::Vim, Self::LeftHanded];
+
+    /// Used in the settings file
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Vim => "vim",
+            Self::LeftHanded => "left-handed",
+        }
+    }
+
+    pub fn from_str(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.name() == name)
+    }
+
+    /// The preset after this one in `ALL`, going round to the start
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|&preset| preset == self).unwrap();
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    fn key(self, action: Action) -> KeyCode {
+        match (self, action) {
+            (Self::Vim, Action::Left) => KeyCode::Char('h'),
+            (Self::Vim, Action::Down) => KeyCode::Char('j'),
+            (Self::Vim, Action::Up) => KeyCode::Char('k'),
+            (Self::Vim, Action::Right) => KeyCode::Char('l'),
+            (Self::LeftHanded, Action::Up) => KeyCode::Char('w'),
+            (Self::LeftHanded, Action::Left) => KeyCode::Char('a'),
+            (Self::LeftHanded, Action::Down) => KeyCode::Char('s'),
+            (Self::LeftHanded, Action::Right) => KeyCode::Char('d'),
+            // `a` is taken by moving left
+            (Self::LeftHanded, Action::Analysis) => KeyCode::Char('f'),
+            (Self::LeftHanded, Action::Yes) => KeyCode::Char('e'),
+            (Self::LeftHanded, Action::No) => KeyCode::Char('x'),
+            (Self::LeftHanded, Action::Debug) => KeyCode::F(1),
+            // and `s` by moving down
+            (Self::LeftHanded, Action::Share) => KeyCode::Char('v'),
+            _ => action.default_key(),
+        }
+    }
+}
+
+/// The key bound to each action
+#[derive(Clone)]
+pub struct Keymap {
+    pub preset: Preset,
+    /// Keys the player has chosen themselves, in place of the preset's
+    overrides: [Option<KeyCode>; Action::ALL.len()],
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            preset: Preset::Default,
+            overrides: [None; Action::ALL.len()],
+        }
+    }
+}
+
+impl Keymap {
+    pub fn key(&self, action: Action) -> KeyCode {
+        self.overrides[action as usize].unwrap_or(self.preset.key(action))
+    }
+
+    /// The action `code` is bound to, if any
+    pub fn action(&self, code: KeyCode) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|action| self.key(*action) == code)
+    }
+
+    /// Bind `action` to `code`, even if another action already uses it
+    pub fn set(&mut self, action: Action, code: KeyCode) {
+        self.overrides[action as usize] = (code != self.preset.key(action)).then_some(code);
+    }
+
+    /// Bind `action` to `code`, unless another action already uses it, in which case that action
+    /// is returned and nothing changes
+    pub fn bind(&mut self, action: Action, code: KeyCode) -> Result<(), Action> {
+        match self.action(code) {
+            Some(other) if other != action => Err(other),
+            _ => {
+                self.set(action, code);
+                Ok(())
+            }
+        }
+    }
+
+    /// Two actions bound to the same key, if there are any
+    pub fn conflict(&self) -> Option<(Action, Action)> {
+        Action::ALL.into_iter().find_map(|action| {
+            let first = self.action(self.key(action))?;
+            (first != action).then_some((first, action))
+        })
+    }
+
+    /// Whether `action` is bound to its preset's key
+    pub fn is_default(&self, action: Action) -> bool {
+        self.overrides[action as usize].is_none()
+    }
+
+    /// The name of the key bound to `action`, for showing in hints
+    pub fn name(&self, action: Action) -> String {
+        key_name(self.key(action)).unwrap_or_else(|| "?".to_owned())
+    }
+
+    /// The keys bound to `actions` as briefly as possible, like `←→` or `h/l`, for the key legend
+    pub fn symbols(&self, actions: &[Action]) -> String {
+        let symbols: Vec<_> = actions
+            .iter()
+            .map(|&action| match self.key(action) {
+                KeyCode::Left => "←".to_owned(),
+                KeyCode::Right => "→".to_owned(),
+                KeyCode::Up => "↑".to_owned(),
+                KeyCode::Down => "↓".to_owned(),
+                _ => self.name(action),
+            })
+            .collect();
+        let arrows = symbols
+            .iter()
+            .all(|symbol| "←→↑↓".contains(symbol.as_str()));
+        symbols.join(if arrows { "" } else { "/" })
+    }
+}
+
+/// How `code` is written in the settings file, like `q`, `space` or `left`. `None` for keys that
+/// can't be bound.
+pub fn key_name(code: KeyCode) -> Option<String> {
+    let name = match code {
+        KeyCode::Char(' ') => "space",
+        KeyCode::Char(c) => return Some(c.to_string()),
+        KeyCode::F(n) => return Some(format!("f{n}")),
+        KeyCode::Left => "left",
+        KeyCode::Right => "right",
+        KeyCode::Up => "up",
+        KeyCode::Down => "down",
+        KeyCode::Enter => "enter",
+        KeyCode::Esc => "esc",
+        KeyCode::Tab => "tab",
+        KeyCode::BackTab => "backtab",
+        KeyCode::Backspace => "backspace",
+        KeyCode::Delete => "delete",
+        KeyCode::Insert => "insert",
+        KeyCode::Home => "home",
+        KeyCode::End => "end",
+        KeyCode::PageUp => "pageup",
+        KeyCode::PageDown => "pagedown",
+        _ => return None,
+    };
+    Some(name.to_owned())
+}
+
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+        return Some(KeyCode::F(n));
+    }
+    let code = match name {
+        "space" => KeyCode::Char(' '),
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => return None,
+    };
+    Some(code)
+}
@@ -0,0 +1,1018 @@
+use std::{
+    env, fs,
+    io::{self, Write},
+    panic,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use bot::{GameState, Mcts};
+use crossterm::{
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute,
+};
+use ratatui::buffer::Buffer;
+
+#[cfg(any(test, feature = "test-util"))]
+use games_core::testing;
+use games_core::{
+    achievements, annotations, bot, date, dirs,
+    error::{self, GamesError},
+    event_log, export, locale, migrate, player, rating, rng, select, stats, time_control, today,
+    tournament, tutorial,
+};
+
+mod announce;
+mod api;
+mod board_game;
+mod cast;
+mod clipboard;
+mod commands;
+mod crash;
+mod events;
+mod frames;
+mod gravity;
+mod grid;
+mod infinite;
+mod keys;
+mod menu;
+mod notakto;
+mod overlay;
+mod pentago;
+mod plugin;
+mod quantum;
+mod random;
+mod settings;
+mod snapshot;
+mod sos;
+mod sound;
+mod status_bar;
+mod suspend;
+mod theme;
+mod three_mens_morris;
+mod tic_tac_toe;
+
+/// Most boards `--simul` plays at once, each with its own computer player thinking
+const MAX_SIMUL_BOARDS: usize = 9;
+/// The port `serve-api` listens on, unless it's given another
+const DEFAULT_API_PORT: u16 = 8080;
+/// How long each move is shown for in a replay saved with `--export-cast`
+const CAST_INTERVAL: Duration = Duration::from_millis(1500);
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        // Ctrl+C, or a signal: not worth an error message
+        Err(e) if e.is_interrupted() => ExitCode::from(130),
+        Err(e) => {
+            eprintln!("games: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Everything `main` does, with any error left for it to print once the terminal is back to
+/// normal
+fn run() -> Result<(), GamesError> {
+    let mut args = env::args().skip(1).peekable();
+    if args.next_if_eq("perft").is_some() {
+        let game = args.next().as_deref().and_then(Game::from_str);
+        let depth = args.next().map(|depth| depth.parse());
+        match (game, depth, args.next()) {
+            (Some(game), None, None) => perft(game, None),
+            (Some(game), Some(Ok(depth)), None) => perft(game, Some(depth)),
+            _ => {
+                usage();
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    if args.next_if_eq("book").is_some() {
+        let game = args.next().as_deref().and_then(Game::from_str);
+        let numbers: Result<Vec<usize>, _> = args.map(|arg| arg.parse()).collect();
+        let (games, depth) = match numbers.as_deref() {
+            Ok([]) => (100, 4),
+            Ok([games]) => (*games, 4),
+            Ok([games, depth]) => (*games, *depth),
+            _ => (0, 0),
+        };
+        let Some(game) = game.filter(|_| games > 0) else {
+            usage();
+            std::process::exit(1);
+        };
+        print!("{}", build_book(game, games, depth));
+        return Ok(());
+    }
+    if args.next_if_eq("simulate").is_some() {
+        let game = args.next().as_deref().and_then(Game::from_str);
+        let path = args.next().map(PathBuf::from);
+        let mode = match args.next() {
+            Some(arg) => parse_mode(&arg),
+            None => Some(tic_tac_toe::Mode::Normal),
+        };
+        let (Some(game), Some(path), Some(mode), None) = (game, path, mode, args.next()) else {
+            usage();
+            std::process::exit(1);
+        };
+        let events = events::read_recording(&path)?;
+        let settings = settings::Settings::load();
+        locale::set_language(settings.language);
+        let events = events.into_iter().map(|(_, event)| event);
+        match simulate(&settings, game, mode, events) {
+            Some(report) => print!("{report}"),
+            None => {
+                println!("only tic-tac-toe can be simulated");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    if args.next_if_eq("replay").is_some() {
+        let game = args.next_if(|arg| !arg.starts_with("--"));
+        let game = game.map_or(Ok(1), |game| game.parse());
+        let cast = match args.next().as_deref() {
+            Some("--export-cast") => args.next().map(PathBuf::from),
+            _ => None,
+        };
+        let (Ok(game @ 1..), Some(cast), None) = (game, cast, args.next()) else {
+            usage();
+            std::process::exit(1);
+        };
+        export_cast(game, &cast)?;
+        return Ok(());
+    }
+    #[cfg(feature = "test-util")]
+    if args.next_if_eq("check").is_some() {
+        let game = args.next().as_deref().and_then(Game::from_str);
+        let playouts = args.next().map_or_else(
+            || Ok(game.map_or(0, default_playouts)),
+            |playouts| playouts.parse(),
+        );
+        // a different seed each time finds more, and printing it means failures can be repeated
+        let seed = args
+            .next()
+            .map_or_else(|| Ok(rng::Rng::from_time().next_u64()), |seed| seed.parse());
+        let (Some(game), Ok(playouts), Ok(seed), None) = (game, playouts, seed, args.next()) else {
+            usage();
+            std::process::exit(1);
+        };
+        match check_playouts(game, playouts, seed) {
+            Ok(()) => println!("{playouts} random games played without problems"),
+            Err(msg) => {
+                println!("with seed {seed}, {msg}");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    if args.next_if_eq("tournament").is_some() {
+        let game = args.next().as_deref().and_then(Game::from_str);
+        let games = args.next().map_or(Ok(10), |games| games.parse());
+        let (Some(game), Ok(games), None) = (game, games, args.next()) else {
+            usage();
+            std::process::exit(1);
+        };
+        tournament(game, games)?;
+        return Ok(());
+    }
+    if args.next_if_eq("stats").is_some() {
+        let format = match (args.next().as_deref(), args.next().as_deref()) {
+            (Some("export"), Some("--format")) => {
+                args.next().as_deref().and_then(export::Format::from_str)
+            }
+            _ => None,
+        };
+        let path = args.next().map(PathBuf::from);
+        let (Some(format), Some(path), None) = (format, path, args.next()) else {
+            usage();
+            std::process::exit(1);
+        };
+        export_stats(format, &path)?;
+        return Ok(());
+    }
+    if args.next_if_eq("serve-api").is_some() {
+        let port = args
+            .next()
+            .map_or(Ok(DEFAULT_API_PORT), |port| port.parse());
+        let (Ok(port), None) = (port, args.next()) else {
+            usage();
+            std::process::exit(1);
+        };
+        return api::serve(port, settings::Settings::load().bot_budget);
+    }
+    if args.next_if_eq("snapshots").is_some() {
+        let update = match args.next().as_deref() {
+            None => false,
+            Some("--update") => true,
+            Some(_) => {
+                usage();
+                std::process::exit(1);
+            }
+        };
+        if !check_snapshots(update)? {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    // `play` is optional: `./games play tic-tac-toe` and `./games tic-tac-toe` are the same
+    args.next_if_eq("play");
+    let mut random = false;
+    let mut game = match args.next_if(|arg| !arg.starts_with("--")) {
+        // chosen once the settings are loaded
+        Some(name) if name == "random" => {
+            random = true;
+            None
+        }
+        Some(name) => match Game::from_str(&name) {
+            Some(game) => Some(game),
+            None => {
+                println!("game \"{name}\" not found");
+                usage();
+                std::process::exit(1);
+            }
+        },
+        // no game given: show the menu
+        None => None,
+    };
+    let mut mode = tic_tac_toe::Mode::Normal;
+    let mut record = None;
+    let mut replay = None;
+    let mut log = None;
+    let mut handicap = None;
+    let mut random_blocks = false;
+    let mut blitz = None;
+    let mut colors = None;
+    let mut simul = None;
+    let mut by_file = None;
+    let mut side = None;
+    while let Some(arg) = args.next() {
+        if let Some(new_mode) = parse_mode(&arg) {
+            mode = new_mode;
+            continue;
+        }
+        match &*arg {
+            "--event-log" => {
+                let Some(path) = args.next() else {
+                    println!("{arg} needs a file");
+                    usage();
+                    std::process::exit(1);
+                };
+                log = Some(PathBuf::from(path));
+            }
+            "--handicap" => {
+                let Some(value) = args.next() else {
+                    println!("{arg} needs a handicap, like \"x:b2 blocked:a1\"");
+                    usage();
+                    std::process::exit(1);
+                };
+                match tic_tac_toe::parse_handicap(&value) {
+                    Ok(value) => handicap = Some(value),
+                    Err(msg) => {
+                        println!("{msg}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "--random-blocks" => random_blocks = true,
+            "--blitz" => {
+                let control = args.next().map(|time| format!("move {time}"));
+                match control.as_deref().map(time_control::TimeControl::from_str) {
+                    Some(Some(control)) => blitz = Some(control),
+                    _ => {
+                        println!("{arg} needs a time for each move, like \"10s\"");
+                        usage();
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "--colors" => {
+                let Some(value) = args.next() else {
+                    println!("{arg} needs colors for each player, like \"red,blue\"");
+                    usage();
+                    std::process::exit(1);
+                };
+                match theme::parse_colors(&value) {
+                    Ok(value) => colors = Some(value),
+                    Err(msg) => {
+                        println!("{msg}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "--simul" => match args.next().map(|boards| boards.parse()) {
+                Some(Ok(boards @ 1..=MAX_SIMUL_BOARDS)) => simul = Some(boards),
+                _ => {
+                    println!("{arg} needs a number of boards, up to {MAX_SIMUL_BOARDS}");
+                    usage();
+                    std::process::exit(1);
+                }
+            },
+            "--play-by-file" => {
+                let Some(path) = args.next() else {
+                    println!("{arg} needs a file");
+                    usage();
+                    std::process::exit(1);
+                };
+                by_file = Some(PathBuf::from(path));
+            }
+            "--as" => {
+                let Some(value) = args.next() else {
+                    println!("{arg} needs a side, x or o");
+                    usage();
+                    std::process::exit(1);
+                };
+                side = Some(value);
+            }
+            "--record-input" | "--replay-input" => {
+                let Some(path) = args.next() else {
+                    println!("{arg} needs a file");
+                    usage();
+                    std::process::exit(1);
+                };
+                if arg == "--record-input" {
+                    record = Some(PathBuf::from(path));
+                } else {
+                    replay = Some(PathBuf::from(path));
+                }
+            }
+            _ => {
+                println!("unknown option \"{arg}\"");
+                usage();
+                std::process::exit(1);
+            }
+        }
+    }
+    if record.is_some() && replay.is_some() {
+        println!("can't record and replay input at the same time");
+        std::process::exit(1);
+    }
+    match (by_file, side) {
+        (Some(path), side) => {
+            match tic_tac_toe::Correspondence::open(path, side.as_deref().unwrap_or("x")) {
+                Ok(correspondence) => mode = tic_tac_toe::Mode::Correspondence(correspondence),
+                Err(msg) => {
+                    println!("{msg}");
+                    std::process::exit(1);
+                }
+            }
+            // only tic-tac-toe can be played this way
+            game = Some(Game::TicTacToe);
+        }
+        (None, Some(_)) => {
+            println!("--as only goes with --play-by-file");
+            std::process::exit(1);
+        }
+        (None, None) => (),
+    }
+    // only tic-tac-toe has boards to handicap, a clock, or moves to log
+    let tic_tac_toe_options: Vec<_> = [
+        ("--simul", simul.is_some()),
+        ("--handicap", handicap.is_some()),
+        ("--random-blocks", random_blocks),
+        ("--blitz", blitz.is_some()),
+        ("--event-log", log.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(option, given)| given.then_some(option))
+    .collect();
+    let mut settings = settings::Settings::load();
+    if let Some(handicap) = handicap {
+        settings.handicap = handicap;
+    }
+    settings.random_blocks |= random_blocks;
+    if let Some(blitz) = blitz {
+        settings.time_control = blitz;
+    }
+    if let Some(colors) = colors {
+        settings.player_colors = colors;
+    }
+    locale::set_language(settings.language);
+    let plugins = plugin::discover();
+    let mut plugin = None;
+    if random {
+        match random::choose(&settings, &plugins) {
+            random::Choice::Game(chosen) => game = Some(chosen),
+            random::Choice::Plugin(chosen) => plugin = Some(chosen),
+        }
+    }
+    if let Some(
+        game @ (Game::ThreeMensMorris
+        | Game::Quantum
+        | Game::Notakto
+        | Game::Sos
+        | Game::Pentago
+        | Game::Gravity
+        | Game::Infinite),
+    ) = game
+    {
+        if let Some(option) = tic_tac_toe_options.first() {
+            println!("{option} is only for tic-tac-toe");
+            std::process::exit(1);
+        }
+        if !matches!(
+            mode,
+            tic_tac_toe::Mode::Normal | tic_tac_toe::Mode::VsComputer
+        ) {
+            println!(
+                "{} is only for two players, or against --computer",
+                game.name()
+            );
+            std::process::exit(1);
+        }
+    }
+    if let Some(path) = &replay {
+        events::replay_from(path)?;
+    }
+    if let Some(path) = &record {
+        events::record_to(path)?;
+    }
+    if let Some(path) = &log {
+        event_log::open(path).map_err(|source| GamesError::File {
+            path: path.clone(),
+            source,
+        })?;
+    }
+    events::catch_signals()?;
+    push_title()?;
+    let mut terminal = ratatui::init();
+    install_panic_hook();
+    // most terminals send held keys as repeated presses, but ones that support this say they're
+    // held instead. The others ignore it.
+    let _ = execute!(
+        io::stdout(),
+        PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+    );
+    execute!(io::stdout(), EnableMouseCapture)?;
+    let result = match (game, plugin, simul) {
+        // other games were turned away above
+        (None | Some(Game::TicTacToe), _, Some(boards)) => {
+            tic_tac_toe::run_simul(&mut terminal, &settings, boards).map_err(GamesError::from)
+        }
+        (Some(game), _, _) => play(&mut terminal, game, mode, &settings)
+            .map_err(GamesError::from)
+            .and_then(|suspended| menu_if_suspended(&mut terminal, &settings, suspended)),
+        (None, Some(plugin), None) => plugin::run(&mut terminal, &settings, plugin)
+            .map(|suspended| suspended.map(suspend::Suspended::Plugin))
+            .and_then(|suspended| menu_if_suspended(&mut terminal, &settings, suspended)),
+        (None, None, None) => menu::run(&mut terminal, &settings, vec![]).map_err(GamesError::from),
+    };
+    restore_input();
+    ratatui::restore();
+    pop_title()?;
+    result
+}
+
+/// The mode chosen by an option like `--tutorial`
+fn parse_mode(arg: &str) -> Option<tic_tac_toe::Mode> {
+    match arg {
+        "--tutorial" => Some(tic_tac_toe::Mode::Tutorial),
+        "--daily" => Some(tic_tac_toe::Mode::DailyPuzzle),
+        "--computer" => Some(tic_tac_toe::Mode::VsComputer),
+        "--demo" => Some(tic_tac_toe::Mode::Demo),
+        _ => None,
+    }
+}
+
+/// Undo the input modes turned on after `ratatui::init`, which `ratatui::restore` doesn't know
+/// about
+fn restore_input() {
+    let _ = execute!(io::stdout(), DisableMouseCapture);
+    let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+}
+
+/// On top of `ratatui::init`'s hook, which restores the terminal before printing the panic, put
+/// the input back to normal and save a crash report
+fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_input();
+        previous(info);
+        match crash::write_report(info) {
+            Ok(path) => eprintln!("a crash report was saved to {}", path.display()),
+            Err(e) => eprintln!("could not save a crash report: {e}"),
+        }
+    }));
+}
+
+/// Play `game` until the players quit, or put it aside
+fn play(
+    terminal: &mut ratatui::DefaultTerminal,
+    game: Game,
+    mode: tic_tac_toe::Mode,
+    settings: &settings::Settings,
+) -> io::Result<Option<suspend::Suspended>> {
+    Ok(match game {
+        Game::TicTacToe => {
+            tic_tac_toe::run(terminal, settings, mode)?.map(suspend::Suspended::TicTacToe)
+        }
+        Game::ThreeMensMorris => {
+            board_game::run::<three_mens_morris::Morris>(terminal, settings, &mode)?;
+            None
+        }
+        Game::Quantum => {
+            board_game::run::<quantum::Quantum>(terminal, settings, &mode)?;
+            None
+        }
+        Game::Notakto => {
+            board_game::run::<notakto::Notakto>(terminal, settings, &mode)?;
+            None
+        }
+        Game::Sos => {
+            board_game::run::<sos::Sos>(terminal, settings, &mode)?;
+            None
+        }
+        Game::Pentago => {
+            board_game::run::<pentago::Pentago>(terminal, settings, &mode)?;
+            None
+        }
+        Game::Gravity => {
+            board_game::run::<gravity::Gravity>(terminal, settings, &mode)?;
+            None
+        }
+        Game::Infinite => {
+            board_game::run::<infinite::Infinite>(terminal, settings, &mode)?;
+            None
+        }
+    })
+}
+
+/// A game started from the command line and put aside goes to the menu, to carry on from there
+fn menu_if_suspended(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &settings::Settings,
+    suspended: Option<suspend::Suspended>,
+) -> Result<(), GamesError> {
+    match suspended {
+        Some(suspended) => Ok(menu::run(terminal, settings, vec![suspended])?),
+        None => Ok(()),
+    }
+}
+
+/// Play `events` without a terminal, then describe the screen and what the game is doing. `None`
+/// if `game` can't be simulated.
+fn simulate(
+    settings: &settings::Settings,
+    game: Game,
+    mode: tic_tac_toe::Mode,
+    events: impl Iterator<Item = Event>,
+) -> Option<String> {
+    match game {
+        Game::TicTacToe => {
+            let mut sim = tic_tac_toe::Simulation::new(settings, mode);
+            for event in events {
+                sim.send(event);
+            }
+            let mut report = snapshot::to_text(sim.screen());
+            report += &format!("state: {}\n", sim.state());
+            if let Some(board) = sim.board() {
+                report += &format!("board:\n{board}\n");
+            }
+            if let Some(winner) = sim.winner() {
+                report += &format!("winner: {winner}\n");
+            }
+            let (x, o) = sim.score();
+            report += &format!("score: X {x} : {o} O\n");
+            if sim.quit() {
+                report += "quit\n";
+            }
+            Some(report)
+        }
+        Game::ThreeMensMorris
+        | Game::Quantum
+        | Game::Notakto
+        | Game::Sos
+        | Game::Pentago
+        | Game::Gravity
+        | Game::Infinite => None,
+    }
+}
+
+/// Step through game `game` of the history, counting back from the latest, and save it to
+/// `path` as an asciicast
+fn export_cast(game: usize, path: &Path) -> Result<(), GamesError> {
+    let settings = settings::Settings::load();
+    locale::set_language(settings.language);
+    let records = stats::load()?;
+    // the same games as the history in the menu: puzzles have no players to replay
+    let games: Vec<_> = records
+        .iter()
+        .rev()
+        .filter(|record| record.players.len() >= 2)
+        .collect();
+    let Some(record) = games.get(game - 1) else {
+        println!("there are only {} games in the history", games.len());
+        std::process::exit(1);
+    };
+    let Some(replay) = tic_tac_toe::Replay::from_record(record) else {
+        println!("that game's moves weren't saved");
+        std::process::exit(1);
+    };
+    let title = format!("{}: {}", record.game, record.players.join(" vs "));
+    let mut sim = tic_tac_toe::Simulation::new(&settings, tic_tac_toe::Mode::Replay(replay));
+    // a replay starts by reviewing the first move, and stays on the last once it's reached
+    let mut frames = vec![sim.screen().clone()];
+    loop {
+        sim.press(settings.keys.key(keys::Action::Right));
+        if frames.last() == Some(sim.screen()) {
+            break;
+        }
+        frames.push(sim.screen().clone());
+    }
+    // and the result to finish
+    sim.press(settings.keys.key(keys::Action::Review));
+    frames.push(sim.screen().clone());
+    let cast = cast::encode(&frames, CAST_INTERVAL, &title);
+    fs::write(path, cast).map_err(|source| GamesError::File {
+        path: path.to_owned(),
+        source,
+    })?;
+    println!("saved {} screens to {}", frames.len(), path.display());
+    Ok(())
+}
+
+/// Save the stats and ratings to `path` in `format`. CSV has the players in a file of their own,
+/// next to `path`.
+fn export_stats(format: export::Format, path: &Path) -> Result<(), GamesError> {
+    let records = stats::load()?;
+    let ratings = rating::Ratings::load()?;
+    let write = |path: &Path, contents: String| {
+        fs::write(path, contents).map_err(|source| GamesError::File {
+            path: path.to_owned(),
+            source,
+        })
+    };
+    match format {
+        export::Format::Csv => {
+            let players = players_path(path);
+            write(path, export::games_csv(&records))?;
+            write(&players, export::players_csv(&ratings))?;
+            println!(
+                "saved {} games to {} and the players to {}",
+                records.len(),
+                path.display(),
+                players.display()
+            );
+        }
+        export::Format::Json => {
+            write(path, export::to_json(&records, &ratings))?;
+            println!("saved {} games to {}", records.len(), path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Where the players go when the games go to `path` as CSV: `stats.csv` becomes
+/// `stats-players.csv`
+fn players_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}-players.{}", extension.to_string_lossy()),
+        None => format!("{stem}-players"),
+    };
+    path.with_file_name(name)
+}
+
+/// Compare every game's snapshots with the saved ones, printing any that differ, or save them
+/// all if `update`. Returns whether they all matched.
+fn check_snapshots(update: bool) -> Result<bool, GamesError> {
+    let mut matched = true;
+    for game in Game::ALL {
+        for (width, height) in snapshot::SIZES {
+            for (name, screen) in game.snapshots(width, height) {
+                let path = snapshot::dir()
+                    .join(game.name())
+                    .join(format!("{name}-{width}x{height}.txt"));
+                let text = snapshot::to_text(&screen);
+                let same =
+                    snapshot::check(&path, &text, update).map_err(|source| GamesError::File {
+                        path: path.clone(),
+                        source,
+                    })?;
+                if !same {
+                    println!("{} differs, now:\n{text}", path.display());
+                    matched = false;
+                }
+            }
+        }
+    }
+    Ok(matched)
+}
+
+/// How many random games `check` plays when it isn't told, a few seconds' worth
+#[cfg(feature = "test-util")]
+fn default_playouts(game: Game) -> usize {
+    match game {
+        // each position has hundreds of moves, and each of those is checked
+        Game::Pentago => 20,
+        Game::Sos => 200,
+        Game::TicTacToe
+        | Game::ThreeMensMorris
+        | Game::Quantum
+        | Game::Notakto
+        | Game::Gravity
+        | Game::Infinite => 1000,
+    }
+}
+
+/// Play `playouts` random games of `game`, the same ones for the same `seed`, checking the rules
+/// in every position
+#[cfg(any(test, feature = "test-util"))]
+fn check_playouts(game: Game, playouts: usize, seed: u64) -> Result<(), String> {
+    match game {
+        Game::TicTacToe => testing::check_playouts(&tic_tac_toe::Position::new(), playouts, seed),
+        Game::ThreeMensMorris => {
+            testing::check_playouts(&three_mens_morris::Position::new(), playouts, seed)
+        }
+        Game::Quantum => testing::check_playouts(&quantum::Position::new(), playouts, seed),
+        Game::Notakto => testing::check_playouts(&notakto::Position::new(), playouts, seed),
+        Game::Pentago => testing::check_playouts(&pentago::Position::new(), playouts, seed),
+        Game::Gravity => testing::check_playouts(&gravity_start(), playouts, seed),
+        Game::Infinite => testing::check_playouts(&infinite::Position::new(), playouts, seed),
+        Game::Sos => testing::check_playouts(&sos_start(), playouts, seed),
+    }
+}
+
+/// An empty SOS grid, the size in the settings
+fn sos_start() -> sos::Position {
+    let (width, height) = settings::Settings::load().sos_size;
+    sos::Position::new(width, height)
+}
+
+/// An empty gravity tic-tac-toe board, the size in the settings
+fn gravity_start() -> gravity::Position {
+    gravity::Position::new(settings::Settings::load().gravity_size)
+}
+
+/// Print the perft count for each depth up to `max_depth`, or until the game can't go any further
+fn perft(game: Game, max_depth: Option<usize>) {
+    match game {
+        Game::TicTacToe => print_perft(&tic_tac_toe::Position::new(), max_depth),
+        Game::ThreeMensMorris => print_perft(&three_mens_morris::Position::new(), max_depth),
+        Game::Quantum => print_perft(&quantum::Position::new(), max_depth),
+        Game::Notakto => print_perft(&notakto::Position::new(), max_depth),
+        Game::Sos => print_perft(&sos_start(), max_depth),
+        Game::Pentago => print_perft(&pentago::Position::new(), max_depth),
+        Game::Gravity => print_perft(&gravity_start(), max_depth),
+        Game::Infinite => print_perft(&infinite::Position::new(), max_depth),
+    }
+}
+
+fn print_perft(start: &impl GameState, max_depth: Option<usize>) {
+    for depth in 1.. {
+        if max_depth.is_some_and(|max| depth > max) {
+            break;
+        }
+        let started = Instant::now();
+        let count = start.perft(depth);
+        if count == 0 {
+            break;
+        }
+        println!(
+            "depth {depth}: {count} ({:.3}s)",
+            started.elapsed().as_secs_f64()
+        );
+    }
+}
+
+/// An opening book from self-play, using the bot settings
+fn build_book(game: Game, games: usize, depth: usize) -> String {
+    let mut bot = Mcts::new(settings::Settings::load().bot_budget);
+    match game {
+        Game::TicTacToe => {
+            let start = tic_tac_toe::Position::new();
+            bot::book::build(&start, &mut bot, games, depth)
+        }
+        Game::ThreeMensMorris => {
+            let start = three_mens_morris::Position::new();
+            bot::book::build(&start, &mut bot, games, depth)
+        }
+        Game::Quantum => bot::book::build(&quantum::Position::new(), &mut bot, games, depth),
+        Game::Notakto => bot::book::build(&notakto::Position::new(), &mut bot, games, depth),
+        Game::Sos => bot::book::build(&sos_start(), &mut bot, games, depth),
+        Game::Pentago => bot::book::build(&pentago::Position::new(), &mut bot, games, depth),
+        Game::Gravity => bot::book::build(&gravity_start(), &mut bot, games, depth),
+        Game::Infinite => bot::book::build(&infinite::Position::new(), &mut bot, games, depth),
+    }
+}
+
+/// Play the computer players against each other, record the games, and print the ratings
+fn tournament(game: Game, games: usize) -> Result<(), GamesError> {
+    let settings = settings::Settings::load();
+    let records = match game {
+        Game::TicTacToe => tic_tac_toe::tournament(&settings, games),
+        Game::ThreeMensMorris
+        | Game::Quantum
+        | Game::Notakto
+        | Game::Sos
+        | Game::Pentago
+        | Game::Gravity
+        | Game::Infinite => {
+            println!("tournaments are only for tic-tac-toe");
+            std::process::exit(1);
+        }
+    };
+    for record in &records {
+        stats::record(record)?;
+        rating::record(record)?;
+    }
+    println!("{} games played", records.len());
+    for (name, rating) in rating::Ratings::load()?.leaderboard(game.name()) {
+        println!(
+            "{:>6.0}  {name} ({} won, {} drawn, {} lost)",
+            rating.elo, rating.wins, rating.draws, rating.losses
+        );
+    }
+    Ok(())
+}
+
+/// Save the terminal's title on the xterm title stack, so it can be restored on exit.
+///
+/// Terminals that don't support the title stack ignore this.
+fn push_title() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x1b[22;0t")?;
+    stdout.flush()
+}
+
+/// Restore the title saved by `push_title`.
+fn pop_title() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x1b[23;0t")?;
+    stdout.flush()
+}
+
+#[derive(Clone, Copy)]
+enum Game {
+    TicTacToe,
+    ThreeMensMorris,
+    Quantum,
+    Notakto,
+    Sos,
+    Pentago,
+    Gravity,
+    Infinite,
+}
+
+impl Game {
+    const ALL: [Self; 8] = [
+        Self::TicTacToe,
+        Self::ThreeMensMorris,
+        Self::Quantum,
+        Self::Notakto,
+        Self::Sos,
+        Self::Pentago,
+        Self::Gravity,
+        Self::Infinite,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::TicTacToe => "tic-tac-toe",
+            Self::ThreeMensMorris => "three-mens-morris",
+            Self::Quantum => "quantum-tic-tac-toe",
+            Self::Notakto => "notakto",
+            Self::Sos => "sos",
+            Self::Pentago => "pentago",
+            Self::Gravity => "gravity-tic-tac-toe",
+            Self::Infinite => "infinite-tic-tac-toe",
+        }
+    }
+
+    fn from_str(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|game| game.name() == name)
+    }
+
+    /// The game drawn in each state worth checking, by name
+    fn snapshots(&self, width: u16, height: u16) -> Vec<(&'static str, Buffer)> {
+        match self {
+            Self::TicTacToe => tic_tac_toe::snapshots(width, height),
+            Self::ThreeMensMorris => three_mens_morris::snapshots(width, height),
+            Self::Quantum => quantum::snapshots(width, height),
+            Self::Notakto => notakto::snapshots(width, height),
+            Self::Sos => sos::snapshots(width, height),
+            Self::Pentago => pentago::snapshots(width, height),
+            Self::Gravity => gravity::snapshots(width, height),
+            Self::Infinite => infinite::snapshots(width, height),
+        }
+    }
+}
+
+fn usage() {
+    println!(
+        "usage: ./games [[play] <game | random> [--tutorial | --daily | --computer | --demo]] \
+         [<options>]"
+    );
+    println!("       ./games perft <game> [<depth>]");
+    println!("       ./games book <game> [<games> [<depth>]]");
+    println!("       ./games simulate <game> <file> [--tutorial | --daily | --computer | --demo]");
+    println!("       ./games replay [<n>] --export-cast <file>");
+    println!("       ./games tournament <game> [<games>]");
+    println!("       ./games stats export --format <csv | json> <file>");
+    println!("       ./games serve-api [<port>]");
+    println!("       ./games snapshots [--update]");
+    #[cfg(feature = "test-util")]
+    println!("       ./games check <game> [<games> [<seed>]]");
+    println!(
+        r#"where <game> is one of ("tic-tac-toe" | "three-mens-morris" | "quantum-tic-tac-toe" |"#
+    );
+    println!(
+        r#"  "notakto" | "sos" | "pentago" | "gravity-tic-tac-toe" | "infinite-tic-tac-toe")"#
+    );
+    println!("  --tutorial    learn the controls and some strategy");
+    println!("  --daily       solve today's puzzle");
+    println!("  --computer    play against the computer");
+    println!("  --demo        watch a game play itself, until a key is pressed");
+    println!("with no <game>, a menu is shown, including any games from plugins/ in the data");
+    println!("  directory. random plays one of them, more likely one not played recently");
+    println!("<options> are:");
+    println!("  --record-input <file>  save every key press to <file>, to reproduce a bug");
+    println!("  --replay-input <file>  play back the key presses saved in <file>");
+    println!("  --event-log <file>     write moves and results to <file>, as JSON lines");
+    println!("  --simul <boards>       play the computer on several boards at once, with tab to");
+    println!("                         move between them");
+    println!("  --handicap <setup>     start each game with marks or blocked squares, like");
+    println!(r#"                         "x:b2 o:a1 blocked:c3""#);
+    println!("  --random-blocks        block 1 to 3 squares at random at the start of each game");
+    println!("  --blitz <time>         each move must be made within <time>, like \"10s\"");
+    println!("  --colors <colors>      each player's color, in turn order, like \"red,#00aaff\"");
+    println!("  --play-by-file <file>  play someone else through <file>, in a folder you both");
+    println!("                         sync, each adding your moves to it in turn");
+    println!("  --as <x | o>           the side to play with --play-by-file (default x)");
+    println!("simulate plays back <file> from --record-input without a terminal, then prints the");
+    println!("  screen and the state of the game");
+    println!("replay steps through the <n>th most recent game in the history (default 1) and");
+    println!("  saves it to <file> as an asciicast, which asciinema can play or share");
+    println!("tournament plays the kinds of computer player against each other, <games> times");
+    println!("  each way round (default 10), and records the results for their ratings");
+    println!("stats export saves every game in the history, and each player's rating and");
+    println!("  results, to <file>. As CSV the players go in a file of their own, <file> with");
+    println!("  -players added to its name");
+    println!("serve-api lets other programs play the games over HTTP, on this computer only,");
+    println!("  at <port> (default {DEFAULT_API_PORT})");
+    println!("snapshots checks how each game is drawn against the screens saved in snapshots/,");
+    println!("  or saves them with --update");
+    #[cfg(feature = "test-util")]
+    println!("check plays <games> random games, checking the rules in each position. By default");
+    println!("  it plays as many as take a few seconds");
+    println!("perft counts the positions at each depth of the game tree, to check the rules");
+    println!("book prints an opening book built from <games> games of self-play (default 100),");
+    println!("  covering the first <depth> moves (default 4). Save it as books/<game>.txt in the");
+    println!("  data directory for the computer to use it");
+    println!("bot_script in the settings has a program from bots/ in the data directory choose");
+    println!("  the computer's moves. On Linux it can't change or read your files or use the");
+    println!(
+        "  network, but elsewhere it only has limits on its time and memory: only use ones you"
+    );
+    println!("  trust");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    #[test]
+    fn snapshots_match() {
+        assert!(check_snapshots(false).unwrap());
+    }
+
+    #[test]
+    fn simulate_win() {
+        let path = env::temp_dir().join(format!("games-test-{}.txt", process::id()));
+        // past the names, then X plays a1, b2 and c3, with O in between
+        let mut recording = String::new();
+        for key in "enter enter A 1 enter B 1 enter B 2 enter C 1 enter C 3 enter".split(' ') {
+            recording += &format!("0 key {key} press 0\n0 key {key} release 0\n");
+        }
+        fs::write(&path, recording).unwrap();
+        let events = events::read_recording(&path);
+        fs::remove_file(&path).unwrap();
+        let events = events.unwrap().into_iter().map(|(_, event)| event);
+        let settings = settings::Settings::default();
+        let mode = tic_tac_toe::Mode::Normal;
+        let report = simulate(&settings, Game::TicTacToe, mode, events).unwrap();
+        assert!(report.contains("state: done\n"), "{report}");
+        assert!(report.contains("winner: X\n"), "{report}");
+        assert!(report.contains("score: X 1 : 0 O\n"), "{report}");
+    }
+
+    #[test]
+    fn simulate_only_tic_tac_toe() {
+        let settings = settings::Settings::default();
+        let mode = tic_tac_toe::Mode::Normal;
+        assert!(simulate(&settings, Game::Pentago, mode, [].into_iter()).is_none());
+    }
+
+    #[test]
+    fn check_every_game() {
+        for game in Game::ALL {
+            assert_eq!(check_playouts(game, 20, 1), Ok(()), "{}", game.name());
+        }
+    }
+}
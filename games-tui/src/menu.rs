@@ -0,0 +1,784 @@
+//! The main menu, shown when no game is given on the command line.
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    layout::{
+        Constraint::{Fill, Length},
+        Layout, Rect,
+    },
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{block::Block, List, ListState, Padding, Paragraph},
+    Frame,
+};
+
+use crate::{
+    achievements::{Achievement, Achievements},
+    clipboard, date,
+    error::GamesError,
+    events,
+    keys::{self, Action, Keymap},
+    locale::{tr, trf},
+    plugin,
+    random::{self, Choice},
+    rating::Ratings,
+    settings::Settings,
+    stats::{self, Record},
+    suspend::Suspended,
+    tic_tac_toe, today,
+    tournament::{Format, Tournament},
+    Game,
+};
+
+const ITEMS: [&str; 12] = [
+    "Play tic-tac-toe",
+    "Play the computer",
+    "Tournament",
+    "Tutorial",
+    "Daily puzzle",
+    "Puzzle packs",
+    "Key bindings",
+    "Achievements",
+    "Match history",
+    "Leaderboard",
+    "Random game",
+    "Quit",
+];
+
+/// How many days in a row the game of the day has to be played for `Regular`
+const REGULAR_STREAK: usize = 7;
+
+/// Show the menu until it's quit, with a "Continue" item for each of the `suspended` games
+pub fn run(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    mut suspended: Vec<Suspended>,
+) -> std::io::Result<()> {
+    // the key bindings can be changed from here
+    let mut settings = settings.clone();
+    let settings = &mut settings;
+    let plugins = plugin::discover();
+    let mut games: Vec<_> = Game::ALL.iter().map(Game::name).collect();
+    games.extend(plugins.iter().map(|plugin| plugin.name.as_str()));
+    // today's game, the other built-in games and then the plugins' go after the built-in items,
+    // before "Quit"
+    let (items, quit) = ITEMS.split_at(ITEMS.len() - 1);
+    let mut items: Vec<_> = items.iter().map(|item| tr(item).to_owned()).collect();
+    let today_item = items.len();
+    items.push(today_label(&games));
+    // tic-tac-toe has its own items above
+    let others: Vec<_> = Game::ALL
+        .into_iter()
+        .filter(|game| !matches!(game, Game::TicTacToe))
+        .collect();
+    let first_other = items.len();
+    for game in &others {
+        items.push(trf("Play {}", &[&game.name()]));
+    }
+    let first_plugin = items.len();
+    for plugin in &plugins {
+        items.push(trf("Play {}", &[&plugin.name]));
+    }
+    items.push(tr(quit[0]).to_owned());
+    let mut list_state = ListState::default().with_selected(Some(0));
+    // why the last plugin stopped, if it went wrong, or an achievement that was just unlocked
+    let mut error = None;
+    loop {
+        // games put aside go at the top, to carry on with
+        let mut shown: Vec<_> = suspended
+            .iter()
+            .map(|game| trf("Continue {}", &[&game.name()]))
+            .collect();
+        shown.extend(items.iter().cloned());
+        terminal.draw(|frame| draw(&shown, error.as_deref(), &mut list_state, frame))?;
+        let event = match settings.attract_delay {
+            Some(delay) => events::read_timeout(delay)?,
+            None => Some(events::read()?),
+        };
+        let Some(event) = event else {
+            // nobody's here: show what the games look like until they come back
+            attract(terminal, settings, &plugins)?;
+            continue;
+        };
+        let Event::Key(key) = event else {
+            continue;
+        };
+        // holding a key down only repeats moving the selection
+        let moves = matches!(key.code, KeyCode::Up | KeyCode::Down);
+        if !(key.kind == KeyEventKind::Press || key.kind == KeyEventKind::Repeat && moves) {
+            continue;
+        }
+        error = None;
+        let before = suspended.len();
+        match key.code {
+            KeyCode::Up => list_state.select_previous(),
+            KeyCode::Down => list_state.select_next(),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Enter | KeyCode::Char(' ') => match list_state.selected() {
+                Some(idx) if idx < suspended.len() => {
+                    let game = suspended.remove(idx);
+                    error = resume(terminal, game, &mut suspended)?;
+                }
+                selected => match selected.map(|idx| idx - suspended.len()) {
+                    Some(0) => play(
+                        terminal,
+                        Game::TicTacToe,
+                        tic_tac_toe::Mode::Normal,
+                        settings,
+                        &mut suspended,
+                    )?,
+                    Some(1) => play(
+                        terminal,
+                        Game::TicTacToe,
+                        tic_tac_toe::Mode::VsComputer,
+                        settings,
+                        &mut suspended,
+                    )?,
+                    Some(2) => {
+                        if let Some(tournament) = run_tournament_entry(terminal)? {
+                            run_tournament(terminal, settings, tournament)?;
+                        }
+                    }
+                    Some(3) => play(
+                        terminal,
+                        Game::TicTacToe,
+                        tic_tac_toe::Mode::Tutorial,
+                        settings,
+                        &mut suspended,
+                    )?,
+                    Some(4) => play(
+                        terminal,
+                        Game::TicTacToe,
+                        tic_tac_toe::Mode::DailyPuzzle,
+                        settings,
+                        &mut suspended,
+                    )?,
+                    Some(5) => tic_tac_toe::browse_puzzles(terminal, settings)?,
+                    Some(6) => run_keybindings(terminal, &mut settings.keys)?,
+                    Some(7) => run_achievements(terminal)?,
+                    Some(8) => run_history(terminal, settings)?,
+                    Some(9) => run_leaderboard(terminal)?,
+                    Some(10) => match random::choose(settings, &plugins) {
+                        Choice::Game(game) => play(
+                            terminal,
+                            game,
+                            tic_tac_toe::Mode::Normal,
+                            settings,
+                            &mut suspended,
+                        )?,
+                        Choice::Plugin(plugin) => {
+                            error = run_plugin(terminal, settings, plugin, &mut suspended)?
+                        }
+                    },
+                    Some(idx) if idx == today_item => {
+                        error = play_today(terminal, settings, &plugins, &games, &mut suspended)?;
+                        items[today_item] = today_label(&games);
+                    }
+                    Some(idx) if idx >= first_other && idx - first_other < others.len() => play(
+                        terminal,
+                        others[idx - first_other],
+                        tic_tac_toe::Mode::Normal,
+                        settings,
+                        &mut suspended,
+                    )?,
+                    Some(idx) if idx >= first_plugin && idx - first_plugin < plugins.len() => {
+                        let plugin = &plugins[idx - first_plugin];
+                        error = run_plugin(terminal, settings, plugin, &mut suspended)?;
+                    }
+                    _ => return Ok(()),
+                },
+            },
+            _ => (),
+        }
+        // a game that was put aside or finished moves the items below the games put aside
+        if suspended.len() != before {
+            list_state.select(Some(0));
+        }
+    }
+}
+
+/// Play a built-in game, keeping it in `suspended` if it's put aside
+fn play(
+    terminal: &mut ratatui::DefaultTerminal,
+    game: Game,
+    mode: tic_tac_toe::Mode,
+    settings: &Settings,
+    suspended: &mut Vec<Suspended>,
+) -> std::io::Result<()> {
+    suspended.extend(crate::play(terminal, game, mode, settings)?);
+    Ok(())
+}
+
+/// Carry on with a game that was put aside, keeping it in `suspended` if it's put aside again.
+/// Returns why it stopped, if it went wrong.
+fn resume(
+    terminal: &mut ratatui::DefaultTerminal,
+    game: Suspended,
+    suspended: &mut Vec<Suspended>,
+) -> std::io::Result<Option<String>> {
+    match game.resume(terminal) {
+        Ok(game) => {
+            suspended.extend(game);
+            Ok(None)
+        }
+        Err(GamesError::Io(e)) => Err(e),
+        Err(e) => Ok(Some(e.to_string())),
+    }
+}
+
+/// The menu item for the game of the day, with the streak for playing it
+fn today_label(games: &[&str]) -> String {
+    let game = today::game(games).unwrap_or_default();
+    let streak = today::streak(&stats::load().unwrap_or_default(), games);
+    match streak {
+        0 => trf("★ Today's game: {}", &[&game]),
+        _ => trf("★ Today's game: {} (streak: {} days)", &[&game, &streak]),
+    }
+}
+
+/// Play the game of the day, and unlock `Regular` for a week of them. Returns why it stopped if
+/// it went wrong, or the achievement if it was unlocked.
+fn play_today(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    plugins: &[plugin::Plugin],
+    games: &[&str],
+    suspended: &mut Vec<Suspended>,
+) -> std::io::Result<Option<String>> {
+    let Some(name) = today::game(games) else {
+        return Ok(None);
+    };
+    if let Some(game) = Game::from_str(name) {
+        play(
+            terminal,
+            game,
+            tic_tac_toe::Mode::Normal,
+            settings,
+            suspended,
+        )?;
+    } else if let Some(plugin) = plugins.iter().find(|plugin| plugin.name == name) {
+        if let Some(error) = run_plugin(terminal, settings, plugin, suspended)? {
+            return Ok(Some(error));
+        }
+    }
+    let streak = today::streak(&stats::load().unwrap_or_default(), games);
+    if streak >= REGULAR_STREAK && Achievements::load().unlock(Achievement::Regular) {
+        let name = Achievement::Regular.name();
+        return Ok(Some(trf("Achievement unlocked: {}", &[&name])));
+    }
+    Ok(None)
+}
+
+/// Play a plugin's game, keeping it in `suspended` if it's put aside. Returns why it stopped, if
+/// it went wrong.
+fn run_plugin(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    plugin: &plugin::Plugin,
+    suspended: &mut Vec<Suspended>,
+) -> std::io::Result<Option<String>> {
+    match plugin::run(terminal, settings, plugin) {
+        Ok(game) => {
+            suspended.extend(game.map(Suspended::Plugin));
+            Ok(None)
+        }
+        Err(GamesError::Io(e)) => Err(e),
+        Err(e) => Ok(Some(e.to_string())),
+    }
+}
+
+/// Each game playing itself in turn, over and over, until a key is pressed
+fn attract(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    plugins: &[plugin::Plugin],
+) -> std::io::Result<()> {
+    loop {
+        if !tic_tac_toe::attract(terminal, settings)? {
+            return Ok(());
+        }
+        for plugin in plugins {
+            match plugin::attract(terminal, settings, plugin) {
+                Ok(true) => (),
+                Ok(false) => return Ok(()),
+                Err(GamesError::Io(e)) => return Err(e),
+                // one that doesn't work is left out
+                Err(_) => (),
+            }
+        }
+    }
+}
+
+fn draw(items: &[String], error: Option<&str>, list_state: &mut ListState, frame: &mut Frame) {
+    // as wide as the longest item, in whichever language
+    let longest = items.iter().map(|item| item.chars().count()).max();
+    let width = (longest.unwrap_or(0) as u16 + 8).max(30);
+    // wide enough for the error too, if the screen is
+    let width = width.max(error.map_or(0, |error| error.chars().count() as u16 + 4));
+    let area = centered(frame.area(), width, items.len() as u16 + 4);
+    let mut block = Block::bordered()
+        .title(Line::raw(tr("games")).centered())
+        .padding(Padding::uniform(1));
+    if let Some(error) = error {
+        let style = Style::new().add_modifier(Modifier::BOLD);
+        block = block.title_bottom(Line::styled(error, style).centered());
+    }
+    let list = List::new(items.iter().map(String::as_str))
+        .block(block)
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, area, list_state);
+}
+
+/// List every action with its key, and let the player choose one and press a new key for it, or
+/// switch to another preset. Changes are saved straight away.
+fn run_keybindings(
+    terminal: &mut ratatui::DefaultTerminal,
+    keys: &mut Keymap,
+) -> std::io::Result<()> {
+    let mut list_state = ListState::default().with_selected(Some(0));
+    // the action waiting for its new key
+    let mut rebinding: Option<Action> = None;
+    // why the last change didn't work
+    let mut error: Option<String> = None;
+    loop {
+        terminal.draw(|frame| {
+            let hint = match (rebinding, &error) {
+                (Some(action), _) => trf(
+                    "press a key to {}, esc to cancel",
+                    &[&action.description().to_lowercase()],
+                ),
+                (None, Some(error)) => error.clone(),
+                (None, None) => {
+                    tr("enter to change, p for another preset, q to go back").to_owned()
+                }
+            };
+            draw_keybindings(keys, &hint, &mut list_state, frame)
+        })?;
+        let Event::Key(key) = events::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let Some(action) = rebinding.take() else {
+            match key.code {
+                KeyCode::Up => list_state.select_previous(),
+                KeyCode::Down => list_state.select_next(),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Enter | KeyCode::Char(' ') => {
+                    rebinding = list_state.selected().map(|idx| Action::ALL[idx]);
+                    error = None;
+                }
+                KeyCode::Char('p') => {
+                    keys.preset = keys.preset.next();
+                    error = keys.conflict().map(|(first, second)| {
+                        trf(
+                            "{} and {} use the same key",
+                            &[&first.description(), &second.description().to_lowercase()],
+                        )
+                    });
+                    if let Err(e) = Settings::save_keys(keys) {
+                        error = Some(trf("could not save: {}", &[&e]));
+                    }
+                }
+                _ => (),
+            }
+            continue;
+        };
+        error = match (key.code, keys::key_name(key.code)) {
+            (KeyCode::Esc, _) => None,
+            (_, None) => Some(tr("that key can't be used").to_owned()),
+            (code, Some(name)) => match keys.bind(action, code) {
+                Err(other) => Some(trf(
+                    "{} is already used to {}",
+                    &[&name, &other.description().to_lowercase()],
+                )),
+                Ok(()) => Settings::save_keys(keys)
+                    .err()
+                    .map(|e| trf("could not save: {}", &[&e])),
+            },
+        };
+    }
+}
+
+fn draw_keybindings(keys: &Keymap, hint: &str, list_state: &mut ListState, frame: &mut Frame) {
+    let items =
+        Action::ALL.map(|action| format!("{:<16} {}", action.description(), keys.name(action)));
+    let area = centered(frame.area(), 56, items.len() as u16 + 4);
+    let block = Block::bordered()
+        .title(trf("Key bindings ({})", &[&keys.preset.name()]))
+        .title_bottom(Line::raw(hint).centered())
+        .padding(Padding::uniform(1));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, area, list_state);
+}
+
+/// Show every achievement and whether it's been unlocked, until a key is pressed
+fn run_achievements(terminal: &mut ratatui::DefaultTerminal) -> std::io::Result<()> {
+    let achievements = Achievements::load();
+    loop {
+        terminal.draw(|frame| draw_achievements(&achievements, frame))?;
+        if let Event::Key(key) = events::read()? {
+            if key.kind == KeyEventKind::Press {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn draw_achievements(achievements: &Achievements, frame: &mut Frame) {
+    let locked_style = Style::new().add_modifier(Modifier::DIM);
+    let lines: Vec<_> = Achievement::ALL
+        .into_iter()
+        .map(|achievement| {
+            let text = format!("{:<12} {}", achievement.name(), achievement.description());
+            if achievements.is_unlocked(achievement) {
+                Line::raw(format!("✓ {text}"))
+            } else {
+                Line::styled(format!("  {text}"), locked_style)
+            }
+        })
+        .collect();
+    let area = centered(frame.area(), 50, lines.len() as u16 + 4);
+    let block = Block::bordered()
+        .title(tr("Achievements"))
+        .title_bottom(Line::raw(tr("press any key")).centered())
+        .padding(Padding::uniform(1));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// List every recorded game, newest first, and replay whichever is chosen or copy its moves
+fn run_history(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+) -> std::io::Result<()> {
+    let records = stats::load();
+    let games: Vec<_> = match &records {
+        // puzzles have no players, and nothing to replay
+        Ok(records) => records
+            .iter()
+            .rev()
+            .filter(|record| record.players.len() >= 2)
+            .collect(),
+        Err(_) => vec![],
+    };
+    let mut list_state = ListState::default().with_selected(Some(0));
+    let mut message = None;
+    loop {
+        terminal.draw(|frame| {
+            let hint = match (&message, &records) {
+                (Some(message), _) => message,
+                (None, Err(e)) => &trf("could not load games: {}", &[e]),
+                (None, Ok(_)) => tr("enter to replay, c to copy moves, q to go back"),
+            };
+            draw_history(&games, hint, &mut list_state, frame);
+        })?;
+        let Event::Key(key) = events::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let record = list_state.selected().and_then(|idx| games.get(idx));
+        message = None;
+        match key.code {
+            KeyCode::Up => list_state.select_previous(),
+            KeyCode::Down => list_state.select_next(),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let Some(record) = record else {
+                    continue;
+                };
+                match tic_tac_toe::Replay::from_record(record) {
+                    Some(replay) => {
+                        let mode = tic_tac_toe::Mode::Replay(replay);
+                        crate::play(terminal, Game::TicTacToe, mode, settings)?;
+                    }
+                    None => message = Some(tr("that game's moves weren't saved").to_owned()),
+                }
+            }
+            KeyCode::Char('c') => {
+                let Some(record) = record else {
+                    continue;
+                };
+                message = Some(if record.moves.is_empty() {
+                    tr("that game's moves weren't saved").to_owned()
+                } else {
+                    match clipboard::copy(&record.moves.join(" ")) {
+                        Ok(()) => tr("moves copied").to_owned(),
+                        Err(e) => trf("could not copy: {}", &[&e]),
+                    }
+                });
+            }
+            _ => (),
+        }
+    }
+}
+
+fn draw_history(games: &[&Record], hint: &str, list_state: &mut ListState, frame: &mut Frame) {
+    let items = games.iter().map(|record| {
+        let result = match &record.winner {
+            Some(winner) => trf("{} won", &[winner]),
+            None => tr("draw").to_owned(),
+        };
+        format!(
+            "{}  {:<12} {:<32} {result}",
+            date::format(date::day_of(record.finished)),
+            record.game,
+            record.players.join(" v "),
+        )
+    });
+    let block = Block::bordered()
+        .title(tr("Match history"))
+        .title_bottom(Line::raw(hint).centered())
+        .padding(Padding::uniform(1));
+    if games.is_empty() {
+        let para = Paragraph::new(tr("No games played yet")).block(block);
+        frame.render_widget(para, frame.area());
+        return;
+    }
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, frame.area(), list_state);
+}
+
+/// Show everyone's rating and record at each game, best first, until a key is pressed
+fn run_leaderboard(terminal: &mut ratatui::DefaultTerminal) -> std::io::Result<()> {
+    let ratings = Ratings::load();
+    loop {
+        terminal.draw(|frame| draw_leaderboard(&ratings, frame))?;
+        if let Event::Key(key) = events::read()? {
+            if key.kind == KeyEventKind::Press {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn draw_leaderboard(ratings: &std::io::Result<Ratings>, frame: &mut Frame) {
+    let heading_style = Style::new().add_modifier(Modifier::BOLD);
+    let mut lines = vec![];
+    match ratings {
+        Ok(ratings) => {
+            for game in Game::ALL {
+                lines.push(Line::styled(game.name(), heading_style));
+                let players = ratings.leaderboard(game.name());
+                if players.is_empty() {
+                    lines.push(Line::raw(format!("  {}", tr("no games yet"))));
+                }
+                for (rank, (name, rating)) in players.into_iter().enumerate() {
+                    let played = trf("{} played", &[&format!("{:>4}", rating.games())]);
+                    lines.push(Line::raw(format!(
+                        "{:>3}. {name:<22} {:>5.0}  {played}  {}-{}-{}",
+                        rank + 1,
+                        rating.elo,
+                        rating.wins,
+                        rating.draws,
+                        rating.losses
+                    )));
+                }
+            }
+        }
+        Err(e) => lines.push(Line::raw(trf("could not load ratings: {}", &[e]))),
+    }
+    let height = (lines.len() as u16 + 4).min(frame.area().height);
+    let area = centered(frame.area(), 64, height);
+    let block = Block::bordered()
+        .title(tr("Leaderboard"))
+        .title_bottom(Line::raw(tr("won-drawn-lost, press any key")).centered())
+        .padding(Padding::uniform(1));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Enter the players and choose the format of a tournament. `None` if it's abandoned.
+fn run_tournament_entry(
+    terminal: &mut ratatui::DefaultTerminal,
+) -> std::io::Result<Option<Tournament>> {
+    let mut format = Format::RoundRobin;
+    let mut players: Vec<String> = vec![];
+    let mut name = String::new();
+    let mut error = None;
+    loop {
+        terminal.draw(|frame| {
+            draw_tournament_entry(format, &players, &name, error.as_deref(), frame)
+        })?;
+        let Event::Key(key) = events::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        error = None;
+        match key.code {
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Tab => format = format.next(),
+            KeyCode::Char(ch) if name.chars().count() < tic_tac_toe::MAX_NAME_LEN => name.push(ch),
+            // with nothing typed, take the last player back off the list
+            KeyCode::Backspace if name.is_empty() => {
+                players.pop();
+            }
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            KeyCode::Enter => {
+                let trimmed = name.trim();
+                if trimmed.is_empty() {
+                    match Tournament::new(format, players.clone()) {
+                        Ok(tournament) => return Ok(Some(tournament)),
+                        Err(e) => error = Some(e),
+                    }
+                } else if players.iter().any(|player| player == trimmed) {
+                    error = Some(trf("{} is already playing", &[&trimmed]));
+                } else {
+                    players.push(trimmed.to_owned());
+                    name.clear();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn draw_tournament_entry(
+    format: Format,
+    players: &[String],
+    name: &str,
+    error: Option<&str>,
+    frame: &mut Frame,
+) {
+    let mut lines = vec![
+        Line::raw(trf("Format: {} (tab to change)", &[&tr(format.name())])),
+        Line::raw(""),
+    ];
+    for (idx, player) in players.iter().enumerate() {
+        lines.push(Line::raw(format!("{:>2}. {player}", idx + 1)));
+    }
+    lines.push(Line::raw(format!("{:>2}. {name}_", players.len() + 1)));
+    let hint = error.unwrap_or(tr(
+        "enter a name to add it, or nothing to start, esc to go back",
+    ));
+    let height = (lines.len() as u16 + 4).min(frame.area().height);
+    let area = centered(frame.area(), 64, height);
+    let block = Block::bordered()
+        .title(tr("Tournament"))
+        .title_bottom(Line::raw(hint).centered())
+        .padding(Padding::uniform(1));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Show the standings or the bracket, and play each game in turn until the tournament is over or
+/// abandoned
+fn run_tournament(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    mut tournament: Tournament,
+) -> std::io::Result<()> {
+    loop {
+        terminal.draw(|frame| draw_tournament(&tournament, frame))?;
+        let Event::Key(key) = events::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let Some((round, idx)) = tournament.next_game() else {
+            // the final screen goes on any key
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let names = tournament.names(round, idx);
+                // a game stopped before the end is played again
+                if let Some(winner) = tic_tac_toe::play_match(terminal, settings, names)? {
+                    tournament.record(round, idx, winner);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn draw_tournament(tournament: &Tournament, frame: &mut Frame) {
+    let heading_style = Style::new().add_modifier(Modifier::BOLD);
+    let mut lines = vec![];
+    match tournament.format {
+        Format::RoundRobin => {
+            lines.push(Line::styled(tr("Standings"), heading_style));
+            for (rank, standing) in tournament.standings().iter().enumerate() {
+                let points = trf("{} points", &[&format!("{:>4.1}", standing.points())]);
+                lines.push(Line::raw(format!(
+                    "{:>3}. {:<16} {points}  {}-{}-{}",
+                    rank + 1,
+                    tournament.players[standing.player],
+                    standing.wins,
+                    standing.draws,
+                    standing.losses
+                )));
+            }
+        }
+        Format::Knockout => {
+            for (round, pairings) in tournament.rounds.iter().enumerate() {
+                let title = match pairings.len() {
+                    1 => tr("Final").to_owned(),
+                    2 => tr("Semi-finals").to_owned(),
+                    _ => trf("Round {}", &[&(round + 1)]),
+                };
+                lines.push(Line::styled(title, heading_style));
+                for (idx, pairing) in pairings.iter().enumerate() {
+                    let [x, o] = tournament.names(round, idx);
+                    let result = match pairing.result {
+                        Some(Some(winner)) => {
+                            trf("{} goes through", &[&tournament.players[winner]])
+                        }
+                        _ => String::new(),
+                    };
+                    lines.push(Line::raw(format!("  {x:>16} v {o:<16} {result}")));
+                }
+            }
+        }
+    }
+    lines.push(Line::raw(""));
+    let hint = match tournament.next_game() {
+        Some((round, idx)) => {
+            let [x, o] = tournament.names(round, idx);
+            lines.push(Line::raw(trf(
+                "Next, round {}: {} (X) v {} (O)",
+                &[&(round + 1), &x, &o],
+            )));
+            tr("enter to play, q to stop the tournament")
+        }
+        None => {
+            let names: Vec<_> = tournament
+                .winners()
+                .into_iter()
+                .map(|player| &*tournament.players[player])
+                .collect();
+            lines.push(Line::styled(
+                trf("Winner: {}", &[&names.join(tr(" and "))]),
+                heading_style,
+            ));
+            tr("press any key")
+        }
+    };
+    let title = trf("Tournament ({})", &[&tr(tournament.format.name())]);
+    let height = (lines.len() as u16 + 4).min(frame.area().height);
+    let area = centered(frame.area(), 64, height);
+    let block = Block::bordered()
+        .title(title)
+        .title_bottom(Line::raw(hint).centered())
+        .padding(Padding::uniform(1));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// A `width` by `height` rect in the middle of `area`
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [_, area, _] = Layout::vertical([Fill(1), Length(height), Fill(1)]).areas(area);
+    let [_, area, _] = Layout::horizontal([Fill(1), Length(width), Fill(1)]).areas(area);
+    area
+}
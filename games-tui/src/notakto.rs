@@ -0,0 +1,191 @@
+//! Notakto: both players put X on any of three boards, and whoever makes a line on the last live
+//! board loses.
+//!
+//! The boards are drawn side by side, plugin style, with a cursor that moves across all three.
+//! A dead board is dimmed, with its line picked out.
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+pub use games_core::notakto::Position;
+use games_core::{
+    notakto::{Move, BOARDS},
+    tic_tac_toe::board::{parse_square, Player, X},
+};
+
+use crate::{
+    board_game::{self, BoardGame, Screen, Table},
+    bot::GameState,
+    grid::GridCursor,
+    keys::Action,
+    locale::{tr, trf},
+    player::Seat,
+    settings::Settings,
+    status_bar::StatusBar,
+};
+
+/// The space between boards
+const GAP: &str = "   ";
+
+pub struct Notakto {
+    /// Across all the boards, three columns each
+    cursor: GridCursor,
+}
+
+/// Every state worth keeping a snapshot of, by name, drawn at `width` by `height`
+pub fn snapshots(width: u16, height: u16) -> Vec<(&'static str, Buffer)> {
+    // each a press on that board's square. The first player kills boards 1 and 3, and so loses.
+    let states = [
+        ("playing", "1:a1 1:b1 1:c1 2:b2"),
+        ("won", "1:a1 1:b1 1:c1 2:a1 2:b1 2:c1 3:a1 3:b1 3:c1"),
+    ];
+    states
+        .into_iter()
+        .map(|(name, keys)| {
+            let mut screen = Screen::<Notakto>::for_snapshot(&Settings::default());
+            for square in keys.split_whitespace() {
+                let (board, pos) = square.split_once(':').unwrap();
+                let board: usize = board.parse().unwrap();
+                let pos = parse_square(pos).unwrap();
+                screen.game.cursor.pos = pos / 3 * 3 * BOARDS + (board - 1) * 3 + pos % 3;
+                screen.press(Action::Place);
+            }
+            (name, screen.render(width, height))
+        })
+        .collect()
+}
+
+impl Notakto {
+    /// The move on the square under the cursor
+    fn cursor_move(&self) -> Move {
+        let (column, row) = (self.cursor.column(), self.cursor.row());
+        Move {
+            board: column / 3,
+            pos: row * 3 + column % 3,
+        }
+    }
+}
+
+impl BoardGame for Notakto {
+    type Position = Position;
+
+    const NAME: &'static str = "notakto";
+
+    fn title() -> &'static str {
+        tr("Notakto")
+    }
+
+    fn start(_settings: &Settings) -> Position {
+        Position::new()
+    }
+
+    fn new(_settings: &Settings) -> Self {
+        Self {
+            cursor: GridCursor::new(3 * BOARDS, 3),
+        }
+    }
+
+    /// A press plays the square under the cursor
+    fn input(
+        &mut self,
+        table: &Table<Self>,
+        _code: KeyCode,
+        action: Option<Action>,
+    ) -> Result<Option<Move>, String> {
+        if action != Some(Action::Place) {
+            board_game::step(&mut self.cursor, action);
+            return Ok(None);
+        }
+        let mv = self.cursor_move();
+        if table.position.is_dead(mv.board) {
+            Err(tr("That board is dead").to_owned())
+        } else if table.position.legal_moves().contains(&mv) {
+            Ok(Some(mv))
+        } else {
+            Err(tr("Can't play there").to_owned())
+        }
+    }
+
+    fn board(&self, table: &Table<Self>) -> Vec<Line<'static>> {
+        let over = table.over();
+        let letters = [" a  b  c "; BOARDS].join(GAP);
+        let mut lines = vec![Line::raw(format!("   {letters}"))];
+        let cursor = self.cursor_move();
+        for row in 0..3 {
+            let mut spans = vec![Span::raw(format!("{:>2} ", row + 1))];
+            for board in 0..BOARDS {
+                if board > 0 {
+                    spans.push(Span::raw(GAP));
+                }
+                let line = table.position.line(board);
+                for column in 0..3 {
+                    let pos = row * 3 + column;
+                    let mark = table.position.get(board, pos);
+                    let mut style = if mark {
+                        table.theme.player(X.index())
+                    } else {
+                        Style::new()
+                    };
+                    if let Some(line) = line {
+                        style = style.add_modifier(if line.squares().contains(&pos) {
+                            Modifier::BOLD | Modifier::UNDERLINED
+                        } else {
+                            Modifier::DIM
+                        });
+                    } else if !mark {
+                        style = style.add_modifier(Modifier::DIM);
+                    }
+                    if cursor == (Move { board, pos }) && !over {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    let text = if mark { " X " } else { " · " };
+                    spans.push(Span::styled(text, style));
+                }
+            }
+            lines.push(Line::from(spans));
+        }
+        let dead: Vec<_> = (0..BOARDS)
+            .map(|board| {
+                let label = if table.position.is_dead(board) {
+                    tr("dead")
+                } else {
+                    ""
+                };
+                format!("{label:^9}")
+            })
+            .collect();
+        lines.push(Line::styled(
+            format!("   {}", dead.join(GAP)),
+            Style::new().add_modifier(Modifier::DIM),
+        ));
+        lines
+    }
+
+    fn legend(&self, table: &Table<Self>) -> Vec<(String, &'static str)> {
+        let keys = &table.settings.keys;
+        let moves = [Action::Left, Action::Up, Action::Down, Action::Right];
+        vec![
+            (keys.symbols(&moves), tr("move")),
+            (keys.symbols(&[Action::Place]), tr("place")),
+        ]
+    }
+
+    fn status(&self, table: &Table<Self>, status: StatusBar) -> StatusBar {
+        let status = table.turn(status);
+        if table.over() {
+            return status;
+        }
+        let live = (0..BOARDS)
+            .filter(|&board| !table.position.is_dead(board))
+            .count();
+        status.part(trf("{} boards left", &[&live]))
+    }
+
+    /// Everyone plays X, so the players go by their turn
+    fn player_name(player: Player) -> String {
+        format!("Player {}", player.index() + 1)
+    }
+}
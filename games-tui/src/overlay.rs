@@ -0,0 +1,100 @@
+//! The box drawn over a board, in the middle of it: the result of a game, with any lines struck
+//! through the board and the keys for what to do next, or a question like whether to swap sides.
+//! Every game's end screen uses it, rather than drawing its own.
+use ratatui::{
+    layout::{
+        Constraint::{Fill, Length},
+        Layout, Rect,
+    },
+    style::{Modifier, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        canvas::{self, Canvas},
+        Block, Clear, Padding, Paragraph,
+    },
+    Frame,
+};
+
+pub struct Overlay<'a> {
+    title: &'a str,
+    /// Lines through the board, from (0, 0) at its bottom left to (1, 1) at its top right
+    strikes: Vec<canvas::Line>,
+    /// What each key does, like `("enter", "rematch")`
+    buttons: Vec<(String, &'a str)>,
+}
+
+impl<'a> Overlay<'a> {
+    pub fn new(title: &'a str) -> Self {
+        Self {
+            title,
+            strikes: vec![],
+            buttons: vec![],
+        }
+    }
+
+    /// Strike `line` through the board, like the winning row
+    pub fn strike(mut self, line: canvas::Line) -> Self {
+        self.strikes.push(line);
+        self
+    }
+
+    /// Show that `keys` does `what`, under the title
+    pub fn button(mut self, keys: String, what: &'a str) -> Self {
+        self.buttons.push((keys, what));
+        self
+    }
+
+    /// How many rows the box takes
+    pub fn height(&self) -> u16 {
+        self.lines().len() as u16 + 4
+    }
+
+    /// Draw over the board in `area`, with the box in the middle
+    pub fn draw(&self, frame: &mut Frame, area: Rect) {
+        if !self.strikes.is_empty() {
+            let canvas = Canvas::default()
+                .x_bounds([0., 1.])
+                .y_bounds([0., 1.])
+                .marker(Marker::HalfBlock)
+                .paint(|ctx| {
+                    for line in &self.strikes {
+                        ctx.draw(line);
+                    }
+                });
+            frame.render_widget(canvas, area);
+        }
+
+        let lines = self.lines();
+        let widest = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+        let width = (widest + 12).max(20);
+        let [_, area, _] = Layout::vertical([Fill(1), Length(self.height()), Fill(1)]).areas(area);
+        let [_, area, _] = Layout::horizontal([Fill(1), Length(width), Fill(1)]).areas(area);
+        frame.render_widget(Clear, area);
+
+        let block = Block::bordered().padding(Padding::new(5, 5, 1, 1));
+        frame.render_widget(&block, area);
+        frame.render_widget(Paragraph::new(lines), block.inner(area));
+    }
+
+    /// The title, then the buttons on one line
+    fn lines(&self) -> Vec<Line<'_>> {
+        let mut lines = vec![Line::raw(self.title).centered()];
+        if !self.buttons.is_empty() {
+            let mut spans = vec![];
+            for (idx, (keys, what)) in self.buttons.iter().enumerate() {
+                if idx > 0 {
+                    spans.push(Span::raw(" · "));
+                }
+                spans.push(Span::styled(
+                    keys.as_str(),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ));
+                spans.push(Span::raw(format!(" {what}")));
+            }
+            lines.push(Line::raw(""));
+            lines.push(Line::from(spans).centered());
+        }
+        lines
+    }
+}
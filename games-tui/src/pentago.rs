@@ -0,0 +1,309 @@
+//! Pentago: players take turns placing a marble and then turning a quadrant of the board, until
+//! someone has five in a row.
+//!
+//! The board is drawn like a plugin's, with lines between the quadrants. A turn takes three
+//! steps: placing the marble, choosing the quadrant with the arrow keys, and then left or right
+//! for which way it turns. Turning is animated, the marbles around the quadrant's edge sliding
+//! round, unless animations are off.
+use std::time::{Duration, Instant};
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+pub use games_core::pentago::Position;
+use games_core::{
+    pentago::{self, parse_square, Move, Rotation, SIZE},
+    tic_tac_toe::board::Player,
+};
+
+use crate::{
+    board_game::{self, BoardGame, Screen, Table},
+    bot::GameState,
+    grid::GridCursor,
+    keys::Action,
+    locale::tr,
+    player::Seat,
+    settings::Settings,
+    status_bar::StatusBar,
+};
+
+/// How long a quadrant takes to turn
+const TURN_DURATION: Duration = Duration::from_millis(300);
+
+/// How far through their turn the player to move is
+#[derive(Clone, Copy)]
+enum Stage {
+    /// Choosing where their marble goes
+    Place,
+    /// Their marble is on this square, and they're choosing which quadrant to turn
+    Quadrant(usize),
+    /// and then which way to turn it
+    Direction(usize),
+}
+
+/// The quadrant the last move turned, while it's animating
+struct Turning {
+    quadrant: usize,
+    rotation: Rotation,
+    started: Instant,
+}
+
+impl Turning {
+    /// How many squares the marbles around the quadrant's edge still have to go, from 2 when
+    /// it starts to 0 once it's done
+    fn left(&self) -> usize {
+        let progress = self.started.elapsed().as_secs_f64() / TURN_DURATION.as_secs_f64();
+        ((1. - progress.min(1.)) * 2.).ceil() as usize
+    }
+}
+
+pub struct Pentago {
+    cursor: GridCursor,
+    /// The quadrant to turn, while choosing one
+    quadrant: GridCursor,
+    stage: Stage,
+    turning: Option<Turning>,
+}
+
+/// Every state worth keeping a snapshot of, by name, drawn at `width` by `height`
+pub fn snapshots(width: u16, height: u16) -> Vec<(&'static str, Buffer)> {
+    // each a move, or just a square for a marble placed but not turned yet. O only turns the
+    // empty bottom right quadrant, so X's line across the top stays put.
+    const OPENING: &str = "a1/4R a6/4R b1/4R b6/4R";
+    let states = [
+        ("turned", "a1/1R"),
+        ("choosing", &format!("{OPENING} c1") as &str),
+        ("won", &format!("{OPENING} c1/4R c6/4R d1/4R a5/4R e1/4R")),
+    ];
+    states
+        .into_iter()
+        .map(|(name, moves)| {
+            let settings = Settings {
+                animations: false,
+                ..Settings::default()
+            };
+            let mut screen = Screen::<Pentago>::for_snapshot(&settings);
+            for mv in moves.split_whitespace() {
+                let Some(mv) = screen.table.position.parse_move(mv) else {
+                    screen.game.cursor.pos = parse_square(mv).unwrap();
+                    screen.press(Action::Place);
+                    continue;
+                };
+                screen.game.cursor.pos = mv.pos;
+                screen.press(Action::Place);
+                screen.game.quadrant.pos = mv.quadrant;
+                screen.press(Action::Place);
+                screen.press(match mv.rotation {
+                    Rotation::Clockwise => Action::Right,
+                    Rotation::Anticlockwise => Action::Left,
+                });
+            }
+            (name, screen.render(width, height))
+        })
+        .collect()
+}
+
+impl Pentago {
+    /// The marble to draw on `pos`, partway round if its quadrant is still turning
+    fn shown(&self, table: &Table<Self>, pos: usize) -> Option<Player> {
+        let Some(turning) = &self.turning else {
+            return table.position.get(pos);
+        };
+        let ring = pentago::ring(turning.quadrant);
+        let Some(i) = ring.iter().position(|&sq| sq == pos) else {
+            return table.position.get(pos);
+        };
+        let left = match turning.rotation {
+            Rotation::Clockwise => turning.left(),
+            Rotation::Anticlockwise => ring.len() - turning.left(),
+        };
+        table.position.get(ring[(i + left) % ring.len()])
+    }
+}
+
+impl BoardGame for Pentago {
+    type Position = Position;
+
+    const NAME: &'static str = "pentago";
+
+    fn title() -> &'static str {
+        tr("Pentago")
+    }
+
+    fn start(_settings: &Settings) -> Position {
+        Position::new()
+    }
+
+    fn new(_settings: &Settings) -> Self {
+        Self {
+            cursor: GridCursor::new(SIZE, SIZE),
+            quadrant: GridCursor::new(2, 2),
+            stage: Stage::Place,
+            turning: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.stage = Stage::Place;
+        self.turning = None;
+    }
+
+    /// A turn goes from placing the marble under the cursor to choosing the quadrant to turn
+    /// and then which way
+    fn input(
+        &mut self,
+        table: &Table<Self>,
+        _code: KeyCode,
+        action: Option<Action>,
+    ) -> Result<Option<Move>, String> {
+        let (pos, rotation) = match (self.stage, action) {
+            (Stage::Place, Some(Action::Place)) => {
+                let pos = self.cursor.pos;
+                if table.position.get(pos).is_some() {
+                    return Err(tr("Can't play there").to_owned());
+                }
+                // the marble's own quadrant is as good a guess as any
+                self.quadrant.pos = pentago::quadrant(pos);
+                self.stage = Stage::Quadrant(pos);
+                return Ok(None);
+            }
+            (Stage::Place, _) => {
+                board_game::step(&mut self.cursor, action);
+                return Ok(None);
+            }
+            (Stage::Quadrant(pos), Some(Action::Place)) => {
+                self.stage = Stage::Direction(pos);
+                return Ok(None);
+            }
+            (Stage::Quadrant(_), Some(Action::Cancel)) => {
+                self.stage = Stage::Place;
+                return Ok(None);
+            }
+            (Stage::Quadrant(_), _) => {
+                board_game::step(&mut self.quadrant, action);
+                return Ok(None);
+            }
+            (Stage::Direction(pos), Some(Action::Left)) => (pos, Rotation::Anticlockwise),
+            (Stage::Direction(pos), Some(Action::Right)) => (pos, Rotation::Clockwise),
+            (Stage::Direction(pos), Some(Action::Cancel)) => {
+                self.stage = Stage::Quadrant(pos);
+                return Ok(None);
+            }
+            (Stage::Direction(_), _) => return Ok(None),
+        };
+        Ok(Some(Move {
+            pos,
+            quadrant: self.quadrant.pos,
+            rotation,
+        }))
+    }
+
+    fn moved(&mut self, table: &Table<Self>, mv: Move, _before: &Position) -> Option<String> {
+        self.stage = Stage::Place;
+        self.turning = table.settings.animate().then(|| Turning {
+            quadrant: mv.quadrant,
+            rotation: mv.rotation,
+            started: Instant::now(),
+        });
+        None
+    }
+
+    /// The computer waits for the last move to finish turning
+    fn busy(&self, _table: &Table<Self>) -> bool {
+        self.turning
+            .as_ref()
+            .is_some_and(|turning| turning.left() > 0)
+    }
+
+    fn board(&self, table: &Table<Self>) -> Vec<Line<'static>> {
+        let over = table.over();
+        let lines_made: Vec<usize> = table
+            .position
+            .lines()
+            .into_iter()
+            .flat_map(|(line, _)| line)
+            .collect();
+        let (placed, quadrant) = match self.stage {
+            Stage::Place => (None, None),
+            Stage::Quadrant(pos) | Stage::Direction(pos) => (Some(pos), Some(self.quadrant.pos)),
+        };
+        let next = table.position.current_player();
+        let mut lines = vec![Line::raw("    a  b  c   d  e  f ")];
+        for row in 0..SIZE {
+            if row == SIZE / 2 {
+                lines.push(Line::raw("   ─────────┼─────────"));
+            }
+            let mut spans = vec![Span::raw(format!("{:>2} ", row + 1))];
+            for column in 0..SIZE {
+                if column == SIZE / 2 {
+                    spans.push(Span::raw("│"));
+                }
+                let pos = row * SIZE + column;
+                let marble = if placed == Some(pos) {
+                    Some(next)
+                } else {
+                    self.shown(table, pos)
+                };
+                let mut style = match marble {
+                    Some(player) => table.theme.player(player.index()),
+                    None => Style::new().add_modifier(Modifier::DIM),
+                };
+                if placed == Some(pos) || (over && lines_made.contains(&pos)) {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                let highlighted = match quadrant {
+                    Some(quadrant) => pentago::quadrant(pos) == quadrant,
+                    None => self.cursor.pos == pos && !over,
+                };
+                if highlighted {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                let text = match marble {
+                    Some(player) => format!(" {player} "),
+                    None => " · ".to_owned(),
+                };
+                spans.push(Span::styled(text, style));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+
+    fn legend(&self, table: &Table<Self>) -> Vec<(String, &'static str)> {
+        let keys = &table.settings.keys;
+        let key = |action| keys.symbols(&[action]);
+        let moves = keys.symbols(&[Action::Left, Action::Up, Action::Down, Action::Right]);
+        match self.stage {
+            Stage::Place => vec![(moves, tr("move")), (key(Action::Place), tr("place"))],
+            Stage::Quadrant(_) => vec![
+                (moves, tr("move")),
+                (key(Action::Place), tr("turn")),
+                (key(Action::Cancel), tr("put back")),
+            ],
+            Stage::Direction(_) => vec![
+                (key(Action::Left), tr("anticlockwise")),
+                (key(Action::Right), tr("clockwise")),
+                (key(Action::Cancel), tr("back")),
+            ],
+        }
+    }
+
+    fn status(&self, table: &Table<Self>, status: StatusBar) -> StatusBar {
+        let status = table.turn(status);
+        if table.over() {
+            return status;
+        }
+        status.part(
+            match self.stage {
+                Stage::Place => tr("place a marble"),
+                Stage::Quadrant(_) => tr("choose a quadrant"),
+                Stage::Direction(_) => tr("which way?"),
+            }
+            .to_owned(),
+        )
+    }
+}
@@ -0,0 +1,380 @@
+//! Playing a game from a plugin: its board as a grid, with a cursor to choose each move. The
+//! plugin knows the rules; this only draws what it says and sends it the moves. Once the game is
+//! over, the result is shown under the board.
+use std::time::{Duration, Instant};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    layout::{
+        Constraint::{Fill, Length},
+        Layout,
+    },
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{block::Block, Padding, Paragraph},
+    Frame,
+};
+
+use games_core::plugin::{self, Position, Session};
+pub use games_core::plugin::{discover, Plugin};
+
+use crate::{
+    bot::GameResult,
+    commands::{CommandLine, Host, Registry},
+    error::GamesError,
+    events,
+    grid::{Direction, GridCursor},
+    keys::Action,
+    locale::{tr, trf},
+    overlay::Overlay,
+    rating,
+    rng::Rng,
+    settings::Settings,
+    stats::{self, Record},
+    status_bar::{self, StatusBar},
+    suspend,
+    theme::{ColorMode, Theme},
+};
+
+/// How wide each square is drawn
+const SQUARE_WIDTH: u16 = 3;
+
+/// How long each random move waits in attract mode, so it can be followed
+const ATTRACT_MOVE_DELAY: Duration = Duration::from_millis(1000);
+
+/// How long the result of a random game is shown in attract mode
+const ATTRACT_RESULT_DELAY: Duration = Duration::from_secs(3);
+
+struct Screen {
+    /// The plugin's name, which is its game's
+    name: String,
+    settings: Settings,
+    theme: Theme,
+    session: Session,
+    moves: Vec<String>,
+    position: Position,
+    cursor: GridCursor,
+    message: Option<String>,
+    /// Games won by player 1 and by player 2
+    scores: [usize; 2],
+    /// Whether the moves are random ones, for the menu's attract mode, rather than the players'
+    attract: bool,
+    /// When this game started
+    started: Instant,
+    /// A command being typed after `:`
+    command_line: CommandLine,
+    /// Set by `:quit`
+    quit: bool,
+}
+
+/// A plugin's game put aside with the suspend key, to carry on with `resume`
+pub struct Suspended {
+    screen: Box<Screen>,
+    /// When it was put aside
+    at: Instant,
+}
+
+impl Suspended {
+    pub fn name(&self) -> &str {
+        &self.screen.name
+    }
+}
+
+/// Play the plugin's game, two players taking turns, until they quit or put it aside
+pub fn run(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    plugin: &Plugin,
+) -> Result<Option<Suspended>, GamesError> {
+    play(terminal, Screen::new(settings, plugin, false)?)
+}
+
+/// Carry on with a game that was put aside, from where it was left
+pub fn resume(
+    terminal: &mut ratatui::DefaultTerminal,
+    suspended: Suspended,
+) -> Result<Option<Suspended>, GamesError> {
+    let Suspended { mut screen, at } = suspended;
+    // the time it was put aside doesn't count towards the game's
+    screen.started += at.elapsed();
+    play(terminal, *screen)
+}
+
+/// The game loop for `run`
+fn play(
+    terminal: &mut ratatui::DefaultTerminal,
+    mut screen: Screen,
+) -> Result<Option<Suspended>, GamesError> {
+    loop {
+        terminal.draw(|frame| screen.draw(frame))?;
+        let Event::Key(key) = events::read()? else {
+            continue;
+        };
+        if suspend::is_suspend_key(&key) {
+            let (screen, at) = (Box::new(screen), Instant::now());
+            return Ok(Some(Suspended { screen, at }));
+        }
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        screen.message = None;
+        if screen.command_line.is_open() {
+            if let Some(line) = screen.command_line.input(key.code) {
+                screen.message = Registry::new().run(&mut screen, &line).unwrap_or_else(Some);
+            }
+            if screen.quit {
+                return Ok(None);
+            }
+            continue;
+        }
+        if key.code == KeyCode::Char(':') {
+            screen.command_line.open();
+            continue;
+        }
+        let cursor = &mut screen.cursor;
+        match screen.settings.keys.action(key.code) {
+            Some(Action::Quit) => return Ok(None),
+            Some(Action::Left) => cursor.step(Direction::Left, false),
+            Some(Action::Right) => cursor.step(Direction::Right, false),
+            Some(Action::Up) => cursor.step(Direction::Up, false),
+            Some(Action::Down) => cursor.step(Direction::Down, false),
+            Some(Action::Place) => screen.place()?,
+            Some(Action::Cancel)
+                if screen.position.result.is_none() && !screen.moves.is_empty() =>
+            {
+                screen.moves.pop();
+                screen.position = screen.session.position(&screen.moves)?;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Play random moves on both sides for one game, for the menu's attract mode. Returns whether
+/// the game got to the end, rather than being stopped by a key.
+pub fn attract(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    plugin: &Plugin,
+) -> Result<bool, GamesError> {
+    let mut screen = Screen::new(settings, plugin, true)?;
+    let mut rng = Rng::from_time();
+    loop {
+        terminal.draw(|frame| screen.draw(frame))?;
+        let delay = match screen.position.result {
+            None => ATTRACT_MOVE_DELAY,
+            Some(_) => ATTRACT_RESULT_DELAY,
+        };
+        match events::read_timeout(delay)? {
+            Some(Event::Key(key)) if key.kind == KeyEventKind::Press => return Ok(false),
+            Some(_) => continue,
+            None if screen.position.result.is_some() => return Ok(true),
+            None => (),
+        }
+        let legal = &screen.position.legal;
+        if legal.is_empty() {
+            return Ok(true);
+        }
+        let name = legal[rng.below(legal.len())].clone();
+        if let Some((column, row)) = plugin::parse_square(&name, screen.session.columns) {
+            screen.cursor.pos = row * screen.session.columns + column;
+        }
+        screen.moves.push(name);
+        screen.position = screen.session.position(&screen.moves)?;
+    }
+}
+
+impl Screen {
+    fn new(settings: &Settings, plugin: &Plugin, attract: bool) -> Result<Self, GamesError> {
+        let mut session = plugin.start()?;
+        let position = session.position(&[])?;
+        let (columns, rows) = (session.columns, session.rows);
+        Ok(Self {
+            settings: settings.clone(),
+            theme: Theme::new(settings),
+            session,
+            moves: vec![],
+            position,
+            cursor: GridCursor::new(columns, rows),
+            message: None,
+            scores: [0; 2],
+            attract,
+            started: Instant::now(),
+            name: plugin.name.clone(),
+            command_line: CommandLine::default(),
+            quit: false,
+        })
+    }
+
+    /// Play the square under the cursor, or start again once the game is over
+    fn place(&mut self) -> Result<(), GamesError> {
+        if self.position.result.is_some() {
+            self.moves.clear();
+            self.started = Instant::now();
+        } else {
+            let name = plugin::square_name(self.cursor.column(), self.cursor.row());
+            if !self.position.legal.contains(&name) {
+                self.message = Some(tr("Can't play there").to_owned());
+                return Ok(());
+            }
+            self.moves.push(name);
+        }
+        self.position = self.session.position(&self.moves)?;
+        if let Some(result) = self.position.result {
+            self.finish(result);
+        }
+        Ok(())
+    }
+
+    /// Count and record the game that just finished
+    fn finish(&mut self, result: GameResult<usize>) {
+        // the saved names are the same in every language, like tic-tac-toe's X and O
+        let player = |index: usize| format!("Player {}", index + 1);
+        let winner = match result {
+            GameResult::Winner(winner) => {
+                self.scores[winner] += 1;
+                Some(player(winner))
+            }
+            GameResult::Draw => None,
+        };
+        let players = vec![player(0), player(1)];
+        let duration = self.started.elapsed();
+        let record = Record::new(&self.name, players, winner, duration, self.moves.clone());
+        // like tic-tac-toe's, the stats aren't worth stopping the game for
+        match stats::record(&record) {
+            Ok(()) => {
+                let _ = rating::record(&record);
+            }
+            Err(e) => self.message = Some(trf("could not save the game: {}", &[&e])),
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let name = self.name.as_str();
+        let (columns, rows) = (self.session.columns, self.session.rows);
+        let mut lines = vec![];
+        let letters: String = (0..columns)
+            .map(|column| format!(" {} ", (b'a' + column as u8) as char))
+            .collect();
+        lines.push(Line::raw(format!("   {letters}")));
+        for row in 0..rows {
+            let mut spans = vec![Span::raw(format!("{:>2} ", row + 1))];
+            for column in 0..columns {
+                let mark = self.position.cells[row * columns + column];
+                let mut style = match mark {
+                    Some(mark) => self.theme.player(self.mark_player(mark)),
+                    None => Style::new().add_modifier(Modifier::DIM),
+                };
+                if self.cursor.pos == row * columns + column && self.position.result.is_none() {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                let text = format!(" {} ", mark.unwrap_or('·'));
+                spans.push(Span::styled(text, style));
+            }
+            lines.push(Line::from(spans));
+        }
+        let player = |index: usize| trf("Player {}", &[&(index + 1)]);
+        let legend = self.legend();
+        let width = (SQUARE_WIDTH * columns as u16 + 3)
+            .max(legend.chars().count() as u16)
+            .max(name.len() as u16)
+            + 4;
+        let result = self.position.result.map(|result| match result {
+            GameResult::Winner(winner) => trf("{} won!", &[&player(winner)]),
+            GameResult::Draw => tr("draw").to_owned(),
+        });
+        let keys = &self.settings.keys;
+        let overlay = result.as_deref().map(|result| {
+            let overlay = Overlay::new(result);
+            if self.attract {
+                return overlay;
+            }
+            overlay
+                .button(keys.symbols(&[Action::Place]), tr("next game"))
+                .button(keys.symbols(&[Action::Quit]), tr("quit"))
+        });
+        // the result goes under the board, to keep the board in sight
+        let height = lines.len() as u16 + 4;
+        let overlay_height = overlay.as_ref().map_or(0, Overlay::height);
+        let layout = Layout::vertical([Fill(1), Length(height), Length(overlay_height), Fill(1)]);
+        let [main_area, status_area] =
+            Layout::vertical([Fill(1), Length(status_bar::HEIGHT)]).areas(frame.area());
+        let [_, area, overlay_area, _] = layout.areas(main_area);
+        let [_, area, _] = Layout::horizontal([Fill(1), Length(width), Fill(1)]).areas(area);
+        let block = Block::bordered()
+            .title(name)
+            .title_bottom(Line::raw(legend).centered())
+            .padding(Padding::uniform(1));
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+        if let Some(overlay) = overlay {
+            overlay.draw(frame, overlay_area);
+        }
+
+        let (one, two) = (player(0), player(1));
+        let mut status = StatusBar::new().score(&[(&one, self.scores[0]), (&two, self.scores[1])]);
+        if self.position.result.is_none() {
+            let next = self.position.next;
+            status = status.turn(trf("{} to play", &[&player(next)]), self.theme.turn(next));
+        }
+        status = status.part(self.command_line.label().unwrap_or_default());
+        if let Some(message) = &self.message {
+            status = status.message(message);
+        }
+        status.draw(frame, status_area);
+    }
+
+    /// Which player a mark is: whoever played the first square that has it now
+    fn mark_player(&self, mark: char) -> usize {
+        let columns = self.session.columns;
+        let placed = self.moves.iter().enumerate().find(|(_, mv)| {
+            plugin::parse_square(mv, columns).is_some_and(|(column, row)| {
+                self.position.cells.get(row * columns + column) == Some(&Some(mark))
+            })
+        });
+        placed.map_or(0, |(idx, _)| idx % 2)
+    }
+
+    fn legend(&self) -> String {
+        let keys = &self.settings.keys;
+        let key = |action| keys.symbols(&[action]);
+        let moves = [Action::Left, Action::Up, Action::Down, Action::Right];
+        let mut legend = vec![];
+        if self.attract {
+            return tr("press any key").to_owned();
+        }
+        if self.position.result.is_none() {
+            legend.push((keys.symbols(&moves), tr("move")));
+            legend.push((key(Action::Place), tr("place")));
+            if !self.moves.is_empty() {
+                legend.push((key(Action::Cancel), tr("undo")));
+            }
+        } else {
+            // the result's overlay shows what to do next
+            return String::new();
+        }
+        legend.push((key(Action::Quit), tr("quit")));
+        legend
+            .into_iter()
+            .map(|(keys, what)| format!("{keys} {what}"))
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+impl Host for Screen {
+    fn restart(&mut self) -> Result<(), String> {
+        self.position = self.session.position(&[]).map_err(|e| e.to_string())?;
+        self.moves.clear();
+        self.started = Instant::now();
+        Ok(())
+    }
+
+    fn quit(&mut self) {
+        self.quit = true;
+    }
+
+    fn set_color_mode(&mut self, mode: ColorMode) {
+        self.settings.color_mode = mode;
+        self.theme = Theme::new(&self.settings);
+    }
+}
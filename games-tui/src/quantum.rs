@@ -0,0 +1,257 @@
+//! Quantum tic-tac-toe: each move is a spooky mark in two squares, until a cycle of them
+//! collapses into ordinary marks.
+//!
+//! The squares are drawn big enough for every spooky mark in them, like `x1 o2`, with ordinary
+//! marks in capitals. A spooky mark takes two presses, one on each square. When a cycle closes,
+//! the next player presses one of the two squares the last mark is in to choose where it goes.
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Padding,
+};
+
+pub use games_core::quantum::Position;
+use games_core::{
+    quantum::{Mark, Move},
+    tic_tac_toe::board::parse_square,
+};
+
+use crate::{
+    board_game::{self, BoardGame, Screen, Table},
+    bot::GameState,
+    grid::GridCursor,
+    keys::Action,
+    locale::{tr, trf},
+    player::Seat,
+    settings::Settings,
+    status_bar::StatusBar,
+};
+
+/// How many spooky marks fit on each of a square's lines
+const MARKS_PER_LINE: usize = 4;
+
+/// How wide each square is drawn, for `MARKS_PER_LINE` spooky marks side by side
+const SQUARE_WIDTH: usize = 1 + MARKS_PER_LINE * 3;
+
+/// How many lines each square is drawn on, enough for the eight spooky marks it can have before
+/// a cycle has to close
+const SQUARE_HEIGHT: usize = 2;
+
+pub struct Quantum {
+    cursor: GridCursor,
+    /// The first square of the spooky mark being made
+    first: Option<usize>,
+}
+
+/// Every state worth keeping a snapshot of, by name, drawn at `width` by `height`
+pub fn snapshots(width: u16, height: u16) -> Vec<(&'static str, Buffer)> {
+    // each a press on that square. x3 closes a cycle for O to collapse, and o6 one for X.
+    const CYCLE: &str = "a1 b2 b2 c3 c3 a1";
+    let states = [
+        ("playing", "a1 b2 b2"),
+        ("collapse", CYCLE),
+        (
+            "won",
+            &format!("{CYCLE} a1 a2 b1 c1 a3 a2 b1 a2 c1 a3 c1") as &str,
+        ),
+    ];
+    states
+        .into_iter()
+        .map(|(name, keys)| {
+            let mut screen = Screen::<Quantum>::for_snapshot(&Settings::default());
+            for square in keys.split_whitespace() {
+                screen.game.cursor.pos = parse_square(square).unwrap();
+                screen.press(Action::Place);
+            }
+            (name, screen.render(width, height))
+        })
+        .collect()
+}
+
+/// A mark as it's drawn, in capitals once it's an ordinary one
+fn mark_text(mark: Mark, classical: bool) -> String {
+    let player = mark.player.to_string();
+    let player = if classical {
+        player.to_uppercase()
+    } else {
+        player.to_lowercase()
+    };
+    format!("{player}{}", mark.turn)
+}
+
+impl Quantum {
+    /// The lines of the square at `pos`, each `SQUARE_WIDTH` wide
+    fn square(&self, table: &Table<Self>, pos: usize) -> [Line<'static>; SQUARE_HEIGHT] {
+        let over = table.over();
+        let collapsing = table.position.collapsing();
+        let mut base = Style::new();
+        if self.first == Some(pos) {
+            base = base.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        }
+        if self.cursor.pos == pos && !over {
+            base = base.add_modifier(Modifier::REVERSED);
+        }
+        let blank = || Line::from(Span::styled(" ".repeat(SQUARE_WIDTH), base));
+        if let Some(mark) = table.position.classical(pos) {
+            let style = base.patch(table.theme.player(mark.player.index()));
+            let text = format!("{:^SQUARE_WIDTH$}", mark_text(mark, true));
+            return [Line::from(Span::styled(text, style)), blank()];
+        }
+        let marks: Vec<_> = table.position.spooky(pos).collect();
+        let mut lines = [blank(), blank()];
+        for (line, marks) in lines.iter_mut().zip(marks.chunks(MARKS_PER_LINE)) {
+            let mut spans = vec![Span::styled(" ", base)];
+            for &mark in marks {
+                let mut style = base.patch(table.theme.player(mark.player.index()));
+                // the mark to collapse stands out in both its squares
+                if collapsing.is_some_and(|spooky| spooky.mark == mark) {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                spans.push(Span::styled(mark_text(mark, false), style));
+                spans.push(Span::styled(" ", base));
+            }
+            let used = 1 + marks.len() * 3;
+            spans.push(Span::styled(" ".repeat(SQUARE_WIDTH - used), base));
+            *line = Line::from(spans);
+        }
+        lines
+    }
+}
+
+impl BoardGame for Quantum {
+    type Position = Position;
+
+    const NAME: &'static str = "quantum-tic-tac-toe";
+    // the board's own borders are enough space above and below it
+    const PADDING: Padding = Padding::horizontal(1);
+
+    fn title() -> &'static str {
+        tr("Quantum tic-tac-toe")
+    }
+
+    fn start(_settings: &Settings) -> Position {
+        Position::new()
+    }
+
+    fn new(_settings: &Settings) -> Self {
+        Self {
+            cursor: GridCursor::new(3, 3),
+            first: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.first = None;
+    }
+
+    /// A press on the square under the cursor makes a move, or starts a spooky mark
+    fn input(
+        &mut self,
+        table: &Table<Self>,
+        _code: KeyCode,
+        action: Option<Action>,
+    ) -> Result<Option<Move>, String> {
+        match action {
+            Some(Action::Place) => (),
+            Some(Action::Cancel) => {
+                self.first = None;
+                return Ok(None);
+            }
+            action => {
+                board_game::step(&mut self.cursor, action);
+                return Ok(None);
+            }
+        }
+        let pos = self.cursor.pos;
+        let moves = table.position.legal_moves();
+        let mv = match self.first {
+            _ if moves.contains(&Move::Collapse(pos)) => Some(Move::Collapse(pos)),
+            _ if moves.contains(&Move::Classical(pos)) => Some(Move::Classical(pos)),
+            // pressing the first square again takes it back
+            Some(first) if first == pos => {
+                self.first = None;
+                return Ok(None);
+            }
+            Some(first) => Some(Move::Spooky(first.min(pos), first.max(pos))),
+            None if moves.iter().any(|mv| matches!(mv, Move::Spooky(..))) => {
+                if table.position.classical(pos).is_none() {
+                    self.first = Some(pos);
+                    return Ok(None);
+                }
+                None
+            }
+            None => None,
+        };
+        match mv.filter(|mv| moves.contains(mv)) {
+            Some(mv) => Ok(Some(mv)),
+            None => Err(tr("Can't play there").to_owned()),
+        }
+    }
+
+    fn moved(&mut self, _table: &Table<Self>, _mv: Move, _before: &Position) -> Option<String> {
+        self.first = None;
+        None
+    }
+
+    fn board(&self, table: &Table<Self>) -> Vec<Line<'static>> {
+        let border = |left, middle, right| {
+            let rule = "─".repeat(SQUARE_WIDTH);
+            Line::raw(format!("  {left}{rule}{middle}{rule}{middle}{rule}{right}"))
+        };
+        let letters: String = ["a", "b", "c"]
+            .map(|letter| format!("{letter:^SQUARE_WIDTH$} "))
+            .concat();
+        let mut lines = vec![Line::raw(format!("   {letters}")), border('┌', '┬', '┐')];
+        for row in 0..3 {
+            let squares = [0, 1, 2].map(|column| self.square(table, row * 3 + column));
+            for line in 0..SQUARE_HEIGHT {
+                let label = if line == 0 {
+                    format!("{} ", row + 1)
+                } else {
+                    "  ".to_owned()
+                };
+                let mut spans = vec![Span::raw(label), Span::raw("│")];
+                for square in &squares {
+                    spans.extend(square[line].spans.iter().cloned());
+                    spans.push(Span::raw("│"));
+                }
+                lines.push(Line::from(spans));
+            }
+            lines.push(if row < 2 {
+                border('├', '┼', '┤')
+            } else {
+                border('└', '┴', '┘')
+            });
+        }
+        lines
+    }
+
+    fn legend(&self, table: &Table<Self>) -> Vec<(String, &'static str)> {
+        let keys = &table.settings.keys;
+        let key = |action| keys.symbols(&[action]);
+        let moves = [Action::Left, Action::Up, Action::Down, Action::Right];
+        let mut legend = vec![(keys.symbols(&moves), tr("move"))];
+        if table.position.collapsing().is_some() {
+            legend.push((key(Action::Place), tr("collapse")));
+        } else if self.first.is_some() {
+            legend.push((key(Action::Place), tr("second square")));
+            legend.push((key(Action::Cancel), tr("put back")));
+        } else {
+            legend.push((key(Action::Place), tr("place")));
+        }
+        legend
+    }
+
+    fn status(&self, table: &Table<Self>, status: StatusBar) -> StatusBar {
+        let status = table.turn(status);
+        match table.position.collapsing() {
+            Some(spooky) if !table.over() => {
+                let mark = mark_text(spooky.mark, false);
+                status.part(trf("choose where {} goes", &[&mark]))
+            }
+            _ => status,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Choosing a game at random, for when nobody can decide what to play.
+//!
+//! Every installed game can be chosen, plugins included. With `random_avoid_recent`, the games
+//! played most in the last few recorded games are less likely to come up, so it's usually one
+//! that hasn't been played for a while.
+use crate::{plugin::Plugin, rng::Rng, settings::Settings, stats, Game};
+
+/// How many of the latest games in the stats file count as recent
+const RECENT: usize = 10;
+
+pub enum Choice<'a> {
+    Game(Game),
+    Plugin(&'a Plugin),
+}
+
+impl Choice<'_> {
+    fn name(&self) -> &str {
+        match self {
+            Self::Game(game) => game.name(),
+            Self::Plugin(plugin) => &plugin.name,
+        }
+    }
+}
+
+/// One of the built-in games or `plugins`
+pub fn choose<'a>(settings: &Settings, plugins: &'a [Plugin]) -> Choice<'a> {
+    let mut choices: Vec<_> = Game::ALL.into_iter().map(Choice::Game).collect();
+    choices.extend(plugins.iter().map(Choice::Plugin));
+    // each recent game makes its game less likely, but never impossible
+    let records = if settings.random_avoid_recent {
+        stats::load().unwrap_or_default()
+    } else {
+        vec![]
+    };
+    let recent = &records[records.len().saturating_sub(RECENT)..];
+    let weights: Vec<_> = choices
+        .iter()
+        .map(|choice| {
+            let played = recent.iter().filter(|record| record.game == choice.name());
+            RECENT + 1 - played.count()
+        })
+        .collect();
+    let mut roll = Rng::from_time().below(weights.iter().sum());
+    let idx = weights
+        .iter()
+        .position(|&weight| {
+            let chosen = roll < weight;
+            roll = roll.saturating_sub(weight);
+            chosen
+        })
+        .unwrap_or(0);
+    choices.swap_remove(idx)
+}
@@ -0,0 +1,433 @@
+//! User settings, read from `settings.toml` in the config directory.
+//!
+//! The file is a flat list of `key = value` lines (a small subset of TOML). Missing keys fall back
+//! to their defaults. `version` is the version of the settings it was written for: a file from a
+//! newer version may have settings this one doesn't know, which are left alone rather than
+//! complained about.
+use std::{fs, io, path::PathBuf, time::Duration};
+
+use ratatui::style::Color;
+
+use crate::{
+    announce::Announce,
+    bot::{BotKind, Budget},
+    dirs,
+    error::GamesError,
+    gravity,
+    keys::{self, Action, Keymap, Preset},
+    locale::Language,
+    sos,
+    sound::Sound,
+    theme::{self, BoardStyle, ColorMode, Glyphs},
+    tic_tac_toe::{self, Handicap},
+    time_control::{OnTimeout, TimeControl},
+};
+
+/// The version of the settings file this program writes
+const VERSION: u32 = 1;
+
+#[derive(Clone)]
+pub struct Settings {
+    /// Whether to animate things like placing a mark
+    pub animations: bool,
+    /// Keep everything still: no animations, no pulsing cursor and no ticking countdowns
+    pub reduced_motion: bool,
+    /// How often animations and timers are updated
+    pub tick_rate: Duration,
+    /// How long the menu waits for a key before the games start playing themselves, if they do
+    pub attract_delay: Option<Duration>,
+    /// Whether a random game is less likely to be one played recently
+    pub random_avoid_recent: bool,
+    /// Which theme to draw with
+    pub color_mode: ColorMode,
+    /// Colors for players' marks, turns and winning lines in place of the theme's, in turn order
+    pub player_colors: Vec<Color>,
+    /// How players' marks look
+    pub glyphs: Glyphs,
+    /// How the tic-tac-toe board is drawn
+    pub board_style: BoardStyle,
+    /// After the first move, the second player may choose to swap sides (the "pie rule")
+    pub swap_rule: bool,
+    /// Start the next game automatically a few seconds after one finishes
+    pub auto_rematch: bool,
+    /// Who starts each game after the first
+    pub first_player: FirstPlayer,
+    /// How the computer chooses its moves
+    pub bot: BotKind,
+    /// How hard the bot thinks about each move. `AlphaBeta` only uses time budgets.
+    pub bot_budget: Budget,
+    /// How many moves ahead the `AlphaBeta` bot looks, without a time budget
+    pub bot_depth: usize,
+    /// Which of the game's evaluations the `AlphaBeta` bot adds up, and their weights
+    pub evaluation: Vec<(String, f64)>,
+    /// A program in the bots directory to play instead of `bot`, which still moves whenever
+    /// the program doesn't. It runs confined to a directory of its own, as far as the system
+    /// allows.
+    pub bot_script: Option<String>,
+    /// How long `bot_script` may take over each move
+    pub bot_script_limit: Duration,
+    /// Which key does what during a game, set with lines like `key_place = "enter"`
+    pub keys: Keymap,
+    /// Show the frame rate and how long each frame takes to draw, in the top right corner
+    pub show_fps: bool,
+    /// List the keys that do something right now along the bottom, on screens tall enough
+    pub show_keys: bool,
+    /// Whether the cursor jumps over squares that are already taken
+    pub skip_taken: bool,
+    /// The clock for games between people, or against the computer
+    pub time_control: TimeControl,
+    /// Whether running out of time loses the game, or has a random move played instead
+    pub on_timeout: OnTimeout,
+    /// Marks or blocked squares on the board before each game starts, like `"x:b2 blocked:a1"`
+    pub handicap: Handicap,
+    /// Block 1 to 3 squares at random at the start of each game, as well as any in `handicap`
+    pub random_blocks: bool,
+    /// Which language to show text in
+    pub language: Language,
+    /// Describe the game in words on a line of its own, with the cursor on it
+    pub screen_reader: bool,
+    /// Where else to send that description whenever it changes
+    pub announce: Announce,
+    /// What to play on placing a mark, trying an illegal move, and the end of a game
+    pub sound: Sound,
+    /// How many squares across and down the SOS grid is, set like `sos_size = "7x5"`
+    pub sos_size: (usize, usize),
+    /// How many squares across and down the gravity tic-tac-toe board is, 3 or 4
+    pub gravity_size: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FirstPlayer {
+    /// Take turns going first
+    Alternate,
+    /// Whoever lost the last game starts the next (alternating after a draw)
+    Loser,
+    /// Whoever won the last game starts the next (alternating after a draw)
+    Winner,
+    AlwaysX,
+    /// Flip a coin before every game
+    Random,
+}
+
+impl FirstPlayer {
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "alternate" => Some(Self::Alternate),
+            "loser" => Some(Self::Loser),
+            "winner" => Some(Self::Winner),
+            "x" => Some(Self::AlwaysX),
+            "random" => Some(Self::Random),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            animations: true,
+            reduced_motion: false,
+            tick_rate: Duration::from_millis(30),
+            attract_delay: Some(Duration::from_secs(30)),
+            random_avoid_recent: true,
+            color_mode: ColorMode::Default,
+            player_colors: vec![],
+            glyphs: Glyphs::Art,
+            board_style: BoardStyle::Blocks,
+            swap_rule: false,
+            auto_rematch: false,
+            first_player: FirstPlayer::Alternate,
+            bot: BotKind::Mcts,
+            bot_budget: Budget::Iterations(10_000),
+            bot_depth: 4,
+            evaluation: vec![("lines".to_owned(), 1.), ("center".to_owned(), 0.5)],
+            bot_script: None,
+            bot_script_limit: Duration::from_secs(1),
+            keys: Keymap::default(),
+            show_fps: false,
+            show_keys: true,
+            skip_taken: false,
+            time_control: TimeControl::None,
+            on_timeout: OnTimeout::Forfeit,
+            handicap: Handicap::default(),
+            random_blocks: false,
+            language: Language::English,
+            screen_reader: false,
+            announce: Announce::Off,
+            sound: Sound::Off,
+            sos_size: (5, 5),
+            gravity_size: 3,
+        }
+    }
+}
+
+impl Settings {
+    /// Whether to animate things like placing a mark
+    pub fn animate(&self) -> bool {
+        self.animations && !self.reduced_motion
+    }
+
+    /// Load the settings file, falling back to the defaults where it is missing or invalid.
+    pub fn load() -> Self {
+        let mut settings = Self::default();
+        let Some(path) = Self::path() else {
+            return settings;
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return settings,
+            Err(e) => {
+                eprintln!("could not read {}: {e}", path.display());
+                return settings;
+            }
+        };
+        let version = file_version(&contents);
+        if version > VERSION {
+            eprintln!(
+                "{} is from a newer version of the games, so settings this one doesn't know are \
+                 ignored",
+                path.display()
+            );
+        }
+        for (idx, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let result = settings.set_line(line);
+            if version > VERSION && result.as_ref().is_err_and(|e| e.starts_with("unknown")) {
+                continue;
+            }
+            if let Err(message) = result {
+                let error = GamesError::Config {
+                    path: path.clone(),
+                    line: idx + 1,
+                    message,
+                };
+                eprintln!("{error}");
+            }
+        }
+        if let Some((first, second)) = settings.keys.conflict() {
+            eprintln!(
+                "{}: key_{} and key_{} use the same key",
+                path.display(),
+                first.name(),
+                second.name()
+            );
+        }
+        settings
+    }
+
+    /// Save the key preset and any keys that aren't the preset's to the settings file, leaving
+    /// everything else in it alone
+    pub fn save_keys(keys: &Keymap) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        // drop the old key lines and add the new ones at the end
+        let mut out = String::new();
+        if file_version(&contents) == 0 {
+            out.push_str(&format!("version = {VERSION}\n"));
+        }
+        for line in contents.lines() {
+            let key = line.split_once('=').map(|(key, _)| key.trim());
+            if !key.is_some_and(|key| key == "keys" || key.starts_with("key_")) {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        if keys.preset != Preset::Default {
+            out.push_str(&format!("keys = \"{}\"\n", keys.preset.name()));
+        }
+        for action in Action::ALL {
+            if !keys.is_default(action) {
+                out.push_str(&format!(
+                    "key_{} = \"{}\"\n",
+                    action.name(),
+                    keys.name(action)
+                ));
+            }
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, out)
+    }
+
+    fn set_line(&mut self, line: &str) -> Result<(), String> {
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("expected `key = value`, found \"{line}\""));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if let Some(name) = key.strip_prefix("key_") {
+            let action =
+                Action::from_name(name).ok_or_else(|| format!("unknown setting \"{key}\""))?;
+            let code = keys::parse_key(&parse_string(value)?)
+                .ok_or_else(|| format!("unknown key {value}"))?;
+            self.keys.set(action, code);
+            return Ok(());
+        }
+        match key {
+            // read before everything else, by file_version
+            "version" => (),
+            "animations" => self.animations = parse_bool(value)?,
+            "keys" => {
+                let preset = Preset::from_str(&parse_string(value)?)
+                    .ok_or_else(|| format!("unknown key preset {value}"))?;
+                self.keys.preset = preset;
+            }
+            "reduced_motion" => self.reduced_motion = parse_bool(value)?,
+            "tick_rate_ms" => {
+                let ms = value
+                    .parse()
+                    .ok()
+                    .filter(|ms| *ms > 0)
+                    .ok_or_else(|| format!("expected a positive number, found \"{value}\""))?;
+                self.tick_rate = Duration::from_millis(ms)
+            }
+            "attract_delay_s" => {
+                let secs = value
+                    .parse()
+                    .map_err(|_| format!("expected a number, found \"{value}\""))?;
+                // 0 turns it off
+                self.attract_delay = (secs > 0).then(|| Duration::from_secs(secs));
+            }
+            "random_avoid_recent" => self.random_avoid_recent = parse_bool(value)?,
+            "swap_rule" => self.swap_rule = parse_bool(value)?,
+            "show_fps" => self.show_fps = parse_bool(value)?,
+            "show_keys" => self.show_keys = parse_bool(value)?,
+            "skip_taken" => self.skip_taken = parse_bool(value)?,
+            "auto_rematch" => self.auto_rematch = parse_bool(value)?,
+            "color_mode" => {
+                self.color_mode = ColorMode::from_str(&parse_string(value)?)
+                    .ok_or_else(|| format!("unknown color mode {value}"))?
+            }
+            "player_colors" => self.player_colors = theme::parse_colors(&parse_string(value)?)?,
+            "board_style" => {
+                self.board_style = BoardStyle::from_str(&parse_string(value)?)
+                    .ok_or_else(|| format!("expected \"blocks\" or \"canvas\", found {value}"))?
+            }
+            "glyphs" => {
+                self.glyphs = Glyphs::from_str(&parse_string(value)?).ok_or_else(|| {
+                    format!("expected \"art\", \"letters\", \"emoji\" or \"custom <glyphs>\", found {value}")
+                })?
+            }
+            "bot_budget" => {
+                // either a bare number of iterations or a quoted time
+                let budget = parse_string(value).unwrap_or_else(|_| value.to_owned());
+                self.bot_budget = Budget::from_str(&budget).ok_or_else(|| {
+                    format!("expected iterations or a time like \"250ms\", found {value}")
+                })?
+            }
+            "bot" => {
+                self.bot = BotKind::from_str(&parse_string(value)?)
+                    .ok_or_else(|| format!("unknown bot {value}"))?
+            }
+            "bot_depth" => {
+                self.bot_depth = value
+                    .parse()
+                    .map_err(|_| format!("expected a number, found \"{value}\""))?
+            }
+            "evaluation" => self.evaluation = parse_weights(&parse_string(value)?)?,
+            "bot_script" => {
+                let name = parse_string(value)?;
+                self.bot_script = (!name.is_empty()).then_some(name);
+            }
+            "bot_script_limit_ms" => {
+                let ms = value
+                    .parse()
+                    .ok()
+                    .filter(|ms| *ms > 0)
+                    .ok_or_else(|| format!("expected a positive number, found \"{value}\""))?;
+                self.bot_script_limit = Duration::from_millis(ms)
+            }
+            "time_control" => {
+                self.time_control =
+                    TimeControl::from_str(&parse_string(value)?).ok_or_else(|| {
+                        format!("expected a time control like \"fischer 5m 3s\", found {value}")
+                    })?
+            }
+            "on_timeout" => {
+                self.on_timeout = OnTimeout::from_str(&parse_string(value)?)
+                    .ok_or_else(|| format!("expected \"forfeit\" or \"random\", found {value}"))?
+            }
+            "handicap" => self.handicap = tic_tac_toe::parse_handicap(&parse_string(value)?)?,
+            "random_blocks" => self.random_blocks = parse_bool(value)?,
+            "sound" => {
+                self.sound = Sound::from_str(&parse_string(value)?).ok_or_else(|| {
+                    format!(
+                        "expected \"off\", \"bell\" or \"effects\" (with the audio feature), \
+                         found {value}"
+                    )
+                })?
+            }
+            "screen_reader" => self.screen_reader = parse_bool(value)?,
+            "announce" => {
+                self.announce = Announce::from_str(&parse_string(value)?)
+                    .ok_or_else(|| format!("expected \"off\" or \"osc\", found {value}"))?
+            }
+            "language" => {
+                self.language = Language::from_str(&parse_string(value)?)
+                    .ok_or_else(|| format!("unknown language {value}"))?
+            }
+            "sos_size" => self.sos_size = sos::parse_size(&parse_string(value)?)?,
+            "gravity_size" => self.gravity_size = gravity::parse_size(value)?,
+            "first_player" => {
+                self.first_player = FirstPlayer::from_str(&parse_string(value)?)
+                    .ok_or_else(|| format!("unknown first player rule {value}"))?
+            }
+            _ => return Err(format!("unknown setting \"{key}\"")),
+        }
+        Ok(())
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("settings.toml"))
+    }
+}
+
+/// The version a settings file says it's for, or 0 if it says nothing, from before there were
+/// versions
+fn file_version(contents: &str) -> u32 {
+    contents
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == "version")
+        .and_then(|(_, value)| value.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("expected true or false, found \"{value}\"")),
+    }
+}
+
+fn parse_string(value: &str) -> Result<String, String> {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .map(str::to_owned)
+        .ok_or_else(|| format!("expected a quoted string, found {value}"))
+}
+
+/// Parses weights like `lines:1, center:0.5`. A name on its own has a weight of 1.
+fn parse_weights(value: &str) -> Result<Vec<(String, f64)>, String> {
+    value
+        .split(',')
+        .map(|item| {
+            let (name, weight) = item.split_once(':').unwrap_or((item, "1"));
+            let weight = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("expected a number, found \"{}\"", weight.trim()))?;
+            Ok((name.trim().to_owned(), weight))
+        })
+        .collect()
+}
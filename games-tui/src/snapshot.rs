@@ -0,0 +1,48 @@
+//! Saved copies of the screen, to catch mistakes in how games are drawn.
+//!
+//! `./games snapshots` draws each game in a few states and at a few terminal sizes, and compares
+//! the text on screen with the files in `snapshots/`. After a change that's meant to look
+//! different, `--update` saves the new screens instead.
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use ratatui::buffer::Buffer;
+
+/// The usual terminal size, and small odd ones where layouts get squeezed
+pub const SIZES: [(u16, u16); 3] = [(80, 24), (41, 13), (25, 9)];
+
+pub fn dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("snapshots")
+}
+
+/// The text on screen, one line per row without trailing spaces
+pub fn to_text(buffer: &Buffer) -> String {
+    let mut text = String::new();
+    for y in 0..buffer.area.height {
+        let line: String = (0..buffer.area.width)
+            .map(|x| buffer[(x, y)].symbol())
+            .collect();
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// Whether `text` is the same as the snapshot saved at `path`. With `update`, `text` is saved
+/// there instead.
+pub fn check(path: &Path, text: &str, update: bool) -> io::Result<bool> {
+    if update {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)?;
+        return Ok(true);
+    }
+    match fs::read_to_string(path) {
+        Ok(saved) => Ok(saved == text),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,172 @@
+//! SOS: players take turns writing an S or an O, scoring for each S-O-S they complete, and going
+//! again when they do.
+//!
+//! The grid is drawn like a plugin's, `sos_size` from the settings, with each S-O-S in the color
+//! of whoever made it. Tab switches the letter that's written.
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+pub use games_core::sos::{parse_size, Position};
+use games_core::{
+    sos::{Letter, Move},
+    tic_tac_toe::board::{O, X},
+};
+
+use crate::{
+    board_game::{self, BoardGame, Screen, Table},
+    bot::GameState,
+    grid::GridCursor,
+    keys::{self, Action},
+    locale::{tr, trf},
+    player::Seat,
+    settings::Settings,
+    status_bar::StatusBar,
+};
+
+/// Switches the letter, like tab moves between boards in a simul
+const SWITCH_KEY: KeyCode = KeyCode::Tab;
+
+pub struct Sos {
+    cursor: GridCursor,
+    /// The letter a press writes
+    letter: Letter,
+}
+
+/// Every state worth keeping a snapshot of, by name, drawn at `width` by `height`
+pub fn snapshots(width: u16, height: u16) -> Vec<(&'static str, Buffer)> {
+    // each a letter written on a square. X makes an S-O-S across the top and goes again.
+    const MADE: &str = "Sa1 Ob1 Sc1";
+    let states = [
+        ("playing", MADE),
+        ("won", &format!("{MADE} Sa2 Sb2 Oc2 Sa3 Ob3 Sc3") as &str),
+    ];
+    states
+        .into_iter()
+        .map(|(name, moves)| {
+            let settings = Settings {
+                sos_size: (3, 3),
+                ..Settings::default()
+            };
+            let mut screen = Screen::<Sos>::for_snapshot(&settings);
+            for mv in moves.split_whitespace() {
+                let mv = screen.table.position.parse_move(mv).unwrap();
+                (screen.game.cursor.pos, screen.game.letter) = (mv.pos, mv.letter);
+                screen.press(Action::Place);
+            }
+            (name, screen.render(width, height))
+        })
+        .collect()
+}
+
+impl BoardGame for Sos {
+    type Position = Position;
+
+    const NAME: &'static str = "sos";
+
+    fn title() -> &'static str {
+        tr("SOS")
+    }
+
+    fn start(settings: &Settings) -> Position {
+        let (width, height) = settings.sos_size;
+        Position::new(width, height)
+    }
+
+    fn new(settings: &Settings) -> Self {
+        let (width, height) = settings.sos_size;
+        Self {
+            cursor: GridCursor::new(width, height),
+            letter: Letter::S,
+        }
+    }
+
+    /// A press writes the letter on the square under the cursor
+    fn input(
+        &mut self,
+        table: &Table<Self>,
+        code: KeyCode,
+        action: Option<Action>,
+    ) -> Result<Option<Move>, String> {
+        if code == SWITCH_KEY {
+            self.letter = self.letter.next();
+            return Ok(None);
+        }
+        if action != Some(Action::Place) {
+            board_game::step(&mut self.cursor, action);
+            return Ok(None);
+        }
+        let mv = Move {
+            pos: self.cursor.pos,
+            letter: self.letter,
+        };
+        if table.position.legal_moves().contains(&mv) {
+            Ok(Some(mv))
+        } else {
+            Err(tr("Can't play there").to_owned())
+        }
+    }
+
+    fn moved(&mut self, table: &Table<Self>, _mv: Move, before: &Position) -> Option<String> {
+        let made = table.position.made().len() > before.made().len();
+        let player = before.current_player();
+        (made && !table.over()).then(|| trf("SOS! {} goes again", &[&table.name(player)]))
+    }
+
+    fn board(&self, table: &Table<Self>) -> Vec<Line<'static>> {
+        let over = table.over();
+        let (columns, rows) = (table.position.width(), table.position.height());
+        // each square in an S-O-S is drawn in the color of the last player to use it
+        let mut made = vec![None; columns * rows];
+        for &(squares, player) in table.position.made() {
+            for pos in squares {
+                made[pos] = Some(player);
+            }
+        }
+        let letters: String = (0..columns)
+            .map(|column| format!(" {} ", (b'a' + column as u8) as char))
+            .collect();
+        let mut lines = vec![Line::raw(format!("   {letters}"))];
+        for row in 0..rows {
+            let mut spans = vec![Span::raw(format!("{:>2} ", row + 1))];
+            for column in 0..columns {
+                let pos = row * columns + column;
+                let letter = table.position.get(pos);
+                let mut style = match (letter, made[pos]) {
+                    (Some(_), Some(player)) => table.theme.player(player.index()),
+                    (Some(_), None) => Style::new(),
+                    (None, _) => Style::new().add_modifier(Modifier::DIM),
+                };
+                if self.cursor.pos == pos && !over {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                let text = format!(" {} ", letter.map_or('·', Letter::name));
+                spans.push(Span::styled(text, style));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+
+    fn legend(&self, table: &Table<Self>) -> Vec<(String, &'static str)> {
+        let keys = &table.settings.keys;
+        let moves = [Action::Left, Action::Up, Action::Down, Action::Right];
+        vec![
+            (keys.symbols(&moves), tr("move")),
+            (keys.symbols(&[Action::Place]), tr("write")),
+            (keys::key_name(SWITCH_KEY).unwrap_or_default(), tr("S/O")),
+        ]
+    }
+
+    fn status(&self, table: &Table<Self>, status: StatusBar) -> StatusBar {
+        let points = [X, O].map(|player| table.position.score(player));
+        let status = table.turn(status.part(trf("points {}–{}", &[&points[0], &points[1]])));
+        if table.over() {
+            return status;
+        }
+        status.part(trf("writing {}", &[&self.letter.name()]))
+    }
+}
@@ -0,0 +1,142 @@
+//! Sounds for placing a mark, trying a move that isn't allowed, and the end of a game, chosen
+//! with `sound` in the settings file.
+//!
+//! The terminal bell works anywhere the terminal has one. With the `audio` feature, each cue gets
+//! a short tone of its own instead, played with whichever of `paplay`, `aplay` or `afplay` is
+//! installed.
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Cue {
+    Place,
+    Illegal,
+    GameOver,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Sound {
+    Off,
+    /// The terminal bell, the same for every cue
+    Bell,
+    /// A different tone for each cue
+    #[cfg(feature = "audio")]
+    Effects,
+}
+
+impl Sound {
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "off" => Some(Self::Off),
+            "bell" => Some(Self::Bell),
+            #[cfg(feature = "audio")]
+            "effects" => Some(Self::Effects),
+            _ => None,
+        }
+    }
+
+    /// Play the sound for `cue`, without waiting for it to finish
+    pub fn play(self, cue: Cue) -> io::Result<()> {
+        match (self, cue) {
+            (Self::Off, _) => Ok(()),
+            (Self::Bell, _) => {
+                let mut stdout = io::stdout();
+                stdout.write_all(b"\x07")?;
+                stdout.flush()
+            }
+            #[cfg(feature = "audio")]
+            (Self::Effects, cue) => effects::play(cue),
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+mod effects {
+    use std::{
+        env, fs, io,
+        process::{Command, Stdio},
+        thread,
+    };
+
+    use super::Cue;
+
+    /// Programs that can play a wav file, tried in turn
+    const PLAYERS: [&str; 3] = ["paplay", "aplay", "afplay"];
+    const SAMPLE_RATE: u32 = 8000;
+
+    impl Cue {
+        fn name(self) -> &'static str {
+            match self {
+                Self::Place => "place",
+                Self::Illegal => "illegal",
+                Self::GameOver => "game-over",
+            }
+        }
+
+        /// Each note's frequency in Hz and length in milliseconds
+        fn notes(self) -> &'static [(f64, u32)] {
+            match self {
+                Self::Place => &[(880., 40)],
+                Self::Illegal => &[(196., 150)],
+                Self::GameOver => &[(523., 120), (659., 120), (784., 240)],
+            }
+        }
+    }
+
+    pub fn play(cue: Cue) -> io::Result<()> {
+        let path = env::temp_dir().join(format!("games-{}.wav", cue.name()));
+        if !path.exists() {
+            fs::write(&path, wav(cue.notes()))?;
+        }
+        thread::spawn(move || {
+            for player in PLAYERS {
+                let status = Command::new(player)
+                    .arg(&path)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status();
+                if status.is_ok_and(|status| status.success()) {
+                    return;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// `notes` as square waves, in an 8-bit mono wav file
+    fn wav(notes: &[(f64, u32)]) -> Vec<u8> {
+        let mut samples = vec![];
+        for &(freq, ms) in notes {
+            let count = SAMPLE_RATE * ms / 1000;
+            samples.extend((0..count).map(|idx| {
+                let phase = (idx as f64 * freq / SAMPLE_RATE as f64).fract();
+                // quiet, and silent at the end so notes don't run together
+                if idx + count / 10 >= count {
+                    128
+                } else if phase < 0.5 {
+                    104
+                } else {
+                    152
+                }
+            }));
+        }
+        let len = samples.len() as u32;
+        let mut wav = vec![];
+        wav.extend(b"RIFF");
+        wav.extend((36 + len).to_le_bytes());
+        wav.extend(b"WAVEfmt ");
+        wav.extend(16u32.to_le_bytes());
+        // PCM, one channel
+        wav.extend(1u16.to_le_bytes());
+        wav.extend(1u16.to_le_bytes());
+        wav.extend(SAMPLE_RATE.to_le_bytes());
+        // bytes per second, bytes per sample and bits per sample
+        wav.extend(SAMPLE_RATE.to_le_bytes());
+        wav.extend(1u16.to_le_bytes());
+        wav.extend(8u16.to_le_bytes());
+        wav.extend(b"data");
+        wav.extend(len.to_le_bytes());
+        wav.extend(samples);
+        wav
+    }
+}
@@ -0,0 +1,103 @@
+//! The bar along the bottom of a game: the score, whose turn it is, the clocks and how long
+//! everyone has been playing, with any message in its bottom border. Every game fills one in,
+//! so they all look the same.
+use std::{fmt, time::Duration};
+
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::locale::{tr, trf};
+
+/// How far apart the parts of the bar are
+const GAP: &str = "   ";
+
+/// How many rows the bar takes
+pub const HEIGHT: u16 = 3;
+
+#[derive(Default)]
+pub struct StatusBar {
+    /// Each part in order, only the turn styled
+    parts: Vec<Span<'static>>,
+    message: Option<String>,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Each player's name and score
+    pub fn score(mut self, scores: &[(&str, usize)]) -> Self {
+        let scores: Vec<_> = scores
+            .iter()
+            .map(|(name, score)| format!("{name}: {score}"))
+            .collect();
+        let text = trf("Score - {}", &[&scores.join(", ")]);
+        self.parts.push(Span::raw(text));
+        self
+    }
+
+    /// Whose turn it is, like "X to play", in their color
+    pub fn turn(mut self, text: String, style: Style) -> Self {
+        self.parts.push(Span::styled(text, style));
+        self
+    }
+
+    /// Anything else, like the clocks or a move being typed
+    pub fn part(mut self, text: String) -> Self {
+        if !text.is_empty() {
+            self.parts.push(Span::raw(text));
+        }
+        self
+    }
+
+    /// How long this game and the whole session have gone on
+    pub fn timers(self, game: Duration, session: Duration) -> Self {
+        self.part(trf("Game {}", &[&FmtDuration(game)]))
+            .part(trf("Session {}", &[&FmtDuration(session)]))
+    }
+
+    /// Shown in the bottom border, to stand out
+    pub fn message(mut self, message: &str) -> Self {
+        self.message = Some(message.to_owned());
+        self
+    }
+
+    pub fn draw(self, frame: &mut Frame, area: Rect) {
+        let mut block = Block::bordered().title(tr("Status"));
+        if let Some(message) = self.message {
+            let style = Style::new().add_modifier(Modifier::BOLD);
+            block = block.title_bottom(Line::styled(format!(" {message} "), style));
+        }
+        let mut spans = vec![];
+        for (idx, part) in self.parts.into_iter().enumerate() {
+            if idx > 0 {
+                spans.push(Span::raw(GAP));
+            }
+            spans.push(part);
+        }
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        frame.render_widget(Paragraph::new(Line::from(spans)), inner);
+    }
+}
+
+/// Formats as `m:ss`, or `h:mm:ss` if over an hour
+struct FmtDuration(Duration);
+
+impl fmt::Display for FmtDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+        if hours > 0 {
+            write!(f, "{hours}:{mins:02}:{secs:02}")
+        } else {
+            write!(f, "{mins}:{secs:02}")
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Putting a game aside to go back to the menu, and carrying on with it later. Ctrl+G puts any
+//! game aside, and the menu has a "Continue" item for each one until it's finished or quit.
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+use crate::{error::GamesError, plugin, tic_tac_toe, Game};
+
+/// A game put aside part of the way through, with everything needed to carry on
+pub enum Suspended {
+    TicTacToe(tic_tac_toe::Suspended),
+    Plugin(plugin::Suspended),
+}
+
+impl Suspended {
+    /// The game's name, for the menu
+    pub fn name(&self) -> &str {
+        match self {
+            Self::TicTacToe(_) => Game::TicTacToe.name(),
+            Self::Plugin(suspended) => suspended.name(),
+        }
+    }
+
+    /// Carry on playing until the game is over, or it's put aside again
+    pub fn resume(
+        self,
+        terminal: &mut ratatui::DefaultTerminal,
+    ) -> Result<Option<Self>, GamesError> {
+        Ok(match self {
+            Self::TicTacToe(suspended) => {
+                tic_tac_toe::resume(terminal, suspended)?.map(Self::TicTacToe)
+            }
+            Self::Plugin(suspended) => plugin::resume(terminal, suspended)?.map(Self::Plugin),
+        })
+    }
+}
+
+/// Whether `key` is the one that puts a game aside. It isn't a key binding, so it's the same in
+/// every game and can't be lost under one of the game's own keys.
+pub fn is_suspend_key(key: &KeyEvent) -> bool {
+    key.kind == KeyEventKind::Press
+        && key.code == KeyCode::Char('g')
+        && key.modifiers.contains(KeyModifiers::CONTROL)
+}
@@ -0,0 +1,232 @@
+//! Colors and styles used when drawing, chosen by the `color_mode` setting (with players' own
+//! colors from `player_colors` on top), how players' marks look, chosen by `glyphs`, and how
+//! boards are drawn, chosen by `board_style`.
+//!
+//! The accessible modes never rely on hue alone: the two players also get different text
+//! modifiers, so they can be told apart in monochrome.
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::settings::Settings;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    Default,
+    /// Black and white, with one player drawn inverted
+    HighContrast,
+    /// Blue and orange, which stay distinct with the common kinds of color blindness
+    Colorblind,
+}
+
+impl ColorMode {
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::Default),
+            "high-contrast" => Some(Self::HighContrast),
+            "colorblind" => Some(Self::Colorblind),
+            _ => None,
+        }
+    }
+}
+
+/// How players' marks are drawn
+#[derive(Clone, PartialEq)]
+pub enum Glyphs {
+    /// Shapes filling the square, falling back to letters on small boards (`"art"`)
+    Art,
+    /// Each player's letter (`"letters"`)
+    Letters,
+    /// ❌ ⭕ 🔺 🔷 (`"emoji"`)
+    Emoji,
+    /// Any text for each player, in turn order (`"custom 🐱 🐶"`)
+    Custom(Vec<String>),
+}
+
+impl Glyphs {
+    pub fn from_str(value: &str) -> Option<Self> {
+        let words: Vec<_> = value.split_whitespace().collect();
+        match words[..] {
+            ["art"] => Some(Self::Art),
+            ["letters"] => Some(Self::Letters),
+            ["emoji"] => Some(Self::Emoji),
+            ["custom", ref glyphs @ ..] if !glyphs.is_empty() => Some(Self::Custom(
+                glyphs.iter().map(|&glyph| glyph.to_owned()).collect(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// The text player number `index` is shown as, given their letter, or `None` to draw art
+    pub fn text(&self, index: usize, letter: char) -> Option<String> {
+        const EMOJI: [&str; 4] = ["❌", "⭕", "🔺", "🔷"];
+        match self {
+            Self::Art => None,
+            Self::Letters => Some(letter.to_string()),
+            Self::Emoji => Some(EMOJI[index % EMOJI.len()].to_owned()),
+            // players without one of their own keep their letter
+            Self::Custom(glyphs) => Some(glyphs.get(index).cloned().unwrap_or(letter.to_string())),
+        }
+    }
+}
+
+/// How boards are drawn, on terminals big enough for more than one character per square
+#[derive(Clone, Copy, PartialEq)]
+pub enum BoardStyle {
+    /// Each square in a box of its own (`"blocks"`)
+    Blocks,
+    /// The whole board as one picture, grid lines and all (`"canvas"`)
+    Canvas,
+}
+
+impl BoardStyle {
+    pub fn from_str(name: &str) -> Option<Self> {
+        match name {
+            "blocks" => Some(Self::Blocks),
+            "canvas" => Some(Self::Canvas),
+            _ => None,
+        }
+    }
+}
+
+/// Colors for each player in turn order, like `"red, #00aaff"`. Each can be a name, a hex code
+/// or a 256-color palette index.
+pub fn parse_colors(value: &str) -> Result<Vec<Color>, String> {
+    let colors = value
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .filter(|color| !color.is_empty())
+        .map(|color| {
+            color
+                .parse()
+                .map_err(|_| format!("unknown color \"{color}\""))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if colors.is_empty() {
+        return Err("expected at least one color".to_owned());
+    }
+    Ok(colors)
+}
+
+pub struct Theme {
+    /// Style for each player's pieces or squares, in turn order (marks are drawn in the
+    /// foreground color). Games with more players than this go round again.
+    pub players: [Style; 4],
+    /// 256-color palette indices the cursor pulses through, dimmest first
+    pub cursor: &'static [u8],
+    /// Color of the strikethrough on the winning line
+    pub win_line: Color,
+    /// Background of squares highlighted in an annotation
+    pub highlight: Color,
+    /// Styles for marking moves that lose, draw and win
+    pub analysis: [Style; 3],
+    pub glyphs: Glyphs,
+    pub board: BoardStyle,
+    /// Colors the players chose themselves, in turn order
+    player_colors: Vec<Color>,
+}
+
+impl Theme {
+    pub fn new(settings: &Settings) -> Self {
+        let glyphs = settings.glyphs.clone();
+        let board = settings.board_style;
+        match settings.color_mode {
+            ColorMode::Default => Self {
+                players: [
+                    Style::new(),
+                    Style::new(),
+                    Style::new().fg(Color::LightCyan),
+                    Style::new().fg(Color::LightMagenta),
+                ],
+                cursor: &[22, 28, 34, 40, 46],
+                win_line: Color::White,
+                highlight: Color::Indexed(58),
+                analysis: [Color::Red, Color::Yellow, Color::Green].map(|c| Style::new().fg(c)),
+                glyphs,
+                board,
+                player_colors: settings.player_colors.clone(),
+            },
+            ColorMode::HighContrast => Self {
+                players: [
+                    Style::new().fg(Color::White).add_modifier(Modifier::BOLD),
+                    Style::new()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                    Style::new()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    Style::new().fg(Color::White).add_modifier(Modifier::ITALIC),
+                ],
+                cursor: &[244, 248, 252, 255, 231],
+                win_line: Color::LightYellow,
+                highlight: Color::Indexed(240),
+                analysis: [
+                    Style::new().add_modifier(Modifier::DIM),
+                    Style::new(),
+                    Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                ],
+                glyphs,
+                board,
+                player_colors: settings.player_colors.clone(),
+            },
+            ColorMode::Colorblind => Self {
+                players: [
+                    Style::new()
+                        .fg(Color::Indexed(33))
+                        .add_modifier(Modifier::BOLD),
+                    Style::new()
+                        .fg(Color::Indexed(208))
+                        .add_modifier(Modifier::UNDERLINED),
+                    Style::new().fg(Color::Indexed(231)),
+                    Style::new()
+                        .fg(Color::Indexed(226))
+                        .add_modifier(Modifier::ITALIC),
+                ],
+                cursor: &[58, 100, 142, 184, 226],
+                win_line: Color::White,
+                highlight: Color::Indexed(24),
+                analysis: [
+                    Style::new().fg(Color::Indexed(208)),
+                    Style::new().fg(Color::Gray),
+                    Style::new()
+                        .fg(Color::Indexed(33))
+                        .add_modifier(Modifier::BOLD),
+                ],
+                glyphs,
+                board,
+                player_colors: settings.player_colors.clone(),
+            },
+        }
+    }
+
+    /// The style for player number `index`
+    pub fn player(&self, index: usize) -> Style {
+        let style = self.players[index % self.players.len()];
+        match self.player_colors.get(index) {
+            Some(&color) => style.fg(color),
+            None => style,
+        }
+    }
+
+    /// The style for saying it's player number `index`'s turn: their own color if they chose
+    /// one, and nothing otherwise
+    pub fn turn(&self, index: usize) -> Style {
+        match self.player_colors.get(index) {
+            Some(&color) => Style::new().fg(color),
+            None => Style::new(),
+        }
+    }
+
+    /// The color of the strikethrough on a line won by player number `index`
+    pub fn win_line(&self, index: usize) -> Color {
+        self.player_colors
+            .get(index)
+            .copied()
+            .unwrap_or(self.win_line)
+    }
+
+    /// The color a mark is drawn in, given the style for its square
+    ///
+    /// Canvases treat `Color::Reset` as "nothing drawn here", so that can't be used as a
+    /// default.
+    pub fn mark_color(style: Style) -> Color {
+        style.fg.unwrap_or(Color::White)
+    }
+}
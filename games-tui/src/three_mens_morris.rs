@@ -0,0 +1,169 @@
+//! Three Men's Morris: each player places three pieces, then takes turns moving one to an empty
+//! neighbouring square, until someone has a line.
+//!
+//! The board is drawn like a plugin's, a letter for each piece. Moving takes two presses: the
+//! first picks a piece up and marks where it can go, the second puts it down there.
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+pub use games_core::three_mens_morris::Position;
+use games_core::{three_mens_morris::Move, tic_tac_toe::board::parse_square};
+
+use crate::{
+    board_game::{self, BoardGame, Screen, Table},
+    bot::GameState,
+    grid::GridCursor,
+    keys::Action,
+    locale::{tr, trf},
+    player::Seat,
+    select::Selection,
+    settings::Settings,
+    status_bar::StatusBar,
+};
+
+pub struct Morris {
+    cursor: GridCursor,
+    selection: Selection,
+}
+
+/// Every state worth keeping a snapshot of, by name, drawn at `width` by `height`
+pub fn snapshots(width: u16, height: u16) -> Vec<(&'static str, Buffer)> {
+    // each a press on that square
+    const PLACED: &str = "a1 a3 b1 b3 c2 b2";
+    let states = [
+        ("placing", "a1 b2"),
+        ("selected", &format!("{PLACED} c2") as &str),
+        ("won", &format!("{PLACED} c2 c1")),
+    ];
+    states
+        .into_iter()
+        .map(|(name, keys)| {
+            let mut screen = Screen::<Morris>::for_snapshot(&Settings::default());
+            for square in keys.split_whitespace() {
+                screen.game.cursor.pos = parse_square(square).unwrap();
+                screen.press(Action::Place);
+            }
+            (name, screen.render(width, height))
+        })
+        .collect()
+}
+
+impl BoardGame for Morris {
+    type Position = Position;
+
+    const NAME: &'static str = "three-mens-morris";
+
+    fn title() -> &'static str {
+        tr("Three Men's Morris")
+    }
+
+    fn start(_settings: &Settings) -> Position {
+        Position::new()
+    }
+
+    fn new(_settings: &Settings) -> Self {
+        Self {
+            cursor: GridCursor::new(3, 3),
+            selection: Selection::default(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.selection.cancel();
+    }
+
+    /// A press on the square under the cursor picks a piece up or puts it down
+    fn input(
+        &mut self,
+        table: &Table<Self>,
+        _code: KeyCode,
+        action: Option<Action>,
+    ) -> Result<Option<Move>, String> {
+        match action {
+            Some(Action::Place) => {
+                let moves = table.position.legal_moves();
+                match self.selection.press(self.cursor.pos, &moves) {
+                    Some(mv) => return Ok(Some(mv)),
+                    None if self.selection.selected().is_none() => {
+                        return Err(tr("Can't play there").to_owned());
+                    }
+                    None => (),
+                }
+            }
+            Some(Action::Cancel) => self.selection.cancel(),
+            action => board_game::step(&mut self.cursor, action),
+        }
+        Ok(None)
+    }
+
+    /// The cursor follows the computer's moves
+    fn moved(&mut self, _table: &Table<Self>, mv: Move, _before: &Position) -> Option<String> {
+        self.cursor.pos = mv.to;
+        None
+    }
+
+    fn board(&self, table: &Table<Self>) -> Vec<Line<'static>> {
+        let over = table.over();
+        let targets = self.selection.targets(&table.position.legal_moves());
+        let mut lines = vec![Line::raw("    a  b  c ")];
+        for row in 0..3 {
+            let mut spans = vec![Span::raw(format!("{:>2} ", row + 1))];
+            for column in 0..3 {
+                let pos = row * 3 + column;
+                let piece = table.position.get(pos);
+                let mut style = match piece {
+                    Some(player) => table.theme.player(player.index()),
+                    None if targets.contains(&pos) => Style::new(),
+                    None => Style::new().add_modifier(Modifier::DIM),
+                };
+                if self.selection.selected() == Some(pos) {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                if self.cursor.pos == pos && !over {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                let text = match piece {
+                    Some(player) => format!(" {player} "),
+                    None if targets.contains(&pos) => " • ".to_owned(),
+                    None => " · ".to_owned(),
+                };
+                spans.push(Span::styled(text, style));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+
+    fn legend(&self, table: &Table<Self>) -> Vec<(String, &'static str)> {
+        let keys = &table.settings.keys;
+        let key = |action| keys.symbols(&[action]);
+        let moves = [Action::Left, Action::Up, Action::Down, Action::Right];
+        let mut legend = vec![(keys.symbols(&moves), tr("move"))];
+        if self.selection.selected().is_some() {
+            legend.push((key(Action::Place), tr("put down")));
+            legend.push((key(Action::Cancel), tr("put back")));
+        } else if table.position.in_hand(table.position.current_player()) > 0 {
+            legend.push((key(Action::Place), tr("place")));
+        } else {
+            legend.push((key(Action::Place), tr("pick up")));
+        }
+        legend
+    }
+
+    fn status(&self, table: &Table<Self>, status: StatusBar) -> StatusBar {
+        let status = table.turn(status);
+        if table.over() {
+            return status;
+        }
+        let in_hand = table.position.in_hand(table.position.current_player());
+        status.part(if in_hand > 0 {
+            trf("{} to place", &[&in_hand])
+        } else {
+            trf("draw in {} moves", &[&table.position.moves_left()])
+        })
+    }
+}
@@ -0,0 +1,2894 @@
+use core::fmt::Write;
+use std::{
+    array,
+    cell::Cell,
+    mem,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crossterm::{
+    event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind},
+    execute,
+    terminal::SetTitle,
+};
+use ratatui::{
+    layout::{
+        Constraint::{self, Fill, Length},
+        Layout, Margin, Rect,
+    },
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    text::{self, Span},
+    widgets::{
+        block::{Block, BorderType},
+        canvas::{Canvas, Circle, Context, Line, Rectangle},
+        Clear, List, ListState, Padding, Paragraph, Wrap,
+    },
+    Frame,
+};
+
+use crate::{
+    achievements::{Achievement, Achievements},
+    bot::{
+        script, AlphaBeta, Book, BookBot, Bot, BotKind, Budget, GameResult, GameState, Mcts,
+        Outcome, Script, Solver, Weighted, Worker,
+    },
+    clipboard,
+    commands::CommandLine,
+    crash, date, dirs,
+    event_log::{self, Value},
+    events::{self, AppEvent, Events},
+    frames::FrameStats,
+    grid::{Direction, GridCursor},
+    keys::Action,
+    locale::{tr, trf},
+    overlay::Overlay,
+    player::Seat,
+    rating,
+    rng::Rng,
+    select::Selection,
+    settings::{FirstPlayer, Settings},
+    sound::Cue,
+    stats::{self, Record},
+    status_bar::{self, StatusBar},
+    suspend,
+    theme::{BoardStyle, Theme},
+    time_control::{Clock, TimeControl},
+    tutorial::{Step, Tutorial},
+};
+
+use games_core::tic_tac_toe::{board, eval, image, puzzle};
+
+mod clock;
+mod commands;
+mod correspondence;
+mod review;
+mod sim;
+mod simul;
+
+pub use board::Handicap;
+pub use board::Position;
+use board::{parse_square, Board, MoveError, Player, Win, O, X};
+pub use correspondence::Correspondence;
+use puzzle::Puzzle;
+use review::Review;
+pub use sim::{snapshots, Simulation};
+pub use simul::run_simul;
+
+/// How long the strikethrough takes to draw across the winning line.
+const WIN_LINE_DURATION: Duration = Duration::from_millis(300);
+/// How long a newly placed mark takes to grow to full size.
+const PLACE_DURATION: Duration = Duration::from_millis(150);
+/// Below this width or height the board is drawn with one character per square.
+const COMPACT_WIDTH: u16 = 21;
+const COMPACT_HEIGHT: u16 = 15;
+/// Longest name a player can enter.
+pub const MAX_NAME_LEN: usize = 16;
+/// How long an "achievement unlocked" message is shown for.
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+/// How long a message stays in the status bar.
+const MESSAGE_DURATION: Duration = Duration::from_secs(4);
+/// Screens shorter than this leave out the key legend, to keep room for the board.
+const LEGEND_MIN_HEIGHT: u16 = 20;
+/// What daily puzzle solves are recorded as in the stats.
+const DAILY_STATS_NAME: &str = "tic-tac-toe-daily";
+/// How long the cursor takes to go from dim to bright and back again.
+const PULSE_PERIOD: Duration = Duration::from_millis(1200);
+/// How long after a game ends the next one starts, when auto-rematch is on.
+const REMATCH_DELAY: Duration = Duration::from_secs(3);
+/// How long the coin spins for when deciding who goes first, and how long the result is shown
+/// after that.
+const COIN_SPIN_DURATION: Duration = Duration::from_millis(800);
+const COIN_RESULT_DURATION: Duration = Duration::from_millis(700);
+
+/// The tutorial script: a game where X wins with a fork.
+const TUTORIAL: &[Step] = &[
+    Step {
+        prompt: "Welcome to tic-tac-toe! Take turns placing marks, and get three in a row to \
+                 win. Press space to continue.",
+        expect: None,
+        reply: None,
+    },
+    Step {
+        prompt: "Move the cursor with the arrow keys. Move to the center square and press space \
+                 to place your X there.",
+        expect: Some(4),
+        reply: Some(1),
+    },
+    Step {
+        prompt: "The center is part of four lines, so it's a strong start. O took an edge, \
+                 which is weaker. Take the top-left corner to threaten the diagonal.",
+        expect: Some(0),
+        reply: Some(8),
+    },
+    Step {
+        prompt: "O had to block. Now take the bottom-left corner: you'll threaten two lines at \
+                 once, which is called a fork.",
+        expect: Some(6),
+        reply: Some(3),
+    },
+    Step {
+        prompt: "O can only block one of your lines. Complete the other diagonal to win!",
+        expect: Some(2),
+        reply: None,
+    },
+    Step {
+        prompt: "You won! Look for forks in your own games, and block your opponent's. Press \
+                 space to start a real game.",
+        expect: None,
+        reply: None,
+    },
+];
+
+/// How long the computer waits before each move when it's playing itself, so it can be followed
+const ATTRACT_MOVE_DELAY: Duration = Duration::from_millis(1000);
+
+/// The demo script: each square played, and how long before it's played. O wins with a fork.
+const DEMO: &[(usize, Duration)] = &[
+    (1, Duration::from_millis(1000)),
+    (4, Duration::from_millis(1200)),
+    (7, Duration::from_millis(1000)),
+    (0, Duration::from_millis(1400)),
+    (8, Duration::from_millis(1000)),
+    (6, Duration::from_millis(1600)),
+    (3, Duration::from_millis(1000)),
+    (2, Duration::from_millis(1400)),
+];
+
+pub enum Mode {
+    /// Hot-seat games between two people
+    Normal,
+    /// One person playing X against the computer playing O
+    VsComputer,
+    /// Walk through `TUTORIAL` before playing normally
+    Tutorial,
+    /// Solve today's puzzle
+    DailyPuzzle,
+    /// Solve a puzzle from a pack
+    Puzzle(Puzzle),
+    /// Play `DEMO` over and over, until a key is pressed
+    Demo,
+    /// The computer playing both sides of one game, until a key is pressed
+    Attract,
+    /// Step through a game from the stats file
+    Replay(Replay),
+    /// One game of a tournament, between X's name then O's, which ends when the game does
+    Match([String; 2]),
+    /// One side of a game kept in a shared file, against whoever plays the other side
+    Correspondence(Correspondence),
+}
+
+/// A handicap like `x:b2 blocked:a1,c3`, checked that a game can start from it
+pub fn parse_handicap(value: &str) -> Result<Handicap, String> {
+    let handicap = Handicap::from_str(value)?;
+    Board::with_handicap(&handicap).map_err(|e| format!("can't start from {value}: {e}"))?;
+    Ok(handicap)
+}
+
+/// A finished game, ready to be stepped through
+pub struct Replay {
+    /// X's name then O's
+    names: [String; 2],
+    /// The board before the first move, from the game's handicap
+    start: Board,
+    moves: Vec<usize>,
+    duration: Duration,
+    finished: SystemTime,
+}
+
+impl Replay {
+    /// `None` unless `record` has every move of a game of tic-tac-toe
+    pub fn from_record(record: &Record) -> Option<Self> {
+        let [name_x, name_o] = &record.players[..] else {
+            return None;
+        };
+        if record.game != "tic-tac-toe" {
+            return None;
+        }
+        let handicap = Handicap::from_str(&record.setup).ok()?;
+        let start = Board::with_handicap(&handicap).ok()?;
+        let mut position = Position::from_board(start.clone(), X);
+        let mut moves = vec![];
+        for name in &record.moves {
+            let pos = position.parse_move(name)?;
+            position.apply(pos);
+            moves.push(pos);
+        }
+        position.result()?;
+        Some(Self {
+            names: [name_x.clone(), name_o.clone()],
+            start,
+            moves,
+            duration: record.duration,
+            finished: record.finished,
+        })
+    }
+
+    fn done(&self) -> Done {
+        let mut playing = Playing::from_position(self.start.clone(), X);
+        for &pos in &self.moves {
+            playing
+                .board
+                .apply_move(pos, playing.next)
+                .expect("checked in from_record");
+            playing.moves.push((pos, playing.next));
+            playing.next.toggle();
+        }
+        let win = playing.last_win();
+        let mut done = Done::new(playing, win, self.duration);
+        done.finished = Some(self.finished);
+        done
+    }
+}
+
+/// List the puzzles from every pack, and play whichever is chosen until the list is closed
+pub fn browse_puzzles(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+) -> std::io::Result<()> {
+    let dir = dirs::data_file("puzzles")?;
+    let (puzzles, errors) = puzzle::load_packs(&dir);
+    let mut list_state = ListState::default().with_selected(Some(0));
+    loop {
+        terminal.draw(|frame| {
+            let block = Block::bordered()
+                .title(tr("Puzzles"))
+                .title_bottom(text::Line::raw(tr("enter to play, q to go back")).centered())
+                .padding(Padding::uniform(1));
+            let inner = block.inner(frame.area());
+            frame.render_widget(block, frame.area());
+            let [list_area, errors_area] =
+                Layout::vertical([Fill(1), Length(errors.len().min(5) as u16)]).areas(inner);
+            if puzzles.is_empty() {
+                let text = trf("No puzzles found in {}", &[&dir.display()]);
+                frame.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }), list_area);
+            }
+            let list = List::new(puzzles.iter().map(|puzzle| puzzle.title.as_str()))
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+                .highlight_symbol("> ");
+            frame.render_stateful_widget(list, list_area, &mut list_state);
+            let errors = errors.iter().map(|e| text::Line::raw(e.as_str()));
+            let errors = Paragraph::new(errors.collect::<Vec<_>>()).style(Color::Red);
+            frame.render_widget(errors, errors_area);
+        })?;
+        let Event::Key(key) = events::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Up => list_state.select_previous(),
+            KeyCode::Down => list_state.select_next(),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Some(puzzle) = list_state.selected().and_then(|idx| puzzles.get(idx)) {
+                    run(terminal, settings, Mode::Puzzle(puzzle.clone()))?;
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// A game put aside with the suspend key, to carry on with `resume`
+pub struct Suspended {
+    game: Box<Game>,
+    /// When it was put aside
+    at: Instant,
+}
+
+/// Play until the players quit, or put the game aside
+pub fn run(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    mode: Mode,
+) -> std::io::Result<Option<Suspended>> {
+    let mut game = Game::new(settings, mode);
+    game.suspendable = true;
+    log("start", &[("mode", Value::Str(game.mode_name()))]);
+    run_suspendable(terminal, game)
+}
+
+/// Carry on with a game that was put aside, from where it was left
+pub fn resume(
+    terminal: &mut ratatui::DefaultTerminal,
+    suspended: Suspended,
+) -> std::io::Result<Option<Suspended>> {
+    let Suspended { mut game, at } = suspended;
+    game.resume(at.elapsed());
+    log("resume", &[]);
+    run_suspendable(terminal, *game)
+}
+
+/// The game loop, keeping the game if it's put aside
+fn run_suspendable(
+    terminal: &mut ratatui::DefaultTerminal,
+    mut game: Game,
+) -> std::io::Result<Option<Suspended>> {
+    let settings = game.settings.clone();
+    let result = run_game(terminal, &mut game, &settings);
+    crash::set_state(String::new());
+    result?;
+    if !game.suspended {
+        return Ok(None);
+    }
+    log("suspend", &[]);
+    let at = Instant::now();
+    let game = Box::new(game);
+    Ok(Some(Suspended { game, at }))
+}
+
+/// Let the computer play itself for one game, for the menu's attract mode. Returns whether the
+/// game got to the end, rather than being stopped by a key.
+pub fn attract(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+) -> std::io::Result<bool> {
+    let mut game = Game::new(settings, Mode::Attract);
+    log("start", &[("mode", Value::Str(game.mode_name()))]);
+    let result = run_game(terminal, &mut game, settings);
+    crash::set_state(String::new());
+    result?;
+    Ok(game.exit)
+}
+
+/// Play one game of a tournament between `names`, X's then O's. Returns the winner's index into
+/// `names`, or `Some(None)` for a draw, or `None` if they stopped before the end.
+pub fn play_match(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    names: [String; 2],
+) -> std::io::Result<Option<Option<usize>>> {
+    let mut game = Game::new(settings, Mode::Match(names));
+    log("start", &[("mode", Value::Str(game.mode_name()))]);
+    let result = run_game(terminal, &mut game, settings);
+    crash::set_state(String::new());
+    result?;
+    let done = match &game.state {
+        State::Done(done) => done,
+        State::Review(review) => &review.done,
+        _ => return Ok(None),
+    };
+    Ok(Some(done.winner().map(Player::index)))
+}
+
+/// Add a line to the event log, if it's on
+fn log(event: &str, fields: &[(&str, Value)]) {
+    event_log::log("tic-tac-toe", event, fields);
+}
+
+/// The game loop for `run`
+fn run_game(
+    terminal: &mut ratatui::DefaultTerminal,
+    game: &mut Game,
+    settings: &Settings,
+) -> std::io::Result<()> {
+    let mut events = Events::new(settings.tick_rate);
+    let mut title = String::new();
+    // what ticks can change, as of the last frame drawn
+    let mut drawn = None;
+    let mut input = true;
+    loop {
+        let view = game.tick_view();
+        if input || view.is_none() || view != drawn {
+            crash::set_state(game.dump());
+            let started = Instant::now();
+            terminal.draw(|frame| game.draw(frame))?;
+            game.frames.record(started);
+            game.announce();
+            let new_title = game.title();
+            if new_title != title {
+                execute!(std::io::stdout(), SetTitle(&new_title))?;
+                title = new_title;
+            }
+            drawn = view;
+        }
+        input = true;
+        let before = game.state_name();
+        let quit = match events.next()? {
+            AppEvent::Input(event) => handle_event(game, event),
+            AppEvent::Tick => {
+                game.tick();
+                input = false;
+                false
+            }
+            AppEvent::Wake => {
+                game.bot_move();
+                false
+            }
+        };
+        let after = game.state_name();
+        if after != before {
+            log(
+                "state",
+                &[("from", Value::Str(before)), ("to", Value::Str(after))],
+            );
+        }
+        if quit || game.exit {
+            break Ok(());
+        }
+    }
+}
+
+struct Game {
+    state: State,
+    player_first: Player,
+    /// Games won by each player
+    scores: [usize; Player::COUNT],
+    name_x: String,
+    name_o: String,
+    settings: Settings,
+    theme: Theme,
+    session_started: Instant,
+    tutorial: Option<Tutorial>,
+    puzzle: Option<ActivePuzzle>,
+    achievements: Achievements,
+    /// Name of the player who won the last game, and how many they've won in a row
+    streak: (String, usize),
+    /// Message about newly unlocked achievements, and when it was first shown
+    toast: Option<(String, Instant)>,
+    /// The last thing worth mentioning that happened, like a move that wasn't allowed, shown in
+    /// the status bar for a few seconds
+    message: Option<(String, Instant)>,
+    /// Set when the game is over and we should return
+    exit: bool,
+    /// Whether the game can be put aside to go back to the menu, as only a game started on its
+    /// own can
+    suspendable: bool,
+    /// Set when the game has been put aside, and should return rather than end
+    suspended: bool,
+    /// A command being typed after `:`
+    command_line: CommandLine,
+    /// Whether to show how each possible move would turn out
+    analysis: bool,
+    rng: Rng,
+    /// Kept between moves so positions aren't solved again
+    solver: Solver<Position>,
+    /// The computer opponent, which plays O
+    bot: Option<Worker<Position>>,
+    /// Where the demo is up to, when it's playing
+    demo: Option<Demo>,
+    /// Whether to save stats and achievements. Off in simulations.
+    save: bool,
+    /// Where the board was last drawn, to find which square the mouse is over
+    board_area: Cell<Rect>,
+    /// The square under the mouse pointer
+    hover: Option<usize>,
+    /// Whether to show the debug overlay
+    debug: bool,
+    /// The last event read, for the debug overlay
+    last_event: Option<Event>,
+    /// For the frame rate overlay and debug overlay
+    frames: FrameStats,
+    /// Looking at a game from the stats file, rather than playing
+    replay: bool,
+    /// Playing one game of a tournament
+    tournament: bool,
+    /// Playing a game kept in a shared file, with the other side's moves read from it
+    correspondence: Option<Correspondence>,
+    /// The last description sent out by `announce`
+    announced: Option<String>,
+}
+
+/// The computer opponent described by the settings, and any problems with them. Problems are
+/// worked around rather than stopping the game.
+fn make_bot(settings: &Settings) -> (impl Bot<Position> + Send, Vec<String>) {
+    let mut errors = vec![];
+    let bot: Box<dyn Bot<Position> + Send> = match settings.bot {
+        BotKind::AlphaBeta => match Weighted::new(&settings.evaluation, eval::feature) {
+            Ok(evaluator) => {
+                // a time budget means thinking for that long, rather than to a fixed depth
+                let time = match settings.bot_budget {
+                    Budget::Time(time) => Some(time),
+                    Budget::Iterations(_) => None,
+                };
+                Box::new(AlphaBeta::new(
+                    settings.bot_depth,
+                    time,
+                    Box::new(evaluator),
+                ))
+            }
+            Err(msg) => {
+                errors.push(msg);
+                Box::new(Mcts::new(settings.bot_budget))
+            }
+        },
+        BotKind::Mcts => Box::new(Mcts::new(settings.bot_budget)),
+    };
+    let bot = match &settings.bot_script {
+        Some(name) => match script::find(name) {
+            Ok(path) => Box::new(Script::new(path, settings.bot_script_limit, bot)),
+            Err(msg) => {
+                errors.push(msg);
+                bot
+            }
+        },
+        None => bot,
+    };
+    let book = match settings.bot_script {
+        // a script chooses all of its own moves
+        Some(_) => Ok(Book::empty()),
+        None => dirs::data_file("books/tic-tac-toe.txt")
+            .map_err(|e| e.to_string())
+            .and_then(|path| Book::load(&path, &Position::new())),
+    };
+    let book = book.unwrap_or_else(|msg| {
+        errors.push(msg);
+        Book::empty()
+    });
+    (BookBot::new(book, bot), errors)
+}
+
+/// Play each kind of computer player against each other kind, `games` times going first and
+/// `games` times going second, with the rest of `settings`. Returns the results to be recorded,
+/// with each player named like "Computer (mcts)".
+pub fn tournament(settings: &Settings, games: usize) -> Vec<Record> {
+    let mut bots: Vec<_> = BotKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let settings = Settings {
+                bot: kind,
+                ..settings.clone()
+            };
+            (format!("Computer ({})", kind.name()), make_bot(&settings).0)
+        })
+        .collect();
+    let mut records = vec![];
+    for first in 0..bots.len() {
+        for second in (0..bots.len()).filter(|&second| second != first) {
+            for _ in 0..games {
+                let started = Instant::now();
+                let mut position = Position::new();
+                let mut moves = vec![];
+                let result = loop {
+                    if let Some(result) = position.result() {
+                        break result;
+                    }
+                    let bot = match position.current_player() {
+                        X => first,
+                        O => second,
+                    };
+                    let mv = bots[bot]
+                        .1
+                        .choose_move(&position)
+                        .expect("the game isn't over");
+                    moves.push(position.move_name(mv));
+                    position.apply(mv);
+                };
+                let winner = match result {
+                    GameResult::Winner(X) => Some(bots[first].0.clone()),
+                    GameResult::Winner(O) => Some(bots[second].0.clone()),
+                    GameResult::Draw => None,
+                };
+                let players = vec![bots[first].0.clone(), bots[second].0.clone()];
+                let duration = started.elapsed();
+                records.push(Record::new("tic-tac-toe", players, winner, duration, moves));
+            }
+        }
+    }
+    records
+}
+
+/// A puzzle being attempted
+struct ActivePuzzle {
+    puzzle: Puzzle,
+    /// Days in a row the daily puzzle has been solved, if this is the daily puzzle
+    streak: Option<usize>,
+    /// Whether the last move tried was wrong
+    wrong: bool,
+}
+
+impl ActivePuzzle {
+    fn new(puzzle: Puzzle) -> Self {
+        Self {
+            puzzle,
+            streak: None,
+            wrong: false,
+        }
+    }
+
+    fn daily() -> Self {
+        let records = stats::load().unwrap_or_default();
+        Self {
+            streak: Some(stats::daily_streak(&records, DAILY_STATS_NAME)),
+            ..Self::new(Puzzle::daily(date::today()))
+        }
+    }
+
+    fn prompt(&self) -> String {
+        let mut prompt = format!("{}. ", self.puzzle.objective);
+        if self.wrong {
+            prompt.push_str(tr("That isn't the solution, try again! "));
+        }
+        if let Some(streak) = self.streak {
+            prompt.push_str(&trf("Streak: {} days.", &[&streak]));
+        }
+        prompt
+    }
+}
+
+enum State {
+    Borrowed,
+    EnterNames(EnterNames),
+    Playing(Playing),
+    /// The swap rule is on and the first move has been made: the second player can choose to
+    /// take it as their own
+    SwapOffer(Playing),
+    Done(Done),
+    /// Deciding who goes first, for the `Random` first player rule
+    CoinFlip(Instant),
+    /// Stepping back through a finished game
+    Review(Review),
+}
+
+impl Game {
+    fn new(settings: &Settings, mode: Mode) -> Self {
+        let mut toast = None;
+        let bot = match mode {
+            Mode::VsComputer | Mode::Attract => {
+                let (bot, errors) = make_bot(settings);
+                if !errors.is_empty() {
+                    toast = Some((errors.join("; "), Instant::now()));
+                }
+                Some(Worker::spawn(bot, events::wake))
+            }
+            _ => None,
+        };
+        let demo = match mode {
+            Mode::Demo => Some(Demo::new()),
+            Mode::Attract => Some(Demo::bots()),
+            _ => None,
+        };
+        let replay = matches!(mode, Mode::Replay(_));
+        let tournament = matches!(mode, Mode::Match(_));
+        let names = match &mode {
+            Mode::Replay(replay) => Some(replay.names.clone()),
+            Mode::Match(names) => Some(names.clone()),
+            Mode::Attract => Some(["Computer".to_owned(), "Computer".to_owned()]),
+            Mode::Correspondence(Correspondence { side: X, .. }) => {
+                Some(["You".to_owned(), "Opponent".to_owned()])
+            }
+            Mode::Correspondence(_) => Some(["Opponent".to_owned(), "You".to_owned()]),
+            _ => None,
+        };
+        let mut correspondence = None;
+        let (state, tutorial, puzzle) = match mode {
+            Mode::Normal => (State::EnterNames(EnterNames::new()), None, None),
+            Mode::VsComputer | Mode::Demo | Mode::Attract | Mode::Match(_) => {
+                (State::Playing(Playing::new(X)), None, None)
+            }
+            Mode::Tutorial => (
+                State::Playing(Playing::new(X)),
+                Some(Tutorial::new(TUTORIAL)),
+                None,
+            ),
+            Mode::DailyPuzzle | Mode::Puzzle(_) => {
+                let active = match mode {
+                    Mode::Puzzle(puzzle) => ActivePuzzle::new(puzzle),
+                    _ => ActivePuzzle::daily(),
+                };
+                let playing =
+                    Playing::from_position(active.puzzle.board.clone(), active.puzzle.to_move);
+                (State::Playing(playing), None, Some(active))
+            }
+            Mode::Replay(replay) => (State::Done(replay.done()), None, None),
+            Mode::Correspondence(game) => {
+                correspondence = Some(game);
+                (State::Playing(Playing::new(X)), None, None)
+            }
+        };
+        let [name_x, name_o] = names.unwrap_or_else(|| match bot {
+            Some(_) => ["You".to_owned(), "Computer".to_owned()],
+            None => ["X".to_owned(), "O".to_owned()],
+        });
+        let mut game = Self {
+            state,
+            scores: [0; Player::COUNT],
+            name_x,
+            name_o,
+            player_first: X,
+            settings: settings.clone(),
+            theme: Theme::new(settings),
+            session_started: Instant::now(),
+            tutorial,
+            puzzle,
+            achievements: Achievements::load(),
+            streak: (String::new(), 0),
+            toast,
+            message: None,
+            exit: false,
+            suspendable: false,
+            suspended: false,
+            command_line: CommandLine::default(),
+            analysis: false,
+            rng: Rng::from_time(),
+            solver: Solver::new(),
+            bot,
+            demo,
+            save: true,
+            board_area: Cell::new(Rect::ZERO),
+            hover: None,
+            debug: false,
+            last_event: None,
+            frames: FrameStats::default(),
+            replay,
+            tournament,
+            correspondence,
+            announced: None,
+        };
+        if game.replay {
+            game.input_review();
+        }
+        // now the game knows its mode, give it the clock and handicap that go with it
+        if matches!(game.state, State::Playing(_)) && game.puzzle.is_none() {
+            game.state = State::Playing(game.new_playing(X));
+        }
+        // catch up with the moves already in the file
+        game.correspondence_move();
+        game
+    }
+
+    fn input_mouse_moved(&mut self, column: u16, row: u16) {
+        self.hover = Board::square_at(self.board_area.get(), column, row);
+    }
+
+    /// Clicking a square is the same as moving the cursor there and pressing space
+    fn input_click(&mut self, column: u16, row: u16) {
+        let square = Board::square_at(self.board_area.get(), column, row);
+        match (&mut self.state, square) {
+            (State::Playing(playing), Some(square)) => {
+                playing.cursor.pos = square;
+                self.input_space();
+            }
+            (State::Review(review), Some(square)) => {
+                if let Err(text) = review.toggle_highlight(square) {
+                    self.toast = Some((text, Instant::now()));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn input_cancel(&mut self) {
+        if let State::Playing(playing) = &mut self.state {
+            playing.selection.cancel();
+        }
+    }
+
+    /// `wrap` is whether the cursor goes round to the other side of the board at the edge
+    fn input_left(&mut self, wrap: bool) {
+        match &mut self.state {
+            State::Playing(playing) => {
+                playing.move_cursor(Direction::Left, wrap, self.settings.skip_taken)
+            }
+            State::Review(review) => review.back(),
+            _ => (),
+        }
+    }
+
+    fn input_right(&mut self, wrap: bool) {
+        match &mut self.state {
+            State::Playing(playing) => {
+                playing.move_cursor(Direction::Right, wrap, self.settings.skip_taken)
+            }
+            State::Review(review) => review.forward(),
+            _ => (),
+        }
+    }
+
+    fn input_up(&mut self, wrap: bool) {
+        if let State::Playing(playing) = &mut self.state {
+            playing.move_cursor(Direction::Up, wrap, self.settings.skip_taken);
+        }
+    }
+
+    fn input_down(&mut self, wrap: bool) {
+        if let State::Playing(playing) = &mut self.state {
+            playing.move_cursor(Direction::Down, wrap, self.settings.skip_taken);
+        }
+    }
+
+    /// Whether keys should go to `input_text` rather than being treated as commands
+    fn is_typing(&self) -> bool {
+        match &self.state {
+            State::EnterNames(_) => true,
+            State::Playing(playing) => !playing.typed.is_empty(),
+            State::Review(review) => review.editing.is_some() || !review.typed.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Whether `code` starts typing the name of a square to play, or to highlight when reviewing.
+    /// Keys used for something else don't, but squares can be typed in either case, so `A1`
+    /// works even when `a` is bound.
+    fn starts_typing_move(&self, code: KeyCode) -> bool {
+        matches!(self.state, State::Playing(_) | State::Review(_))
+            && matches!(code, KeyCode::Char(ch) if ch.is_ascii_alphanumeric())
+            && self.settings.keys.action(code).is_none()
+    }
+
+    fn input_text(&mut self, code: KeyCode) {
+        if let State::Playing(_) = self.state {
+            return self.input_move_text(code);
+        }
+        if let State::Review(_) = self.state {
+            return self.input_review_text(code);
+        }
+        let State::EnterNames(form) = &mut self.state else {
+            return;
+        };
+        match code {
+            KeyCode::Char(ch) if form.names[form.field].chars().count() < MAX_NAME_LEN => {
+                form.names[form.field].push(ch)
+            }
+            KeyCode::Backspace => {
+                form.names[form.field].pop();
+            }
+            KeyCode::Up | KeyCode::Down | KeyCode::Tab | KeyCode::BackTab => {
+                form.field = 1 - form.field
+            }
+            KeyCode::Enter if form.field == 0 => form.field = 1,
+            KeyCode::Enter | KeyCode::Esc => {
+                let [name_x, name_o] =
+                    mem::take(&mut form.names).map(|name| name.trim().to_owned());
+                if !name_x.is_empty() {
+                    self.name_x = name_x;
+                }
+                if !name_o.is_empty() {
+                    self.name_o = name_o;
+                }
+                if self.settings.first_player == FirstPlayer::Random {
+                    self.flip_coin();
+                } else {
+                    self.state = State::Playing(self.new_playing(self.player_first));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Typing a square name like `b2`, and then enter to play there
+    fn input_move_text(&mut self, code: KeyCode) {
+        let State::Playing(playing) = &mut self.state else {
+            return;
+        };
+        match code {
+            KeyCode::Char(ch) if playing.typed.len() < 2 => playing.typed.push(ch),
+            KeyCode::Backspace => {
+                playing.typed.pop();
+            }
+            KeyCode::Esc => playing.typed.clear(),
+            KeyCode::Enter => {
+                let typed = mem::take(&mut playing.typed);
+                match parse_square(&typed) {
+                    Some(pos) => {
+                        playing.cursor.pos = pos;
+                        self.input_space();
+                    }
+                    None => self.show_message(trf("{} isn't a square", &[&typed])),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// The game in words, for screen readers: the last move, what happens next, and where each
+    /// player has played
+    fn announcement(&self) -> Option<String> {
+        let (board, moves) = match &self.state {
+            State::Playing(playing) | State::SwapOffer(playing) => (&playing.board, &playing.moves),
+            State::Done(done) => (&done.board, &done.moves),
+            State::Review(review) => return Some(self.review_text(review)),
+            State::EnterNames(_) | State::CoinFlip(_) | State::Borrowed => return None,
+        };
+        let mut parts = vec![];
+        if let Some(&(pos, player)) = moves.last() {
+            parts.push(trf(
+                "{} played {}",
+                &[&self.name(player), &square_words(pos)],
+            ));
+        }
+        parts.push(match &self.state {
+            State::SwapOffer(playing) => trf("{} can swap sides", &[&self.name(playing.next)]),
+            State::Done(done) => match done.winner() {
+                Some(winner) => trf("{} won", &[&self.name(winner)]),
+                None => tr("draw").to_owned(),
+            },
+            State::Playing(playing) => trf("{} to play", &[&self.name(playing.next)]),
+            _ => unreachable!(),
+        });
+        let mut text = format!("{}.", parts.join("; "));
+        for player in [X, O] {
+            let squares: Vec<_> = (0..9)
+                .filter(|&pos| board.get(pos) == Some(player))
+                .map(square_words)
+                .collect();
+            if !squares.is_empty() {
+                let has = trf("{} has {}.", &[&self.name(player), &squares.join(", ")]);
+                write!(&mut text, " {has}").unwrap();
+            }
+        }
+        Some(text)
+    }
+
+    /// Send the description of the game out as set by the `announce` setting, if it's changed
+    fn announce(&mut self) {
+        let text = self.announcement();
+        if text == self.announced {
+            return;
+        }
+        if let Some(text) = &text {
+            // the game itself is still there to look at
+            let _ = self.settings.announce.send(text);
+        }
+        self.announced = text;
+    }
+
+    /// The line for screen readers: the description of the game, and what's under the cursor
+    fn screen_reader_line(&self) -> String {
+        let mut line = self.announcement().unwrap_or_default();
+        if let State::Playing(playing) = &self.state {
+            let pos = playing.cursor.pos;
+            let contents = match playing.board.get(pos) {
+                Some(player) => self.name(player).to_owned(),
+                None if playing.board.is_blocked(pos) => tr("blocked").to_owned(),
+                None => tr("empty").to_owned(),
+            };
+            let cursor = trf("Cursor on {}: {}.", &[&square_words(pos), &contents]);
+            write!(&mut line, " {cursor}").unwrap();
+        }
+        if let Some((message, _)) = &self.message {
+            write!(&mut line, " {message}").unwrap();
+        }
+        line
+    }
+
+    /// Show `text` in the status bar for a while, in place of any message before it
+    fn show_message(&mut self, text: String) {
+        self.message = Some((text, Instant::now()));
+    }
+
+    /// How `done` turned out, like "X won!"
+    fn result(&self, done: &Done) -> String {
+        match (&done.win, done.out_of_time) {
+            _ if self.puzzle.is_some() => tr("Solved!").to_owned(),
+            (Some((_, player)), _) => trf("{} won!", &[&self.name(*player)]),
+            (None, Some(loser)) => trf("{} won on time!", &[&self.name(loser.other())]),
+            (None, None) => tr("draw").to_owned(),
+        }
+    }
+
+    /// Copy the finished game to the clipboard, as text to paste anywhere: the result, the
+    /// board and the moves
+    fn input_share(&mut self) {
+        let State::Done(done) = &self.state else {
+            return;
+        };
+        let mut text = format!("tic-tac-toe: {}\n", self.result(done));
+        for row in done.board.to_string().lines() {
+            let squares: Vec<_> = row.chars().map(String::from).collect();
+            writeln!(text, "{}", squares.join(" ")).unwrap();
+        }
+        let moves: Vec<_> = done
+            .moves
+            .iter()
+            .map(|&(pos, _)| board::square_name(pos))
+            .collect();
+        if !moves.is_empty() {
+            text.push_str(&trf("moves: {}", &[&moves.join(" ")]));
+        }
+        let message = match clipboard::copy(text.trim_end()) {
+            Ok(()) => tr("game copied").to_owned(),
+            Err(e) => trf("could not copy: {}", &[&e]),
+        };
+        self.show_message(message);
+    }
+
+    /// Type into the command line, running the command when it's finished
+    fn input_command(&mut self, code: KeyCode) {
+        let Some(line) = self.command_line.input(code) else {
+            return;
+        };
+        match commands::registry().run(self, &line) {
+            Ok(Some(text)) | Err(text) => self.show_message(text),
+            Ok(None) => (),
+        }
+    }
+
+    fn cue(&self, cue: Cue) {
+        // sounds are only a nicety
+        let _ = self.settings.sound.play(cue);
+    }
+
+    fn name(&self, player: Player) -> &str {
+        match player {
+            X => &self.name_x,
+            O => &self.name_o,
+        }
+    }
+
+    fn input_space(&mut self) {
+        match self.borrow() {
+            State::Playing(playing) if self.bot.is_some() && playing.next == O => {
+                // wait for the computer
+                self.state = State::Playing(playing);
+            }
+            State::Playing(playing)
+                if self
+                    .correspondence
+                    .as_ref()
+                    .is_some_and(|correspondence| correspondence.side != playing.next) =>
+            {
+                self.show_message(trf("Waiting for {}'s move", &[&self.name(playing.next)]));
+                self.state = State::Playing(playing);
+            }
+            State::Playing(mut playing) if self.correspondence.is_some() => {
+                // the move only counts once it's in the file, where the other side can see it
+                let pos = playing.cursor.pos;
+                let moved = match playing.board.clone().apply_move(pos, playing.next) {
+                    Ok(_) => match self.correspondence.as_ref().unwrap().send(pos) {
+                        Ok(()) => playing.play(pos).is_ok(),
+                        Err(e) => {
+                            self.show_message(trf("Could not save the move: {}", &[&e]));
+                            false
+                        }
+                    },
+                    Err(e) => {
+                        self.show_message(trf("Can't play there: {}", &[&e]));
+                        self.cue(Cue::Illegal);
+                        false
+                    }
+                };
+                self.after_move(playing, moved);
+            }
+            State::Playing(mut playing) => {
+                let step = self.tutorial.as_ref().and_then(Tutorial::step);
+                if let Some(step) = step {
+                    if step.expect.is_none() {
+                        self.tutorial.as_mut().unwrap().advance();
+                    }
+                    if step.expect != Some(playing.cursor.pos) {
+                        // either a "press space" step, or not the move the tutorial asked for
+                        self.state = State::Playing(playing);
+                        return;
+                    }
+                }
+                if self.puzzle.is_some() {
+                    return self.try_solution(playing);
+                }
+                let moved = match playing.make_move() {
+                    Ok(()) => true,
+                    Err(e) => {
+                        self.show_message(trf("Can't play there: {}", &[&e]));
+                        self.cue(Cue::Illegal);
+                        false
+                    }
+                };
+                if let (true, Some(step)) = (moved, step) {
+                    if let Some(reply) = step.reply {
+                        playing
+                            .play(reply)
+                            .expect("the tutorial's replies are legal");
+                    }
+                    self.tutorial.as_mut().unwrap().advance();
+                }
+                self.after_move(playing, moved);
+            }
+            state @ State::Done(_) if self.ends_after_one() => {
+                self.state = state;
+                self.exit = true
+            }
+            State::Done(done) => self.next_game(done.winner()),
+            State::CoinFlip(_) => self.state = State::Playing(self.new_playing(self.player_first)),
+
+            State::Review(review) => self.state = State::Done(review.done),
+            state @ (State::EnterNames(_) | State::SwapOffer(_)) => self.state = state,
+            State::Borrowed => unreachable!(),
+        }
+    }
+
+    /// Work out what happens next, now a mark might have been placed at the cursor
+    fn after_move(&mut self, mut playing: Playing, moved: bool) {
+        if moved && self.settings.animate() {
+            playing.placing = Some(Placing::new(playing.cursor.pos));
+        }
+        // the computer doesn't know how to answer a swap offer
+        // nor is there a way to offer one through a shared file
+        let swap = self.settings.swap_rule
+            && self.bot.is_none()
+            && self.demo.is_none()
+            && self.correspondence.is_none();
+        if moved && swap && playing.board.count() == 1 {
+            self.cue(Cue::Place);
+            self.state = State::SwapOffer(playing);
+        } else if let Some(win) = playing.last_win() {
+            self.finish(playing, Some(win), None);
+        } else if playing.board.is_full() {
+            self.finish(playing, None, None);
+        } else {
+            if moved {
+                self.cue(Cue::Place);
+            }
+            self.state = State::Playing(playing);
+            // start the computer thinking now, rather than at the next tick
+            self.bot_move();
+        }
+    }
+
+    /// Let the computer move, if it's its turn and the last mark has finished appearing
+    fn bot_move(&mut self) {
+        let (Some(bot), State::Playing(playing)) = (&mut self.bot, &self.state) else {
+            return;
+        };
+        // playing itself, the computer moves for both sides, but waits to let each move be seen
+        let attract = self.demo.as_mut().filter(|demo| demo.bots);
+        if playing.next != O && attract.is_none() || playing.placing.is_some() {
+            return;
+        }
+        if !bot.is_thinking() {
+            bot.think(playing.position());
+        }
+        if attract
+            .as_ref()
+            .is_some_and(|demo| demo.moved.elapsed() < ATTRACT_MOVE_DELAY)
+        {
+            return;
+        }
+        let Some(pos) = bot.poll() else {
+            return;
+        };
+        if let Some(demo) = attract {
+            demo.moved = Instant::now();
+        }
+        let State::Playing(mut playing) = self.borrow() else {
+            unreachable!()
+        };
+        playing.cursor.pos = pos;
+        let moved = playing.make_move().is_ok();
+        self.after_move(playing, moved);
+    }
+
+    /// Play the other side's moves, once they've appeared in the shared file
+    fn correspondence_move(&mut self) {
+        let (Some(correspondence), State::Playing(playing)) =
+            (&mut self.correspondence, &self.state)
+        else {
+            return;
+        };
+        let Some(moves) = correspondence.poll() else {
+            return;
+        };
+        let moves = match moves {
+            Ok(moves) => moves,
+            Err(e) => return self.show_message(trf("Could not read the game file: {}", &[&e])),
+        };
+        let known = playing.moves.len();
+        if moves.len() <= known {
+            return;
+        }
+        if moves[..known] != playing.moves[..] {
+            return self.show_message(tr("The game file doesn't match this game").to_owned());
+        }
+        let State::Playing(mut playing) = self.borrow() else {
+            unreachable!()
+        };
+        for &(pos, player) in &moves[known..] {
+            let played = if player == playing.next {
+                playing.play(pos).map_err(|e| e.to_string())
+            } else {
+                Err(tr("a move is out of turn").to_owned())
+            };
+            if let Err(e) = played {
+                self.show_message(trf(
+                    "The game file has a move that can't be played: {}",
+                    &[&e],
+                ));
+                break;
+            }
+            playing.cursor.pos = pos;
+            if playing.last_win().is_some() || playing.board.is_full() {
+                break;
+            }
+        }
+        let moved = playing.moves.len() > known;
+        self.after_move(playing, moved);
+    }
+
+    /// Move the cursor to the demo's next square and then play there, or start the demo again
+    /// once its game is over
+    fn demo_move(&mut self) {
+        let Some(demo) = &mut self.demo else {
+            return;
+        };
+        match &mut self.state {
+            // the moves are the computer's, and one game is enough
+            State::Done(done) if demo.bots && done.started.elapsed() >= REMATCH_DELAY => {
+                self.exit = true;
+            }
+            _ if demo.bots => (),
+            State::Playing(playing) if playing.placing.is_none() => {
+                let Some((square, delay)) = DEMO.get(demo.step) else {
+                    return;
+                };
+                let waited = demo.moved.elapsed();
+                // give the cursor time to be seen on the square before playing there
+                if waited >= *delay / 2 {
+                    playing.cursor.pos = *square;
+                }
+                if waited < *delay {
+                    return;
+                }
+                demo.step += 1;
+                demo.moved = Instant::now();
+                let State::Playing(mut playing) = self.borrow() else {
+                    unreachable!()
+                };
+                let moved = playing.make_move().is_ok();
+                self.after_move(playing, moved);
+            }
+            State::Done(done) if done.started.elapsed() >= REMATCH_DELAY => {
+                *demo = Demo::new();
+                self.state = State::Playing(self.new_playing(X));
+            }
+            _ => (),
+        }
+    }
+
+    /// The handicap the next game starts from, if any: the one in the settings, with some
+    /// squares blocked at random for `random_blocks`. Like the clock, only games that count have
+    /// one. The shared file doesn't say what the game started from, so games through one don't.
+    fn next_handicap(&mut self) -> Option<Handicap> {
+        if !self.counts() || self.correspondence.is_some() {
+            return None;
+        }
+        let mut handicap = self.settings.handicap.clone();
+        if self.settings.random_blocks {
+            handicap = handicap.with_random_blocks(&mut self.rng);
+        }
+        (!handicap.is_empty()).then_some(handicap)
+    }
+
+    /// Whether the game ends after one, rather than going on to the next
+    fn ends_after_one(&self) -> bool {
+        self.puzzle.is_some() || self.replay || self.tournament || self.correspondence.is_some()
+    }
+
+    /// Whether this isn't the tutorial, the demo or a puzzle
+    fn counts(&self) -> bool {
+        self.tutorial.is_none() && self.demo.is_none() && self.puzzle.is_none()
+    }
+
+    fn new_playing(&mut self, first_player: Player) -> Playing {
+        let setup = self.next_handicap();
+        let board = match &setup {
+            // random blocks only go on empty squares, and always leave one
+            Some(handicap) => {
+                Board::with_handicap(handicap).expect("checked when the settings were read")
+            }
+            None => Board::new(),
+        };
+        let mut playing = Playing::from_position(board, first_player);
+        playing.clock = Clock::new(self.time_control(), first_player);
+        playing.setup = setup;
+        playing
+    }
+
+    /// Start a new game, with who goes first decided by the `first_player` setting
+    fn next_game(&mut self, winner: Option<Player>) {
+        if let Some(tutorial) = &mut self.tutorial {
+            tutorial.advance();
+            if tutorial.step().is_none() {
+                self.tutorial = None;
+            }
+        }
+        self.player_first = match (self.settings.first_player, winner) {
+            (FirstPlayer::Alternate, _) | (FirstPlayer::Loser | FirstPlayer::Winner, None) => {
+                self.player_first.other()
+            }
+            (FirstPlayer::Loser, Some(winner)) => winner.other(),
+            (FirstPlayer::Winner, Some(winner)) => winner,
+            (FirstPlayer::AlwaysX, _) => X,
+            (FirstPlayer::Random, _) => return self.flip_coin(),
+        };
+        self.state = State::Playing(self.new_playing(self.player_first));
+    }
+
+    /// Pick who goes first at random, showing a coin flip if animations are on
+    fn flip_coin(&mut self) {
+        self.player_first = [X, O][self.rng.below(2)];
+        self.state = if self.settings.animate() {
+            State::CoinFlip(Instant::now())
+        } else {
+            State::Playing(self.new_playing(self.player_first))
+        };
+    }
+
+    /// Stop the next game from starting by itself
+    fn cancel_rematch(&mut self) {
+        if let State::Done(done) = &mut self.state {
+            done.rematch = false;
+        }
+    }
+
+    /// Carry on after being put aside for `gap`, which doesn't count towards the game's time or
+    /// anyone's clock
+    fn resume(&mut self, gap: Duration) {
+        self.suspended = false;
+        match &mut self.state {
+            State::Playing(playing) | State::SwapOffer(playing) => {
+                playing.started += gap;
+                playing.clock.skip(gap);
+            }
+            State::Done(done) => done.started += gap,
+            State::CoinFlip(started) => *started += gap,
+            State::Borrowed | State::EnterNames(_) | State::Review(_) => (),
+        }
+        // the computer may have finished thinking while the game was put aside
+        self.bot_move();
+    }
+
+    /// Check whether the move at the cursor solves the puzzle
+    fn try_solution(&mut self, mut playing: Playing) {
+        let solver = self.name(playing.next).to_owned();
+        let active = self.puzzle.as_mut().unwrap();
+        if let Err(e) = playing
+            .board
+            .clone()
+            .apply_move(playing.cursor.pos, playing.next)
+        {
+            self.show_message(trf("Can't play there: {}", &[&e]));
+            self.cue(Cue::Illegal);
+        } else if active.puzzle.solutions.contains(&playing.cursor.pos) {
+            playing.make_move().expect("checked above");
+            let duration = playing.started.elapsed();
+            if active.streak.is_some() && self.save {
+                let record = Record::new(DAILY_STATS_NAME, vec![], Some(solver), duration, vec![]);
+                // stats are nice to have, so don't interrupt the game if they can't be saved
+                let _ = stats::record(&record);
+                let records = stats::load().unwrap_or_default();
+                active.streak = Some(stats::daily_streak(&records, DAILY_STATS_NAME));
+            }
+            active.wrong = false;
+            let win = playing.last_win();
+            self.log_result(Some(playing.next.other()), duration);
+            self.cue(Cue::GameOver);
+            self.state = State::Done(Done::new(playing, win, duration));
+            return;
+        } else {
+            active.wrong = true;
+        }
+        self.state = State::Playing(playing);
+    }
+
+    /// Whether the solver's opinion can be shown. Not in the tutorial or puzzles, where it would
+    /// give the answers away.
+    fn can_analyse(&self) -> bool {
+        self.tutorial.is_none() && self.puzzle.is_none()
+    }
+
+    fn toggle_analysis(&mut self) {
+        if self.can_analyse() {
+            self.analysis = !self.analysis;
+        }
+    }
+
+    /// Answer a yes/no question
+    fn input_confirm(&mut self, yes: bool) {
+        match self.borrow() {
+            State::SwapOffer(playing) => {
+                if yes {
+                    // the players change sides, taking their names and scores with them
+                    mem::swap(&mut self.name_x, &mut self.name_o);
+                    self.scores.swap(X.index(), O.index());
+                    self.show_message(tr("Sides swapped").to_owned());
+                }
+                self.state = State::Playing(playing);
+            }
+            state => self.state = state,
+        }
+    }
+
+    /// End the game, won with a line in `win` or by the other player running `out_of_time`
+    fn finish(
+        &mut self,
+        mut playing: Playing,
+        win: Option<(Win, Player)>,
+        out_of_time: Option<Player>,
+    ) {
+        playing.clock.stop();
+        self.cue(Cue::GameOver);
+        let duration = playing.started.elapsed();
+        let winner = win
+            .as_ref()
+            .map(|(_, player)| *player)
+            .or(out_of_time.map(Player::other));
+        self.log_result(winner, duration);
+        let mut finished = None;
+        // the tutorial and demo games don't count
+        if self.tutorial.is_none() && self.demo.is_none() {
+            if let Some(winner) = winner {
+                self.scores[winner.index()] += 1;
+            }
+            let players = vec![self.name_x.clone(), self.name_o.clone()];
+            let winner_name = winner.map(|player| self.name(player).to_owned());
+            if self.save {
+                // stats are nice to have, so don't interrupt the game if they can't be saved
+                let moves = playing
+                    .moves
+                    .iter()
+                    .map(|&(pos, _)| board::square_name(pos))
+                    .collect();
+                let mut record = Record::new("tic-tac-toe", players, winner_name, duration, moves);
+                if let Some(handicap) = &playing.setup {
+                    record.setup = handicap.to_string();
+                }
+                match stats::record(&record) {
+                    Ok(()) => {
+                        let _ = rating::record(&record);
+                        finished = Some(record.finished);
+                        self.show_message(tr("Game saved").to_owned());
+                    }
+                    Err(e) => self.show_message(trf("could not save the game: {}", &[&e])),
+                }
+            }
+            self.check_achievements(&playing, winner);
+        }
+        let mut done = Done::new(playing, win, duration);
+        done.out_of_time = out_of_time;
+        done.finished = finished;
+        done.rematch =
+            self.settings.auto_rematch && self.tutorial.is_none() && !self.ends_after_one();
+        self.state = State::Done(done);
+    }
+
+    /// `winner` is `None` for a draw
+    fn log_result(&self, winner: Option<Player>, duration: Duration) {
+        let mark = winner.map(|player| player.to_string());
+        let mark = mark.as_deref().map_or(Value::Null, Value::Str);
+        let name = winner.map_or(Value::Null, |player| Value::Str(self.name(player)));
+        let duration = Value::Num(duration.as_millis() as u64);
+        log(
+            "result",
+            &[
+                ("winner", mark),
+                ("winner_name", name),
+                ("duration_ms", duration),
+            ],
+        );
+    }
+
+    /// Unlock any achievements earned by the game that just finished
+    fn check_achievements(&mut self, playing: &Playing, winner: Option<Player>) {
+        let mut earned = vec![];
+        match winner {
+            // against the computer, only the player's own wins count, and losing ends a streak
+            Some(player) if self.bot.is_some() && player != X => {
+                self.streak = (String::new(), 0);
+            }
+            Some(player) => {
+                earned.push(Achievement::FirstWin);
+                if playing.board.get(4) != Some(player) {
+                    earned.push(Achievement::NoCenter);
+                }
+                if playing.started.elapsed() < Duration::from_secs(10) {
+                    earned.push(Achievement::QuickWin);
+                }
+                let name = self.name(player).to_owned();
+                if self.streak.0 == name {
+                    self.streak.1 += 1;
+                } else {
+                    self.streak = (name, 1);
+                }
+                if self.streak.1 >= 10 {
+                    earned.push(Achievement::Streak);
+                }
+                if self.bot.is_some() {
+                    earned.push(Achievement::BeatBot);
+                }
+            }
+            None => {
+                earned.push(Achievement::FirstDraw);
+                self.streak = (String::new(), 0);
+            }
+        }
+        let unlocked: Vec<_> = earned
+            .into_iter()
+            .filter(|achievement| self.achievements.unlock(*achievement))
+            .map(Achievement::name)
+            .collect();
+        if !unlocked.is_empty() {
+            let text = trf("Achievement unlocked: {}", &[&unlocked.join(", ")]);
+            self.toast = Some((text, Instant::now()));
+        }
+    }
+
+    fn tick(&mut self) {
+        if self
+            .toast
+            .as_ref()
+            .is_some_and(|(_, shown)| shown.elapsed() >= TOAST_DURATION)
+        {
+            self.toast = None;
+        }
+        if self
+            .message
+            .as_ref()
+            .is_some_and(|(_, shown)| shown.elapsed() >= MESSAGE_DURATION)
+        {
+            self.message = None;
+        }
+        if let State::Playing(playing) = &mut self.state {
+            if !self.analysis {
+                playing.analysis = None;
+            } else if playing.analysis.is_none() {
+                playing.analysis = Some(analyse(&mut self.solver, &playing.position()));
+            }
+        }
+        self.bot_move();
+        self.correspondence_move();
+        self.demo_move();
+        self.check_clock();
+        match &mut self.state {
+            State::Playing(playing) | State::SwapOffer(playing) => {
+                playing.tick(!self.settings.reduced_motion)
+            }
+            State::Done(done) => done.tick(self.settings.animate()),
+            State::Review(_) | State::CoinFlip(_) | State::EnterNames(_) | State::Borrowed => (),
+        }
+        match &self.state {
+            State::Done(done) if done.rematch && done.started.elapsed() >= REMATCH_DELAY => {
+                self.next_game(done.winner())
+            }
+            State::CoinFlip(started)
+                if started.elapsed() >= COIN_SPIN_DURATION + COIN_RESULT_DURATION =>
+            {
+                self.state = State::Playing(self.new_playing(self.player_first))
+            }
+            _ => (),
+        }
+    }
+
+    /// Everything on screen that a tick can change, so ticks that leave it alone don't need
+    /// drawing. `None` while something is animating, when every tick does.
+    fn tick_view(&self) -> Option<String> {
+        let animating = match &self.state {
+            State::Playing(playing) | State::SwapOffer(playing) => playing.placing.is_some(),
+            State::Done(done) => done.line_progress < 1.,
+            State::CoinFlip(_) => true,
+            State::Review(_) | State::EnterNames(_) | State::Borrowed => false,
+        };
+        if animating {
+            return None;
+        }
+        let mut view = self.dump();
+        let toast = self.toast.as_ref().map(|(toast, _)| toast);
+        let message = self.message.as_ref().map(|(message, _)| message);
+        writeln!(&mut view, "toast: {toast:?}, message: {message:?}").unwrap();
+        // the timers are shown to the second
+        writeln!(
+            &mut view,
+            "time: {} {}",
+            self.game_time().as_secs(),
+            self.session_started.elapsed().as_secs()
+        )
+        .unwrap();
+        match &self.state {
+            State::Playing(playing) | State::SwapOffer(playing) => {
+                // the cursor's pulse only has a few distinct colors
+                let cursor = playing.cursor_color(&self.theme);
+                let analysis = playing.analysis.is_some();
+                let clock = playing.clock.label();
+                writeln!(&mut view, "{cursor:?} {analysis} {clock:?}").unwrap();
+            }
+            State::Done(done) if done.rematch => {
+                let countdown = done.started.elapsed().as_secs();
+                writeln!(&mut view, "rematch in: {countdown}").unwrap();
+            }
+            _ => (),
+        }
+        Some(view)
+    }
+
+    /// How long the game being played, or last played, has taken
+    fn game_time(&self) -> Duration {
+        match &self.state {
+            State::Playing(playing) | State::SwapOffer(playing) => playing.started.elapsed(),
+            State::Done(done) => done.duration,
+            State::Review(review) => review.done.duration,
+            State::CoinFlip(_) | State::EnterNames(_) | State::Borrowed => Duration::ZERO,
+        }
+    }
+
+    /// Title for the terminal window
+    fn title(&self) -> String {
+        format!(
+            "tic-tac-toe — {} {} : {} {}",
+            self.name_x,
+            self.scores[X.index()],
+            self.scores[O.index()],
+            self.name_o
+        )
+    }
+
+    fn mode_name(&self) -> &'static str {
+        if self.demo.is_some() {
+            "demo"
+        } else if self.tutorial.is_some() {
+            "tutorial"
+        } else if self.puzzle.is_some() {
+            "puzzle"
+        } else if self.replay {
+            "replay"
+        } else if self.tournament {
+            "tournament"
+        } else if self.correspondence.is_some() {
+            "by file"
+        } else if self.bot.is_some() {
+            "against the computer"
+        } else {
+            "hot-seat"
+        }
+    }
+
+    /// Everything worth knowing about the game for a crash report
+    fn dump(&self) -> String {
+        let mut dump = format!("state: {}\n", self.state_name());
+        writeln!(&mut dump, "mode: {}", self.mode_name()).unwrap();
+        writeln!(
+            &mut dump,
+            "score: {} (X) {} : {} {} (O)",
+            self.name_x,
+            self.scores[X.index()],
+            self.scores[O.index()],
+            self.name_o
+        )
+        .unwrap();
+        let (board, moves) = match &self.state {
+            State::Playing(playing) | State::SwapOffer(playing) => {
+                writeln!(
+                    &mut dump,
+                    "cursor: {}",
+                    board::square_name(playing.cursor.pos)
+                )
+                .unwrap();
+                writeln!(&mut dump, "next: {}", playing.next).unwrap();
+                (&playing.board, &playing.moves)
+            }
+            State::Done(done) => (&done.board, &done.moves),
+            State::Review(review) => (&review.done.board, &review.done.moves),
+            State::EnterNames(_) | State::CoinFlip(_) | State::Borrowed => return dump,
+        };
+        let moves: Vec<_> = moves
+            .iter()
+            .map(|(pos, player)| format!("{player}{}", board::square_name(*pos)))
+            .collect();
+        writeln!(&mut dump, "moves: {}", moves.join(" ")).unwrap();
+        writeln!(&mut dump, "board:\n{board}").unwrap();
+        dump
+    }
+
+    /// What the game is doing, like `"playing"` or `"done"`
+    fn state_name(&self) -> &'static str {
+        match &self.state {
+            State::Borrowed => unreachable!(),
+            State::EnterNames(_) => "entering names",
+            State::Playing(_) => "playing",
+            State::SwapOffer(_) => "offering to swap",
+            State::Done(_) => "done",
+            State::CoinFlip(_) => "flipping a coin",
+            State::Review(_) => "reviewing",
+        }
+    }
+
+    fn borrow(&mut self) -> State {
+        mem::replace(&mut self.state, State::Borrowed)
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        self.draw_in(frame, frame.area())
+    }
+
+    fn draw_in(&self, frame: &mut Frame, area: Rect) {
+        let mut area = area;
+        if self.settings.screen_reader {
+            let [rest, line_area] = Layout::vertical([Fill(1), Length(1)]).areas(area);
+            area = rest;
+            frame.render_widget(Paragraph::new(self.screen_reader_line()), line_area);
+            // screen readers read the line the cursor is on
+            frame.set_cursor_position(line_area.as_position());
+        }
+        let legend = self.settings.show_keys && area.height >= LEGEND_MIN_HEIGHT;
+        if legend {
+            let [rest, legend_area] = Layout::vertical([Fill(1), Length(1)]).areas(area);
+            area = rest;
+            self.draw_legend(frame, legend_area);
+        }
+        let layout =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(status_bar::HEIGHT)]);
+        let [mut main_area, status_area] = layout.areas(area);
+        let prompt = if let Some(tutorial) = &self.tutorial {
+            tutorial.step().map(|step| {
+                let (current, total) = tutorial.progress();
+                (
+                    trf("Tutorial ({}/{})", &[&current, &total]),
+                    step.prompt.to_owned(),
+                )
+            })
+        } else {
+            self.puzzle
+                .as_ref()
+                .map(|active| (active.puzzle.title.clone(), active.prompt()))
+        };
+        let prompt = match &self.state {
+            State::Review(review) => Some((
+                trf(
+                    "Review (move {}/{})",
+                    &[&(review.step + 1), &review.done.moves.len()],
+                ),
+                self.review_text(review),
+            )),
+            _ => prompt,
+        };
+        if let Some((title, text)) = prompt {
+            let [prompt_area, rest] = Layout::vertical([Length(5), Fill(1)]).areas(main_area);
+            main_area = rest;
+            draw_prompt(&title, &text, frame, prompt_area);
+        }
+        match &self.state {
+            State::Playing(playing) => {
+                if let Some(left) = playing.clock.shot_clock() {
+                    let [countdown_area, rest] =
+                        Layout::vertical([Length(1), Fill(1)]).areas(main_area);
+                    main_area = rest;
+                    self.draw_shot_clock(playing.next, left, frame, countdown_area);
+                }
+                playing.draw(self.hover, &self.theme, frame, main_area);
+                self.board_area.set(main_area);
+                if self.bot.as_ref().is_some_and(Worker::is_thinking) {
+                    self.draw_thinking(frame, main_area);
+                }
+            }
+            State::SwapOffer(playing) => {
+                playing
+                    .board
+                    .draw(&Decorations::default(), &self.theme, frame, main_area);
+                let keys = &self.settings.keys;
+                let text = trf(
+                    "{}: swap sides? ({}/{})",
+                    &[
+                        &self.name(playing.next),
+                        &keys.name(Action::Yes),
+                        &keys.name(Action::No),
+                    ],
+                );
+                Overlay::new(&text).draw(frame, main_area);
+            }
+            State::Done(done) => {
+                let mut text = self.result(done);
+                if done.rematch && self.settings.reduced_motion {
+                    text.push(' ');
+                    text.push_str(tr("Next game soon"));
+                } else if done.rematch {
+                    let left = REMATCH_DELAY.saturating_sub(done.started.elapsed());
+                    text.push(' ');
+                    text.push_str(&trf("Next game in {}", &[&(left.as_secs() + 1)]));
+                }
+                done.draw(&text, &self.theme, frame, main_area)
+            }
+            State::CoinFlip(started) => {
+                let text = if started.elapsed() < COIN_SPIN_DURATION {
+                    // show each side in turn, like a spinning coin
+                    let side = [X, O][started.elapsed().as_millis() as usize / 100 % 2];
+                    trf("Flipping a coin: {}", &[&side])
+                } else {
+                    trf("{} goes first", &[&self.name(self.player_first)])
+                };
+                Overlay::new(&text).draw(frame, main_area);
+            }
+            State::Review(review) => {
+                let text = match (&review.editing, review.annotation()) {
+                    (Some(comment), _) => Some(format!("{comment}_")),
+                    (None, Some(annotation)) if !annotation.comment.is_empty() => {
+                        Some(annotation.comment.clone())
+                    }
+                    _ => None,
+                };
+                if let Some(text) = text {
+                    let [comment_area, rest] =
+                        Layout::vertical([Length(4), Fill(1)]).areas(main_area);
+                    main_area = rest;
+                    let title = if review.editing.is_some() {
+                        tr("Comment (enter to save, esc to cancel)")
+                    } else {
+                        tr("Comment")
+                    };
+                    draw_prompt(title, &text, frame, comment_area);
+                }
+                review.draw(&self.theme, frame, main_area);
+                self.board_area.set(main_area);
+            }
+            State::EnterNames(form) => form.draw(frame, main_area),
+            State::Borrowed => (),
+        }
+
+        if let Some((toast, _)) = &self.toast {
+            draw_toast(toast, frame, main_area);
+        }
+        if self.debug {
+            self.draw_debug(frame, main_area);
+        }
+        if self.settings.show_fps {
+            let [area, _] = Layout::vertical([Length(1), Fill(1)]).areas(area);
+            let text = format!(" {} ", self.frames.label());
+            frame.render_widget(Paragraph::new(text).right_aligned(), area);
+        }
+
+        // status bar
+        let mut status = StatusBar::new().score(&[
+            (&self.name_x, self.scores[X.index()]),
+            (&self.name_o, self.scores[O.index()]),
+        ]);
+        if let Some((message, _)) = &self.message {
+            status = status.message(message);
+        }
+        if let State::Playing(playing) = &self.state {
+            let name = self.name(playing.next);
+            let to_play = if name == playing.next.to_string() {
+                trf("{} to play", &[&name])
+            } else {
+                trf("{} ({}) to play", &[&name, &playing.next])
+            };
+            status = status
+                .turn(to_play, self.theme.turn(playing.next.index()))
+                .part(playing.clock.label().unwrap_or_default());
+            if !playing.typed.is_empty() {
+                let typed = format!("{}_", playing.typed);
+                status = status.part(trf("move: {}", &[&typed]));
+            }
+            if let Some((squares, best)) = playing.analysis.as_ref().and_then(best_moves) {
+                let squares = board::describe_squares(&playing.board, &squares);
+                status = status.part(trf("best: {} ({})", &[&squares, &outcome_label(best)]));
+            }
+        }
+        status = status.part(self.command_line.label().unwrap_or_default());
+        status = status.timers(self.game_time(), self.session_started.elapsed());
+        let keys = &self.settings.keys;
+        let hint = match &self.state {
+            State::Review(review) if !review.typed.is_empty() => {
+                trf("highlight: {}", &[&format!("{}_", review.typed)])
+            }
+            // the legend has the rest
+            _ if legend => String::new(),
+            State::Done(_) if self.can_analyse() => {
+                trf("{} to review", &[&keys.name(Action::Review)])
+            }
+            State::Review(review) => {
+                let mut hint = trf(
+                    "{}/{} to step, {} to go back",
+                    &[
+                        &keys.name(Action::Left),
+                        &keys.name(Action::Right),
+                        &keys.name(Action::Review),
+                    ],
+                );
+                if review.annotations.is_some() {
+                    hint.push_str(&trf(
+                        ", {} to comment, type a square to highlight it",
+                        &[&keys.name(Action::Comment)],
+                    ));
+                }
+                hint
+            }
+            _ => String::new(),
+        };
+        status.part(hint).draw(frame, status_area);
+    }
+
+    /// The keys that do something right now, and what they do
+    fn key_legend(&self) -> Vec<(String, &'static str)> {
+        let keys = &self.settings.keys;
+        let key = |action| keys.symbols(&[action]);
+        let mut legend = vec![];
+        match &self.state {
+            // any key stops the demo
+            _ if self.demo.is_some() => return legend,
+            State::Playing(playing) => {
+                let moves = [Action::Left, Action::Up, Action::Down, Action::Right];
+                legend.push((keys.symbols(&moves), tr("move")));
+                legend.push((key(Action::Place), tr("place")));
+                if playing.selection.selected().is_some() {
+                    legend.push((key(Action::Cancel), tr("put back")));
+                }
+                if self.can_analyse() {
+                    legend.push((key(Action::Analysis), tr("analysis")));
+                }
+            }
+            State::SwapOffer(_) => {
+                legend.push((key(Action::Yes), tr("swap sides")));
+                legend.push((key(Action::No), tr("keep sides")));
+            }
+            State::Done(_) => {
+                let next = if self.ends_after_one() {
+                    tr("back")
+                } else {
+                    tr("next game")
+                };
+                legend.push((key(Action::Place), next));
+                if self.can_analyse() {
+                    legend.push((key(Action::Review), tr("review")));
+                }
+                legend.push((key(Action::Share), tr("copy")));
+            }
+            State::Review(review) if review.editing.is_none() => {
+                let steps = keys.symbols(&[Action::Left, Action::Right]);
+                legend.push((steps, tr("step")));
+                legend.push((key(Action::Review), tr("back")));
+                if review.annotations.is_some() {
+                    legend.push((key(Action::Comment), tr("comment")));
+                    legend.push(("A1".to_owned(), tr("highlight")));
+                }
+            }
+            State::Review(_) | State::EnterNames(_) | State::CoinFlip(_) | State::Borrowed => {
+                return legend
+            }
+        }
+        legend.push((key(Action::Quit), tr("quit")));
+        legend
+    }
+
+    /// The key legend, on one line
+    fn draw_legend(&self, frame: &mut Frame, area: Rect) {
+        let mut spans = vec![Span::raw(" ")];
+        for (idx, (keys, what)) in self.key_legend().into_iter().enumerate() {
+            if idx > 0 {
+                spans.push(Span::raw(" · "));
+            }
+            spans.push(Span::styled(
+                keys,
+                Style::new().add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::raw(format!(" {what}")));
+        }
+        frame.render_widget(Paragraph::new(text::Line::from(spans)), area);
+    }
+
+    /// What's going on inside, in the bottom left corner of `area`
+    fn draw_debug(&self, frame: &mut Frame, area: Rect) {
+        let mut lines = vec![format!("state: {}", self.state_name())];
+        if let State::Playing(playing) = &self.state {
+            lines.push(format!(
+                "cursor: {}",
+                board::square_name(playing.cursor.pos)
+            ));
+        }
+        let event = self.last_event.as_ref().map_or("none".to_owned(), |event| {
+            events::format_event(event).unwrap_or_else(|| format!("{event:?}"))
+        });
+        lines.push(format!("last event: {event}"));
+        lines.push(format!(
+            "frame: {:.1}ms",
+            self.frames.last_draw().as_secs_f64() * 1000.
+        ));
+        if let Some(bot) = &self.bot {
+            let bot = match bot.last_think() {
+                _ if bot.is_thinking() => "thinking".to_owned(),
+                Some(time) => format!("last move took {}ms", time.as_millis()),
+                None => "no moves yet".to_owned(),
+            };
+            lines.push(format!("bot: {bot}"));
+        }
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as u16
+            + 4;
+        let [area, _] = Layout::horizontal([Length(width), Fill(1)]).areas(area);
+        let [_, area] = Layout::vertical([Fill(1), Length(lines.len() as u16 + 2)]).areas(area);
+        frame.render_widget(Clear, area);
+        let block = Block::bordered()
+            .title("Debug")
+            .padding(Padding::horizontal(1));
+        let lines: Vec<_> = lines.into_iter().map(text::Line::raw).collect();
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    /// Show that the computer is working on its move, over the bottom edge of the board
+    fn draw_thinking(&self, frame: &mut Frame, area: Rect) {
+        const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+        let frame_idx = self.session_started.elapsed().as_millis() / 80 % SPINNER.len() as u128;
+        let text = format!(" {} {} ", SPINNER[frame_idx as usize], tr("thinking…"));
+        let [_, area] = Layout::vertical([Fill(1), Length(1)]).areas(area);
+        frame.render_widget(Paragraph::new(text).centered(), area);
+    }
+}
+
+struct Playing {
+    board: Board,
+    cursor: GridCursor,
+    next: Player,
+    /// The most recently placed mark, while it's animating in
+    placing: Option<Placing>,
+    /// Where the cursor is in its pulse cycle, from 0 to 1
+    pulse: f64,
+    started: Instant,
+    /// What each square would lead to for `next`, when analysis is on. `None` when out of date.
+    analysis: Option<[Option<Outcome>; 9]>,
+    /// Every mark placed so far, in order
+    moves: Vec<(usize, Player)>,
+    /// The start of a square name the player is typing, to play there instead of moving the
+    /// cursor
+    typed: String,
+    /// The first square pressed, for moves that take two presses
+    selection: Selection,
+    clock: Clock<Player>,
+    /// The handicap the board started from, if it had one
+    setup: Option<Handicap>,
+}
+
+impl Playing {
+    fn new(first_player: Player) -> Self {
+        Self::from_position(Board::new(), first_player)
+    }
+
+    fn from_position(board: Board, next: Player) -> Self {
+        Self {
+            cursor: GridCursor::new(3, 3),
+            next,
+            board,
+            placing: None,
+            pulse: 0.,
+            started: Instant::now(),
+            analysis: None,
+            moves: vec![],
+            typed: String::new(),
+            selection: Selection::default(),
+            clock: Clock::new(TimeControl::None, next),
+            setup: None,
+        }
+    }
+
+    /// With `skip_taken`, the cursor jumps over squares that already have a mark
+    fn move_cursor(&mut self, direction: Direction, wrap: bool, skip_taken: bool) {
+        if skip_taken {
+            let board = &self.board;
+            self.cursor
+                .step_to_free(direction, wrap, |pos| board.get(pos).is_none());
+        } else {
+            self.cursor.step(direction, wrap);
+        }
+    }
+
+    /// Play at the cursor, or say why that isn't allowed
+    fn make_move(&mut self) -> Result<(), MoveError> {
+        let moves = self.position().legal_moves();
+        match self.selection.press(self.cursor.pos, &moves) {
+            Some(pos) => self.play(pos),
+            // not a legal move, so the board will say why
+            None => self.play(self.cursor.pos),
+        }
+    }
+
+    fn position(&self) -> Position {
+        Position::from_board(self.board.clone(), self.next)
+    }
+
+    /// The line made by the last mark placed, if it made one
+    fn last_win(&self) -> Option<(Win, Player)> {
+        let (pos, _) = self.moves.last()?;
+        self.board.check_win_from(*pos)
+    }
+
+    /// Place the next mark at `pos`
+    fn play(&mut self, pos: usize) -> Result<(), MoveError> {
+        self.board.apply_move(pos, self.next)?;
+        log(
+            "move",
+            &[
+                ("player", Value::Str(&self.next.to_string())),
+                ("square", Value::Str(&board::square_name(pos))),
+            ],
+        );
+        self.moves.push((pos, self.next));
+        self.next.toggle();
+        self.clock.switch(self.next);
+        self.analysis = None;
+        Ok(())
+    }
+
+    /// Without `pulse`, the cursor stays at its brightest
+    fn tick(&mut self, pulse: bool) {
+        self.pulse = if pulse {
+            (self.started.elapsed().as_secs_f64() / PULSE_PERIOD.as_secs_f64()).fract()
+        } else {
+            0.5
+        };
+        if let Some(placing) = &mut self.placing {
+            placing.tick();
+            if placing.progress >= 1. {
+                self.placing = None;
+            }
+        }
+    }
+
+    /// `hover` is the square under the mouse pointer
+    fn draw(&self, hover: Option<usize>, theme: &Theme, frame: &mut Frame, area: Rect) {
+        let targets = self.selection.targets(&self.position().legal_moves());
+        let decorations = Decorations {
+            active: Some((self.cursor.pos, self.cursor_color(theme))),
+            hover,
+            selected: self.selection.selected(),
+            targets: &targets,
+            placing: self.placing.as_ref(),
+            analysis: self.analysis.as_ref(),
+            ..Decorations::default()
+        };
+        self.board.draw(&decorations, theme, frame, area);
+    }
+
+    /// The cursor color for the current point in the pulse cycle
+    fn cursor_color(&self, theme: &Theme) -> Color {
+        // triangle wave: dim -> bright -> dim
+        let brightness = 1. - (2. * self.pulse - 1.).abs();
+        let idx = (brightness * (theme.cursor.len() - 1) as f64).round() as usize;
+        Color::Indexed(theme.cursor[idx])
+    }
+}
+
+/// How far through `DEMO` the demo is
+struct Demo {
+    /// Index of the next move
+    step: usize,
+    /// When the last move was played, or the demo started
+    moved: Instant,
+    /// Whether the computer chooses the moves instead of `DEMO`
+    bots: bool,
+}
+
+impl Demo {
+    fn new() -> Self {
+        Self {
+            step: 0,
+            moved: Instant::now(),
+            bots: false,
+        }
+    }
+
+    fn bots() -> Self {
+        Self {
+            bots: true,
+            ..Self::new()
+        }
+    }
+}
+
+/// Animation state for a mark that has just been placed
+struct Placing {
+    pos: usize,
+    started: Instant,
+    /// From 0 (just placed) to 1 (full size)
+    progress: f64,
+}
+
+impl Placing {
+    fn new(pos: usize) -> Self {
+        Self {
+            pos,
+            started: Instant::now(),
+            progress: 0.,
+        }
+    }
+
+    fn tick(&mut self) {
+        let progress = self.started.elapsed().as_secs_f64() / PLACE_DURATION.as_secs_f64();
+        self.progress = progress.min(1.);
+    }
+}
+
+struct Done {
+    board: Board,
+    /// Every mark placed during the game, in order
+    moves: Vec<(usize, Player)>,
+    win: Option<(Win, Player)>,
+    /// How long the game took
+    duration: Duration,
+    started: Instant,
+    /// How much of the strikethrough to draw, from 0 to 1
+    line_progress: f64,
+    /// Whether the next game will start by itself after `REMATCH_DELAY`
+    rematch: bool,
+    /// When the game was recorded in the stats file, if it was
+    finished: Option<SystemTime>,
+    /// Who lost by running out of time, if anyone did
+    out_of_time: Option<Player>,
+}
+
+impl Done {
+    /// The board before the first move, which only has something on it after a handicap
+    fn start(&self) -> Board {
+        let mut board = self.board.clone();
+        for &(pos, _) in &self.moves {
+            board.set(pos, None);
+        }
+        board
+    }
+
+    fn new(playing: Playing, win: Option<(Win, Player)>, duration: Duration) -> Self {
+        Self {
+            board: playing.board,
+            moves: playing.moves,
+            win,
+            duration,
+            started: Instant::now(),
+            line_progress: 0.,
+            rematch: false,
+            finished: None,
+            out_of_time: None,
+        }
+    }
+
+    fn winner(&self) -> Option<Player> {
+        let on_time = self.out_of_time.map(Player::other);
+        self.win.as_ref().map(|(_, player)| *player).or(on_time)
+    }
+
+    /// Without `animate`, the line is drawn all at once
+    fn tick(&mut self, animate: bool) {
+        let progress = self.started.elapsed().as_secs_f64() / WIN_LINE_DURATION.as_secs_f64();
+        self.line_progress = if animate { progress.min(1.) } else { 1. };
+    }
+
+    /// `text` is shown in a popup over the board
+    fn draw(&self, text: &str, theme: &Theme, frame: &mut Frame, area: Rect) {
+        if Board::is_compact(area) {
+            return self.draw_compact(text, theme, frame, area);
+        }
+        self.board.draw(&Decorations::default(), theme, frame, area);
+        let mut overlay = Overlay::new(text);
+        if let Some((win, player)) = &self.win {
+            let color = theme.win_line(player.index());
+            overlay = overlay.strike(win.line(color, self.line_progress));
+        }
+        overlay.draw(frame, area);
+    }
+
+    /// Too small for the popup, so highlight the winning squares and put the result underneath
+    fn draw_compact(&self, text: &str, theme: &Theme, frame: &mut Frame, area: Rect) {
+        let layout = Layout::vertical([Fill(1), Length(1)]);
+        let [board_area, text_area] = layout.areas(area);
+        let decorations = Decorations {
+            win: self.win.as_ref().map(|(win, _)| win),
+            ..Decorations::default()
+        };
+        self.board
+            .draw_compact(&decorations, theme, frame, board_area);
+        frame.render_widget(Paragraph::new(text).centered(), text_area);
+    }
+}
+
+/// Draw instructions for the tutorial or a puzzle
+fn draw_prompt(title: &str, text: &str, frame: &mut Frame, area: Rect) {
+    let block = Block::bordered()
+        .title(title)
+        .padding(Padding::horizontal(1));
+    let para = Paragraph::new(text).wrap(Wrap { trim: true }).block(block);
+    frame.render_widget(para, area);
+}
+
+/// Draw `text` in a box in the top-right corner of `area`
+fn draw_toast(text: &str, frame: &mut Frame, area: Rect) {
+    let width = text.chars().count() as u16 + 4;
+    let [_, area] = Layout::horizontal([Fill(1), Length(width)]).areas(area);
+    let [area, _] = Layout::vertical([Length(3), Fill(1)]).areas(area);
+    frame.render_widget(Clear, area);
+    let block = Block::bordered().padding(Padding::horizontal(1));
+    frame.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// Form for typing the players' names before the first game
+struct EnterNames {
+    /// X's name then O's
+    names: [String; 2],
+    /// Index into `names` of the one being edited
+    field: usize,
+}
+
+impl EnterNames {
+    fn new() -> Self {
+        Self {
+            names: Default::default(),
+            field: 0,
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame, area: Rect) {
+        let width = MAX_NAME_LEN as u16 + 12;
+        let [_, area, _] = Layout::vertical([Fill(1), Length(8), Fill(1)]).areas(area);
+        let [_, area, _] = Layout::horizontal([Fill(1), Length(width), Fill(1)]).areas(area);
+        let block = Block::bordered()
+            .title(tr("Players"))
+            .padding(Padding::new(2, 2, 1, 1));
+        let active_style = Style::new().add_modifier(Modifier::REVERSED);
+        let mut lines = vec![];
+        for (idx, (player, name)) in [X, O].into_iter().zip(&self.names).enumerate() {
+            let mut field = format!("{name:<MAX_NAME_LEN$}");
+            if idx == self.field && name.chars().count() < MAX_NAME_LEN {
+                // show where the next character will go
+                field.replace_range(name.len()..name.len() + 1, "_");
+            }
+            let field = if idx == self.field {
+                Span::styled(field, active_style)
+            } else {
+                Span::raw(field)
+            };
+            lines.push(text::Line::from(vec![
+                Span::raw(format!("{player}: ")),
+                field,
+            ]));
+        }
+        lines.push(text::Line::raw(""));
+        lines.push(text::Line::raw(tr("enter to start")).centered());
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+/// The strikethrough on a winning line, which `games-core` leaves to frontends
+trait WinLine {
+    fn line(&self, color: Color, progress: f64) -> Line;
+}
+
+impl WinLine for Win {
+    /// Draw a line through the winning row/col/diag assuming area is (0, 0) to (1, 1)
+    ///
+    /// `progress` (0 to 1) is how far along the line to draw, for animating it in.
+    fn line(&self, color: Color, progress: f64) -> Line {
+        const FIRST: f64 = 1. / 6.;
+        const SECOND: f64 = 3. / 6.;
+        const THIRD: f64 = 5. / 6.;
+        use Win::*;
+        let (x1, y1, x2, y2) = match self {
+            LeftCol => (FIRST, 1., FIRST, 0.),
+            MidCol => (SECOND, 1., SECOND, 0.),
+            RightCol => (THIRD, 1., THIRD, 0.),
+            TopRow => (0., THIRD, 1., THIRD),
+            MidRow => (0., SECOND, 1., SECOND),
+            BottomRow => (0., FIRST, 1., FIRST),
+            TLBRDiag => (0., 1., 1., 0.),
+            TRBLDiag => (0., 0., 1., 1.),
+        };
+        Line::new(
+            x1,
+            y1,
+            x1 + (x2 - x1) * progress,
+            y1 + (y2 - y1) * progress,
+            color,
+        )
+    }
+}
+
+/// Drawing the board in the terminal, which `games-core` leaves to frontends
+trait DrawBoard {
+    fn draw(&self, decorations: &Decorations, theme: &Theme, frame: &mut Frame<'_>, area: Rect);
+    fn draw_canvas(
+        &self,
+        decorations: &Decorations,
+        theme: &Theme,
+        frame: &mut Frame<'_>,
+        area: Rect,
+    );
+    fn is_compact(area: Rect) -> bool;
+    fn square_areas(area: Rect) -> [Rect; 9];
+    fn square_at(area: Rect, column: u16, row: u16) -> Option<usize>;
+    fn compact_area(area: Rect) -> Rect;
+    fn draw_compact(
+        &self,
+        decorations: &Decorations,
+        theme: &Theme,
+        frame: &mut Frame<'_>,
+        area: Rect,
+    );
+}
+
+impl DrawBoard for Board {
+    fn draw(&self, decorations: &Decorations, theme: &Theme, frame: &mut Frame<'_>, area: Rect) {
+        if Self::is_compact(area) {
+            return self.draw_compact(decorations, theme, frame, area);
+        }
+        if theme.board == BoardStyle::Canvas {
+            return self.draw_canvas(decorations, theme, frame, area);
+        }
+        let Decorations {
+            active,
+            hover,
+            selected,
+            targets,
+            placing,
+            analysis,
+            highlights,
+            ..
+        } = *decorations;
+        let block = Block::bordered();
+        for (idx, (cell, area)) in self.squares().zip(Self::square_areas(area)).enumerate() {
+            let mut block = if let Some((_, color)) = active.filter(|(pos, _)| *pos == idx) {
+                block.clone().style(Style::new().fg(color))
+            } else {
+                block.clone()
+            };
+            // a different shape rather than a different color, so it can't be mistaken for the
+            // cursor
+            if hover == Some(idx) {
+                block = block.border_type(BorderType::Double);
+            } else if selected == Some(idx) {
+                block = block.border_type(BorderType::Thick);
+            }
+            let inner = block.inner(area);
+            frame.render_widget(&block, area);
+            if highlights.contains(&idx) {
+                frame
+                    .buffer_mut()
+                    .set_style(inner, Style::new().bg(theme.highlight));
+            }
+            if self.is_blocked(idx) {
+                let shading =
+                    vec![text::Line::raw("░".repeat(inner.width.into())); inner.height.into()];
+                let shading = Paragraph::new(shading).style(Style::new().fg(Color::DarkGray));
+                frame.render_widget(shading, inner);
+            }
+            let [_, label_area, _] = Layout::vertical([Fill(1), Length(1), Fill(1)]).areas(inner);
+            if let Some(outcome) = analysis.and_then(|analysis| analysis[idx]) {
+                let label = Paragraph::new(outcome_label(outcome))
+                    .style(outcome_style(theme, outcome))
+                    .centered();
+                frame.render_widget(label, label_area);
+            } else if targets.contains(&idx) {
+                frame.render_widget(Paragraph::new("◦").centered(), label_area);
+            }
+            if let Some(player) = cell {
+                let progress = placing
+                    .filter(|placing| placing.pos == idx)
+                    .map_or(1., |placing| placing.progress);
+                let style = theme.player(player.index());
+                let color = placing_color(style, progress);
+                match theme.glyphs.text(player.index(), player.glyph()) {
+                    Some(glyph) => {
+                        let glyph = Paragraph::new(glyph)
+                            .style(Style::new().fg(color))
+                            .centered();
+                        frame.render_widget(glyph, label_area);
+                    }
+                    None => draw_mark(player, progress, color, frame, inner),
+                }
+                // modifiers (e.g. inverse) apply to the whole square, so players are
+                // distinguishable without relying on color
+                frame
+                    .buffer_mut()
+                    .set_style(inner, Style { fg: None, ..style });
+            }
+        }
+    }
+
+    /// Draw the whole board as one picture, grid lines and all
+    fn draw_canvas(
+        &self,
+        decorations: &Decorations,
+        theme: &Theme,
+        frame: &mut Frame<'_>,
+        area: Rect,
+    ) {
+        let Decorations {
+            active,
+            hover,
+            selected,
+            targets,
+            placing,
+            analysis,
+            highlights,
+            ..
+        } = *decorations;
+        let squares = Self::square_areas(area);
+        let progress = |idx| {
+            placing
+                .filter(|placing: &&Placing| placing.pos == idx)
+                .map_or(1., |placing| placing.progress)
+        };
+        // everything that isn't drawn on the canvas goes first, so it doesn't cover grid lines
+        for (idx, square) in squares.into_iter().enumerate() {
+            if highlights.contains(&idx) {
+                frame
+                    .buffer_mut()
+                    .set_style(square, Style::new().bg(theme.highlight));
+            }
+            if self.is_blocked(idx) {
+                let shading =
+                    vec![text::Line::raw("░".repeat(square.width.into())); square.height.into()];
+                let shading = Paragraph::new(shading).style(Style::new().fg(Color::DarkGray));
+                frame.render_widget(shading, square);
+            }
+            let [_, label_area, _] = Layout::vertical([Fill(1), Length(1), Fill(1)]).areas(square);
+            let label = match self.get(idx) {
+                Some(player) => theme
+                    .glyphs
+                    .text(player.index(), player.glyph())
+                    .map(|glyph| {
+                        let color = placing_color(theme.player(player.index()), progress(idx));
+                        Paragraph::new(glyph).style(Style::new().fg(color))
+                    }),
+                None => match analysis.and_then(|analysis| analysis[idx]) {
+                    Some(outcome) => Some(
+                        Paragraph::new(outcome_label(outcome)).style(outcome_style(theme, outcome)),
+                    ),
+                    None if targets.contains(&idx) => Some(Paragraph::new("◦")),
+                    None => None,
+                },
+            };
+            if let Some(label) = label {
+                frame.render_widget(label.centered(), label_area);
+            }
+        }
+        let canvas = Canvas::default()
+            .x_bounds([0., 3.])
+            .y_bounds([0., 3.])
+            .marker(Marker::Braille)
+            .paint(|ctx| {
+                for line in [1., 2.] {
+                    ctx.draw(&Line::new(line, 0., line, 3., Color::Gray));
+                    ctx.draw(&Line::new(0., line, 3., line, Color::Gray));
+                }
+                // the squares' bottom left corners, since the canvas's y goes up
+                let corner = |idx: usize| ((idx % 3) as f64, (2 - idx / 3) as f64);
+                for (idx, cell) in self.squares().enumerate() {
+                    let Some(player) = cell else {
+                        continue;
+                    };
+                    if theme.glyphs.text(player.index(), player.glyph()).is_some() {
+                        continue;
+                    }
+                    let color = placing_color(theme.player(player.index()), progress(idx));
+                    let (x, y) = corner(idx);
+                    let r = (0.5 - MARK_MARGIN) * progress(idx);
+                    paint_mark(ctx, player, (x + 0.5, y + 0.5), r, color);
+                }
+                // the cursor last, so it's drawn over the others
+                let outlines = [
+                    hover.map(|idx| (idx, Color::DarkGray)),
+                    selected.map(|idx| (idx, theme.win_line)),
+                    active,
+                ];
+                for (idx, color) in outlines.into_iter().flatten() {
+                    let (x, y) = corner(idx);
+                    ctx.draw(&Rectangle {
+                        x: x + 0.08,
+                        y: y + 0.08,
+                        width: 0.84,
+                        height: 0.84,
+                        color,
+                    });
+                }
+            });
+        frame.render_widget(canvas, area);
+        // modifiers (e.g. inverse) tell the players apart without relying on color, as on the
+        // bordered board, but kept off the grid lines
+        for (cell, square) in self.squares().zip(squares) {
+            if let Some(player) = cell {
+                let style = theme.player(player.index());
+                let inner = square.inner(Margin::new(1, 1));
+                frame
+                    .buffer_mut()
+                    .set_style(inner, Style { fg: None, ..style });
+            }
+        }
+    }
+
+    fn is_compact(area: Rect) -> bool {
+        area.width < COMPACT_WIDTH || area.height < COMPACT_HEIGHT
+    }
+
+    /// Where each square is drawn when the board is drawn in `area`
+    fn square_areas(area: Rect) -> [Rect; 9] {
+        if Self::is_compact(area) {
+            // one character per square, with a line between each
+            let area = Self::compact_area(area);
+            return array::from_fn(|idx| {
+                let (row, col) = (idx as u16 / 3, idx as u16 % 3);
+                Rect::new(area.x + 2 * col, area.y + 2 * row, 1, 1).intersection(area)
+            });
+        }
+        let squares = GridCursor::new(3, 3).square_areas(area);
+        array::from_fn(|idx| squares[idx])
+    }
+
+    /// The square drawn at `(column, row)` on the screen, when the board is drawn in `area`
+    fn square_at(area: Rect, column: u16, row: u16) -> Option<usize> {
+        let point = ratatui::layout::Position::new(column, row);
+        Self::square_areas(area)
+            .iter()
+            .position(|square| square.contains(point))
+    }
+
+    /// The 5 by 5 characters in the middle of `area` that the compact board is drawn in
+    fn compact_area(area: Rect) -> Rect {
+        let [_, area, _] = Layout::vertical([Fill(1), Length(5), Fill(1)]).areas(area);
+        let [_, area, _] = Layout::horizontal([Fill(1), Length(5), Fill(1)]).areas(area);
+        area
+    }
+
+    /// Draw the board with one character per square, for small terminals
+    fn draw_compact(
+        &self,
+        decorations: &Decorations,
+        theme: &Theme,
+        frame: &mut Frame<'_>,
+        area: Rect,
+    ) {
+        let Decorations {
+            active,
+            hover,
+            selected,
+            targets,
+            analysis,
+            win,
+            highlights,
+            ..
+        } = *decorations;
+        let win_squares = win.map(Win::squares);
+        let mut lines = vec![];
+        for row in 0..3 {
+            if row > 0 {
+                lines.push(text::Line::raw("─┼─┼─"));
+            }
+            let mut spans = vec![];
+            for col in 0..3 {
+                let idx = row * 3 + col;
+                if col > 0 {
+                    spans.push(Span::raw("│"));
+                }
+                let (ch, mut style) = match self.get(idx) {
+                    None if self.is_blocked(idx) => {
+                        ("#".to_owned(), Style::new().fg(Color::DarkGray))
+                    }
+                    None => match analysis.and_then(|analysis| analysis[idx]) {
+                        Some(outcome) => ("·".to_owned(), outcome_style(theme, outcome)),
+                        None if targets.contains(&idx) => ("◦".to_owned(), Style::new()),
+                        None => ("·".to_owned(), Style::new()),
+                    },
+                    Some(player) => {
+                        // anything wider than a character would push the grid out of line
+                        let glyph = theme.glyphs.text(player.index(), player.glyph());
+                        let glyph = glyph.filter(|glyph| Span::raw(glyph).width() == 1);
+                        let glyph = glyph.unwrap_or(player.glyph().to_string());
+                        (glyph, theme.player(player.index()))
+                    }
+                };
+                if let (Some(player), Some(squares)) = (self.get(idx), win_squares) {
+                    if squares.contains(&idx) {
+                        let color = theme.win_line(player.index());
+                        style = style.fg(color).add_modifier(Modifier::BOLD);
+                    }
+                }
+                if highlights.contains(&idx) {
+                    style = style.bg(theme.highlight);
+                }
+                if let Some((_, color)) = active.filter(|(pos, _)| *pos == idx) {
+                    style = style.bg(color);
+                }
+                if hover == Some(idx) {
+                    style = style.add_modifier(Modifier::UNDERLINED);
+                }
+                if selected == Some(idx) {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                spans.push(Span::styled(ch, style));
+            }
+            lines.push(text::Line::from(spans));
+        }
+        frame.render_widget(Paragraph::new(lines), Self::compact_area(area));
+    }
+}
+
+/// What playing each square would lead to, for the player to move. `None` for taken squares.
+fn analyse(solver: &mut Solver<Position>, position: &Position) -> [Option<Outcome>; 9] {
+    let mut outcomes = [None; 9];
+    for (pos, outcome) in solver.evaluate(position) {
+        outcomes[pos] = Some(outcome);
+    }
+    outcomes
+}
+
+/// The squares with the best of `outcomes`, and what they lead to. `None` if there's nowhere to
+/// play.
+fn best_moves(outcomes: &[Option<Outcome>; 9]) -> Option<(Vec<usize>, Outcome)> {
+    let best = outcomes.iter().copied().max().flatten()?;
+    let squares = (0..9).filter(|&pos| outcomes[pos] == Some(best)).collect();
+    Some((squares, best))
+}
+
+fn outcome_style(theme: &Theme, outcome: Outcome) -> Style {
+    let [loss, draw, win] = theme.analysis;
+    match outcome {
+        Outcome::Loss => loss,
+        Outcome::Draw => draw,
+        Outcome::Win => win,
+    }
+}
+
+/// Square `pos` in words, like "top left", for screen readers
+fn square_words(pos: usize) -> &'static str {
+    const WORDS: [&str; 9] = [
+        "top left",
+        "top",
+        "top right",
+        "left",
+        "center",
+        "right",
+        "bottom left",
+        "bottom",
+        "bottom right",
+    ];
+    tr(WORDS[pos])
+}
+
+/// `outcome` in the current language
+fn outcome_label(outcome: Outcome) -> &'static str {
+    tr(outcome.label())
+}
+
+/// Extra things to show on the board, besides the marks
+#[derive(Clone, Copy, Default)]
+struct Decorations<'a> {
+    /// The cursor position and its color
+    active: Option<(usize, Color)>,
+    /// The square under the mouse pointer
+    hover: Option<usize>,
+    /// The piece picked up to move, and where it can go
+    selected: Option<usize>,
+    targets: &'a [usize],
+    placing: Option<&'a Placing>,
+    /// What each empty square would lead to, for the player to move
+    analysis: Option<&'a [Option<Outcome>; 9]>,
+    /// Only used by the compact board, which can't draw the strikethrough
+    win: Option<&'a Win>,
+    /// Squares picked out in an annotation
+    highlights: &'a [usize],
+}
+
+/// The color to draw a mark in, fading in from grey as it's placed
+fn placing_color(style: Style, progress: f64) -> Color {
+    if progress < 0.5 {
+        Color::DarkGray
+    } else {
+        Theme::mark_color(style)
+    }
+}
+
+/// Gap between a mark and the edge of its square, as a fraction of the square
+const MARK_MARGIN: f64 = 0.15;
+
+/// Draw a mark scaled to fill `area`, shrunk towards the center while `progress` (0 to 1) is
+/// less than 1
+fn draw_mark(player: Player, progress: f64, color: Color, frame: &mut Frame<'_>, area: Rect) {
+    let r = (0.5 - MARK_MARGIN) * progress;
+    let canvas = Canvas::default()
+        .x_bounds([0., 1.])
+        .y_bounds([0., 1.])
+        .marker(Marker::HalfBlock)
+        .paint(|ctx| paint_mark(ctx, player, (0.5, 0.5), r, color));
+    frame.render_widget(canvas, area);
+}
+
+/// Paint a mark centered on `center`, reaching `r` from it
+fn paint_mark(ctx: &mut Context, player: Player, center: (f64, f64), r: f64, color: Color) {
+    let (x, y) = center;
+    match player {
+        X => {
+            ctx.draw(&Line::new(x - r, y - r, x + r, y + r, color));
+            ctx.draw(&Line::new(x - r, y + r, x + r, y - r, color));
+        }
+        O => ctx.draw(&Circle {
+            x,
+            y,
+            radius: r,
+            color,
+        }),
+    }
+}
+
+/// Returns whether to quit
+fn handle_event(game: &mut Game, event: Event) -> bool {
+    game.last_event = Some(event.clone());
+    let key = match event {
+        // any key stops the demo
+        Event::Key(key) if game.demo.is_some() => return key.kind == KeyEventKind::Press,
+        Event::Key(key) if game.suspendable && suspend::is_suspend_key(&key) => {
+            game.suspended = true;
+            return true;
+        }
+        Event::Key(key) => key,
+        Event::Mouse(mouse) => {
+            match mouse.kind {
+                MouseEventKind::Moved => game.input_mouse_moved(mouse.column, mouse.row),
+                MouseEventKind::Down(MouseButton::Left) => {
+                    game.cancel_rematch();
+                    game.input_click(mouse.column, mouse.row);
+                }
+                _ => (),
+            }
+            return false;
+        }
+        _ => return false,
+    };
+    if game.command_line.is_open() {
+        if key.kind != KeyEventKind::Release {
+            game.input_command(key.code);
+        }
+        return false;
+    }
+    if key.code == KeyCode::Char(':') && !game.is_typing() {
+        if key.kind == KeyEventKind::Press {
+            game.command_line.open();
+        }
+        return false;
+    }
+    if game.is_typing() || game.starts_typing_move(key.code) {
+        if key.kind != KeyEventKind::Release {
+            game.input_text(key.code);
+        }
+        return false;
+    }
+    if key.kind == KeyEventKind::Press {
+        // any key cancels the countdown, as well as doing what it normally does
+        game.cancel_rematch();
+    }
+    let Some(action) = game.settings.keys.action(key.code) else {
+        return false;
+    };
+    match key.kind {
+        KeyEventKind::Press => match action {
+            Action::Quit => return true,
+            Action::Left => game.input_left(true),
+            Action::Right => game.input_right(true),
+            Action::Up => game.input_up(true),
+            Action::Down => game.input_down(true),
+            Action::Place => game.input_space(),
+            Action::Cancel => game.input_cancel(),
+            Action::Analysis => game.toggle_analysis(),
+            Action::Review => game.input_review(),
+            Action::Yes => game.input_confirm(true),
+            Action::No => game.input_confirm(false),
+            Action::Debug => game.debug = !game.debug,
+            Action::Comment => game.input_comment(),
+            Action::Share => game.input_share(),
+        },
+        // holding a key down keeps moving the cursor, but doesn't repeat anything else. Held
+        // moves stop at the edge, so it's easy to land there.
+        KeyEventKind::Repeat => match action {
+            Action::Left => game.input_left(false),
+            Action::Right => game.input_right(false),
+            Action::Up => game.input_up(false),
+            Action::Down => game.input_down(false),
+            _ => (),
+        },
+        KeyEventKind::Release => (),
+    }
+    false
+}
@@ -0,0 +1,79 @@
+//! The clocks for timed games: which games have one, and what happens when a player's time runs
+//! out.
+use std::time::Duration;
+
+use ratatui::{layout::Rect, style::Modifier, widgets::Paragraph, Frame};
+
+use super::{make_bot, Game, Player, Playing, State};
+use crate::{
+    bot::{GameState, Worker},
+    events,
+    locale::trf,
+    player::Seat,
+    time_control::{self, OnTimeout, TimeControl},
+};
+
+impl Game {
+    /// The clock for the games being played. Only games that count have one, and not games
+    /// through a shared file, which can take days.
+    pub(super) fn time_control(&self) -> TimeControl {
+        if self.counts() && self.correspondence.is_none() {
+            self.settings.time_control
+        } else {
+            TimeControl::None
+        }
+    }
+
+    /// Deal with a player whose time has run out, as the settings say
+    pub(super) fn check_clock(&mut self) {
+        if let State::Playing(playing) | State::SwapOffer(playing) = &self.state {
+            if let Some(loser) = playing.clock.flagged() {
+                let (State::Playing(playing) | State::SwapOffer(playing)) = self.borrow() else {
+                    unreachable!()
+                };
+                self.discard_bot_move();
+                match self.settings.on_timeout {
+                    OnTimeout::Forfeit => self.finish(playing, None, Some(loser)),
+                    OnTimeout::RandomMove => self.timeout_move(playing, loser),
+                }
+            }
+        }
+    }
+
+    /// Stop waiting for the move the computer is thinking about, for a position that's gone
+    fn discard_bot_move(&mut self) {
+        if self.bot.as_ref().is_some_and(Worker::is_thinking) {
+            self.bot = Some(Worker::spawn(make_bot(&self.settings).0, events::wake));
+        }
+    }
+
+    /// Play a random move for `player`, whose time ran out. From a swap offer, that turns it
+    /// down.
+    fn timeout_move(&mut self, mut playing: Playing, player: Player) {
+        let moves = playing.position().legal_moves();
+        playing.selection.cancel();
+        playing.cursor.pos = moves[self.rng.below(moves.len())];
+        let moved = playing.play(playing.cursor.pos).is_ok();
+        self.show_message(trf(
+            "{} ran out of time: random move",
+            &[&self.name(player)],
+        ));
+        self.after_move(playing, moved);
+    }
+
+    /// The time `player` has left for their move, over the board
+    pub(super) fn draw_shot_clock(
+        &self,
+        player: Player,
+        left: Duration,
+        frame: &mut Frame,
+        area: Rect,
+    ) {
+        let text = trf(
+            "{} to move in {}",
+            &[&self.name(player), &time_control::format_time(left)],
+        );
+        let style = self.theme.turn(player.index()).add_modifier(Modifier::BOLD);
+        frame.render_widget(Paragraph::new(text).style(style).centered(), area);
+    }
+}
@@ -0,0 +1,273 @@
+//! Tic-tac-toe's command line: the commands every game has, `:ai` to change how well the
+//! computer plays, `:save` and `:load` to keep a game in progress in a named slot, and `:export`
+//! to save a picture of a finished game to `exports/` in the data directory.
+//!
+//! A slot is a file in `saves/` in the data directory, with the marks the game started with,
+//! whose turn it is, and each move in order:
+//!
+//! ```text
+//! handicap x:b2
+//! next o
+//! x a1
+//! o c3
+//! x b1
+//! ```
+use std::{fmt::Write, fs, io, path::PathBuf};
+
+use super::{
+    board::square_name, image, make_bot, parse_square, Board, Game, Handicap, Player, Playing,
+    State, O, X,
+};
+use crate::{
+    bot::{Budget, Worker},
+    commands::{Command, Host, Registry},
+    date, dirs, events,
+    locale::{tr, trf},
+    migrate::Format,
+    theme::{ColorMode, Theme},
+    time_control::Clock,
+};
+
+const SAVE_FORMAT: Format = Format {
+    name: "save",
+    migrations: &[],
+};
+
+/// How well the computer plays for each `:ai` level, as its budget and search depth
+const LEVELS: [(&str, Budget, usize); 3] = [
+    ("easy", Budget::Iterations(20), 1),
+    ("medium", Budget::Iterations(500), 2),
+    ("hard", Budget::Iterations(10_000), 4),
+];
+
+pub(super) fn registry() -> Registry<Game> {
+    Registry::new()
+        .with(Command {
+            name: "ai",
+            usage: "<easy | medium | hard>",
+            args: 1,
+            run: ai,
+        })
+        .with(Command {
+            name: "save",
+            usage: "<slot>",
+            args: 1,
+            run: save,
+        })
+        .with(Command {
+            name: "load",
+            usage: "<slot>",
+            args: 1,
+            run: load,
+        })
+        .with(Command {
+            name: "export",
+            usage: "",
+            args: 0,
+            run: export,
+        })
+}
+
+impl Host for Game {
+    fn restart(&mut self) -> Result<(), String> {
+        if self.tournament || self.replay || self.correspondence.is_some() {
+            return Err(not_here("restart"));
+        }
+        let playing = match &self.puzzle {
+            Some(active) => {
+                Playing::from_position(active.puzzle.board.clone(), active.puzzle.to_move)
+            }
+            None => self.new_playing(self.player_first),
+        };
+        self.state = State::Playing(playing);
+        self.respawn_bot();
+        Ok(())
+    }
+
+    fn quit(&mut self) {
+        self.exit = true;
+    }
+
+    fn set_color_mode(&mut self, mode: ColorMode) {
+        self.settings.color_mode = mode;
+        self.theme = Theme::new(&self.settings);
+    }
+}
+
+impl Game {
+    /// Give the computer a fresh start with the current settings, forgetting any move it was
+    /// thinking about
+    fn respawn_bot(&mut self) {
+        if self.bot.is_none() {
+            return;
+        }
+        let (bot, errors) = make_bot(&self.settings);
+        self.bot = Some(Worker::spawn(bot, events::wake));
+        if !errors.is_empty() {
+            self.show_message(errors.join("; "));
+        }
+    }
+
+    /// Whether a game can be saved or loaded: only proper games, with nothing else depending on
+    /// how they turn out
+    fn can_save(&self) -> bool {
+        self.counts() && !self.tournament && !self.replay && self.correspondence.is_none()
+    }
+}
+
+fn ai(game: &mut Game, args: &[&str]) -> Result<Option<String>, String> {
+    if game.bot.is_none() || game.demo.is_some() {
+        return Err(not_here("ai"));
+    }
+    let &(level, budget, depth) = LEVELS
+        .iter()
+        .find(|(level, ..)| *level == args[0])
+        .ok_or_else(|| trf("expected easy, medium or hard, found {}", &[&args[0]]))?;
+    game.settings.bot_budget = budget;
+    game.settings.bot_depth = depth;
+    game.respawn_bot();
+    Ok(Some(trf("the computer is now {}", &[&level])))
+}
+
+fn save(game: &mut Game, args: &[&str]) -> Result<Option<String>, String> {
+    let path = slot_path(args[0])?;
+    let State::Playing(playing) = &game.state else {
+        return Err(tr("only a game in progress can be saved").to_owned());
+    };
+    if !game.can_save() {
+        return Err(not_here("save"));
+    }
+    SAVE_FORMAT
+        .write(&path, &encode(playing))
+        .map_err(|e| trf("could not save the game: {}", &[&e]))?;
+    Ok(Some(trf("saved to {}", &[&args[0]])))
+}
+
+fn load(game: &mut Game, args: &[&str]) -> Result<Option<String>, String> {
+    let path = slot_path(args[0])?;
+    if !game.can_save() {
+        return Err(not_here("load"));
+    }
+    let body = SAVE_FORMAT
+        .read(&path)
+        .map_err(|e| trf("could not load {}: {}", &[&args[0], &e]))?
+        .ok_or_else(|| trf("nothing is saved in {}", &[&args[0]]))?;
+    let mut playing = decode(&body).map_err(|e| trf("could not load {}: {}", &[&args[0], &e]))?;
+    playing.clock = Clock::new(game.time_control(), playing.next);
+    game.state = State::Playing(playing);
+    game.respawn_bot();
+    Ok(Some(trf("loaded {}", &[&args[0]])))
+}
+
+fn export(game: &mut Game, _: &[&str]) -> Result<Option<String>, String> {
+    let done = match &game.state {
+        State::Done(done) => done,
+        State::Review(review) => &review.done,
+        _ => return Err(tr("only a finished game can be exported").to_owned()),
+    };
+    let svg = image::svg(
+        &done.board,
+        done.win.map(|(win, _)| win),
+        &game.result(done),
+    );
+    let path = export_path()
+        .and_then(|path| fs::write(&path, svg).map(|()| path))
+        .map_err(|e| trf("could not export the game: {}", &[&e]))?;
+    Ok(Some(trf("saved {}", &[&path.display()])))
+}
+
+/// A new file in `exports/`, named after today
+fn export_path() -> io::Result<PathBuf> {
+    let dir = dirs::data_file("exports")?;
+    fs::create_dir_all(&dir)?;
+    let day = date::format(date::today());
+    let path = (1..)
+        .map(|n| dir.join(format!("tic-tac-toe-{day}-{n}.svg")))
+        .find(|path| !path.exists())
+        .unwrap();
+    Ok(path)
+}
+
+/// Why a command can't be used in this game
+fn not_here(name: &str) -> String {
+    trf(":{} can't be used in this game", &[&name])
+}
+
+/// Where the game saved in `slot` is kept
+fn slot_path(slot: &str) -> Result<PathBuf, String> {
+    let valid = slot
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    if !valid {
+        return Err(tr("slot names can only have letters, numbers, - and _").to_owned());
+    }
+    dirs::data_file(&format!("saves/{slot}.txt")).map_err(|e| e.to_string())
+}
+
+fn encode(playing: &Playing) -> String {
+    let board = &playing.board;
+    let placed: Vec<_> = playing.moves.iter().map(|&(pos, _)| pos).collect();
+    // whatever's on the board and wasn't placed during the game was there from the start
+    let start = |player| {
+        (0..9)
+            .filter(|pos| board.get(*pos) == Some(player) && !placed.contains(pos))
+            .collect()
+    };
+    let handicap = Handicap {
+        x: start(X),
+        o: start(O),
+        blocked: (0..9).filter(|&pos| board.is_blocked(pos)).collect(),
+    };
+    let mut body = String::new();
+    if !handicap.is_empty() {
+        writeln!(body, "handicap {handicap}").unwrap();
+    }
+    writeln!(body, "next {}", player_name(playing.next)).unwrap();
+    for &(pos, player) in &playing.moves {
+        writeln!(body, "{} {}", player_name(player), square_name(pos)).unwrap();
+    }
+    body
+}
+
+/// The inverse of `encode`
+fn decode(body: &str) -> Result<Playing, String> {
+    let mut handicap = Handicap::default();
+    let mut next = X;
+    let mut moves = vec![];
+    for line in body.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (what, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match what {
+            "handicap" => handicap = Handicap::from_str(rest)?,
+            "next" => next = parse_player(rest)?,
+            _ => {
+                let square = parse_square(rest).ok_or_else(|| format!("{rest} isn't a square"))?;
+                moves.push((square, parse_player(what)?));
+            }
+        }
+    }
+    let board = Board::with_handicap(&handicap).map_err(|e| e.to_string())?;
+    let mut playing = Playing::from_position(board, next);
+    for (pos, player) in moves {
+        playing
+            .board
+            .apply_move(pos, player)
+            .map_err(|e| e.to_string())?;
+        playing.moves.push((pos, player));
+    }
+    Ok(playing)
+}
+
+pub(super) fn player_name(player: Player) -> &'static str {
+    match player {
+        X => "x",
+        O => "o",
+    }
+}
+
+pub(super) fn parse_player(name: &str) -> Result<Player, String> {
+    match name {
+        "x" => Ok(X),
+        "o" => Ok(O),
+        _ => Err(format!("expected x or o, found \"{name}\"")),
+    }
+}
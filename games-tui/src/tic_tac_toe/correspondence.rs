@@ -0,0 +1,106 @@
+//! Playing someone far away by taking turns to add moves to a shared file, in a folder kept in
+//! sync some other way, like Dropbox or a git repository.
+//!
+//! Each end plays one side. A move is added to the end of the file when it's made, and the file
+//! is looked at every second for the other side's. The file is the moves in order:
+//!
+//! ```text
+//! # games correspondence v1
+//! x b2
+//! o a1
+//! ```
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use super::{
+    board::square_name,
+    commands::{parse_player, player_name},
+    parse_square, Player,
+};
+use crate::migrate::Format;
+
+const FORMAT: Format = Format {
+    name: "correspondence",
+    migrations: &[],
+};
+
+/// How often the file is looked at for the other side's move
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Correspondence {
+    path: PathBuf,
+    /// The side played at this end
+    pub(super) side: Player,
+    /// When the file was last changed, as of the last look at it
+    modified: Option<SystemTime>,
+    checked: Option<Instant>,
+}
+
+impl Correspondence {
+    /// Play `side`, `x` or `o`, in the game kept at `path`, starting it if there's no file yet
+    pub fn open(path: PathBuf, side: &str) -> Result<Self, String> {
+        let side = parse_player(side)?;
+        let body = FORMAT
+            .read(&path)
+            .map_err(|e| format!("could not read {}: {e}", path.display()))?;
+        match body {
+            Some(body) => {
+                decode(&body).map_err(|e| format!("could not read {}: {e}", path.display()))?;
+            }
+            None => FORMAT
+                .write(&path, "")
+                .map_err(|e| format!("could not start {}: {e}", path.display()))?,
+        }
+        Ok(Self {
+            path,
+            side,
+            modified: None,
+            checked: None,
+        })
+    }
+
+    /// Every move in the file, if it's been changed since the last look and it's time for
+    /// another
+    pub(super) fn poll(&mut self) -> Option<Result<Vec<(usize, Player)>, String>> {
+        if self
+            .checked
+            .is_some_and(|checked| checked.elapsed() < CHECK_INTERVAL)
+        {
+            return None;
+        }
+        self.checked = Some(Instant::now());
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified.is_some() && modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        let moves = FORMAT
+            .read(&self.path)
+            .map_err(|e| e.to_string())
+            .and_then(|body| decode(&body.unwrap_or_default()));
+        Some(moves)
+    }
+
+    /// Add this side's move at `pos` to the file
+    pub(super) fn send(&self, pos: usize) -> io::Result<()> {
+        let line = format!("{} {}", player_name(self.side), square_name(pos));
+        FORMAT.append(&self.path, &line)
+    }
+}
+
+fn decode(body: &str) -> Result<Vec<(usize, Player)>, String> {
+    let mut moves = vec![];
+    for line in body.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (player, square) = line
+            .split_once(' ')
+            .ok_or_else(|| format!("\"{line}\" isn't a move"))?;
+        let square = parse_square(square).ok_or_else(|| format!("{square} isn't a square"))?;
+        moves.push((square, parse_player(player)?));
+    }
+    Ok(moves)
+}
@@ -0,0 +1,260 @@
+//! Going back over a finished game a move at a time, with what the solver thinks of each move,
+//! and comments and highlighted squares on the moves of saved games.
+use std::{mem, time::Instant};
+
+use crossterm::event::KeyCode;
+use ratatui::{layout::Rect, style::Color, Frame};
+
+use super::{
+    analyse, best_moves, board, outcome_label, parse_square, Board, Decorations, Done, DrawBoard,
+    Game, Player, Position, State, O, X,
+};
+use crate::{
+    annotations::{Annotation, Annotations},
+    bot::{Outcome, Solver},
+    locale::{tr, trf},
+    theme::Theme,
+};
+
+/// Longest comment that can be written on a move when reviewing.
+const MAX_COMMENT_LEN: usize = 200;
+
+/// A finished game, one move at a time, alongside what the solver thinks of each move
+pub(super) struct Review {
+    pub(super) done: Done,
+    /// For each move, what every square would have led to for the player making it
+    pub(super) outcomes: Vec<[Option<Outcome>; 9]>,
+    /// Index into `done.moves` of the move being looked at
+    pub(super) step: usize,
+    /// Comments and highlighted squares on the moves, for games in the stats file
+    pub(super) annotations: Option<Annotations>,
+    /// The comment being written on the current move
+    pub(super) editing: Option<String>,
+    /// Part of the name of a square to highlight, like `b`
+    pub(super) typed: String,
+}
+
+impl Review {
+    pub(super) fn new(done: Done, solver: &mut Solver<Position>) -> Self {
+        let mut board = done.start();
+        let mut outcomes = vec![];
+        for &(pos, player) in &done.moves {
+            let position = Position::from_board(board.clone(), player);
+            outcomes.push(analyse(solver, &position));
+            board.set(pos, Some(player));
+        }
+        Self {
+            done,
+            outcomes,
+            step: 0,
+            annotations: None,
+            editing: None,
+            typed: String::new(),
+        }
+    }
+
+    pub(super) fn annotation(&self) -> Option<&Annotation> {
+        self.annotations.as_ref()?.get(self.step)
+    }
+
+    /// Highlight `square` on the current move, or stop highlighting it
+    pub(super) fn toggle_highlight(&mut self, square: usize) -> Result<(), String> {
+        let Some(annotations) = &mut self.annotations else {
+            return Err(tr("only saved games can be annotated").to_owned());
+        };
+        annotations
+            .toggle_highlight(self.step, &board::square_name(square))
+            .map_err(|e| trf("could not save annotations: {}", &[&e]))
+    }
+
+    /// Start writing a comment on the current move
+    fn start_comment(&mut self) -> Result<(), String> {
+        if self.annotations.is_none() {
+            return Err(tr("only saved games can be annotated").to_owned());
+        }
+        let comment = self
+            .annotation()
+            .map(|annotation| annotation.comment.clone());
+        self.editing = Some(comment.unwrap_or_default());
+        Ok(())
+    }
+
+    /// Save the comment being written
+    fn save_comment(&mut self) -> Result<(), String> {
+        let (Some(comment), Some(annotations)) = (self.editing.take(), &mut self.annotations)
+        else {
+            return Ok(());
+        };
+        annotations
+            .set_comment(self.step, &comment)
+            .map_err(|e| trf("could not save annotations: {}", &[&e]))
+    }
+
+    pub(super) fn back(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+
+    pub(super) fn forward(&mut self) {
+        self.step = (self.step + 1).min(self.done.moves.len() - 1);
+    }
+
+    /// Whether move `step` was worse than the best move available
+    fn is_mistake(&self, step: usize) -> bool {
+        let outcomes = &self.outcomes[step];
+        let (pos, _) = self.done.moves[step];
+        outcomes[pos] < outcomes.iter().copied().max().flatten()
+    }
+
+    /// The first move where `player` threw away a better result
+    fn first_mistake(&self, player: Player) -> Option<usize> {
+        (0..self.done.moves.len())
+            .find(|step| self.done.moves[*step].1 == player && self.is_mistake(*step))
+    }
+
+    /// The board just before the current move
+    fn board(&self) -> Board {
+        let mut board = self.done.start();
+        for &(pos, player) in &self.done.moves[..self.step] {
+            board.set(pos, Some(player));
+        }
+        board
+    }
+
+    /// Draw the board just before the current move, with the move highlighted
+    pub(super) fn draw(&self, theme: &Theme, frame: &mut Frame, area: Rect) {
+        let board = self.board();
+        let (pos, _) = self.done.moves[self.step];
+        let brightest = theme.cursor[theme.cursor.len() - 1];
+        let highlights: Vec<_> = self
+            .annotation()
+            .into_iter()
+            .flat_map(|annotation| &annotation.highlights)
+            .filter_map(|square| parse_square(square))
+            .collect();
+        let decorations = Decorations {
+            active: Some((pos, Color::Indexed(brightest))),
+            analysis: Some(&self.outcomes[self.step]),
+            highlights: &highlights,
+            ..Decorations::default()
+        };
+        board.draw(&decorations, theme, frame, area);
+    }
+}
+
+impl Game {
+    /// Switch between the result of the last game and reviewing it
+    pub(super) fn input_review(&mut self) {
+        match self.borrow() {
+            State::Done(done) if self.can_analyse() => {
+                let mut review = Review::new(done, &mut self.solver);
+                if let Some(finished) = review.done.finished {
+                    match Annotations::load("tic-tac-toe", finished) {
+                        Ok(annotations) => review.annotations = Some(annotations),
+                        Err(e) => {
+                            let text = trf("could not load annotations: {}", &[&e]);
+                            self.toast = Some((text, Instant::now()));
+                        }
+                    }
+                }
+                self.state = State::Review(review)
+            }
+            State::Review(review) => self.state = State::Done(review.done),
+            state => self.state = state,
+        }
+    }
+
+    /// Writing a comment, or typing a square name and then enter to highlight it
+    pub(super) fn input_review_text(&mut self, code: KeyCode) {
+        let State::Review(review) = &mut self.state else {
+            return;
+        };
+        let result = if let Some(comment) = &mut review.editing {
+            match code {
+                KeyCode::Char(ch) if comment.chars().count() < MAX_COMMENT_LEN => {
+                    comment.push(ch);
+                    Ok(())
+                }
+                KeyCode::Backspace => {
+                    comment.pop();
+                    Ok(())
+                }
+                KeyCode::Esc => {
+                    review.editing = None;
+                    Ok(())
+                }
+                KeyCode::Enter => review.save_comment(),
+                _ => Ok(()),
+            }
+        } else {
+            match code {
+                KeyCode::Char(ch) if review.typed.len() < 2 => {
+                    review.typed.push(ch);
+                    Ok(())
+                }
+                KeyCode::Backspace => {
+                    review.typed.pop();
+                    Ok(())
+                }
+                KeyCode::Esc => {
+                    review.typed.clear();
+                    Ok(())
+                }
+                KeyCode::Enter => {
+                    let typed = mem::take(&mut review.typed);
+                    match parse_square(&typed) {
+                        Some(pos) => review.toggle_highlight(pos),
+                        None => Err(trf("{} isn't a square", &[&typed])),
+                    }
+                }
+                _ => Ok(()),
+            }
+        };
+        if let Err(text) = result {
+            self.toast = Some((text, Instant::now()));
+        }
+    }
+
+    /// Start writing a comment on the move being reviewed
+    pub(super) fn input_comment(&mut self) {
+        if let State::Review(review) = &mut self.state {
+            if let Err(text) = review.start_comment() {
+                self.toast = Some((text, Instant::now()));
+            }
+        }
+    }
+
+    /// Describe the move being reviewed, and where each player first went wrong
+    pub(super) fn review_text(&self, review: &Review) -> String {
+        let (pos, player) = review.done.moves[review.step];
+        let outcomes = &review.outcomes[review.step];
+        let name = self.name(player);
+        let mut text = trf(
+            "{} played {}, a {}. ",
+            &[
+                &name,
+                &board::square_name(pos),
+                &outcome_label(outcomes[pos].unwrap()),
+            ],
+        );
+        if review.is_mistake(review.step) {
+            let (squares, best) = best_moves(outcomes).unwrap();
+            text.push_str(&trf(
+                "Mistake! {} would have been a {}. ",
+                &[
+                    &board::describe_squares(&review.board(), &squares),
+                    &outcome_label(best),
+                ],
+            ));
+        }
+        text.push_str(tr("First mistakes:"));
+        for player in [X, O] {
+            text.push_str(&match review.first_mistake(player) {
+                Some(step) => trf(" {} on move {},", &[&self.name(player), &(step + 1)]),
+                None => trf(" {} none,", &[&self.name(player)]),
+            });
+        }
+        text.pop();
+        text.push('.');
+        text
+    }
+}
@@ -0,0 +1,158 @@
+//! Playing a game without a terminal, from a list of events, to check what it ends up doing.
+//!
+//! Nothing is saved: stats and achievements stay in memory. Animations are off and each event
+//! is followed by ticks until the game has caught up (the computer has moved, say), so the
+//! result doesn't depend on how fast the events arrive. Timers like the rematch countdown still
+//! use the clock, so they won't fire unless the simulation waits for them.
+//!
+//! The game is drawn after every event, into a terminal that starts at 80 by 24 and is resized
+//! by resize events, so clicks land where they would have.
+use std::{thread, time::Duration};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+use super::{handle_event, parse_square, Board, Game, Mode, Player, State, O, X};
+use crate::{achievements::Achievements, keys, player::Seat, settings::Settings};
+
+pub struct Simulation {
+    game: Game,
+    terminal: Terminal<TestBackend>,
+    quit: bool,
+}
+
+impl Simulation {
+    pub fn new(settings: &Settings, mode: Mode) -> Self {
+        let settings = Settings {
+            animations: false,
+            ..settings.clone()
+        };
+        let mut game = Game::new(&settings, mode);
+        game.save = false;
+        game.achievements = Achievements::unsaved();
+        // drawing into memory can't fail
+        let terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let mut sim = Self {
+            game,
+            terminal,
+            quit: false,
+        };
+        sim.settle();
+        sim
+    }
+
+    /// Handle `event` as if it had been read from the terminal, and let the game catch up.
+    /// Events after quitting are ignored.
+    pub fn send(&mut self, event: Event) {
+        if self.quit() {
+            return;
+        }
+        if let Event::Resize(width, height) = event {
+            self.terminal.backend_mut().resize(width, height);
+        }
+        self.quit = handle_event(&mut self.game, event);
+        self.settle();
+    }
+
+    /// Press and release `code`
+    pub fn press(&mut self, code: KeyCode) {
+        for kind in [KeyEventKind::Press, KeyEventKind::Release] {
+            let event = KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind);
+            self.send(Event::Key(event));
+        }
+    }
+
+    /// Type the name of a square, like `b2`, to play there
+    pub fn play(&mut self, square: &str) {
+        // in capitals, which aren't bound to anything
+        for ch in square.chars() {
+            self.press(KeyCode::Char(ch.to_ascii_uppercase()));
+        }
+        self.press(KeyCode::Enter);
+    }
+
+    /// Tick until there's nothing left to wait for, then draw
+    fn settle(&mut self) {
+        loop {
+            self.game.tick();
+            if !self.game.bot.as_ref().is_some_and(|bot| bot.is_thinking()) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        let game = &self.game;
+        self.terminal.draw(|frame| game.draw(frame)).unwrap();
+    }
+
+    /// Whether the game has finished, by being quit or otherwise
+    pub fn quit(&self) -> bool {
+        self.quit || self.game.exit
+    }
+
+    /// What the game is doing, like `"playing"` or `"done"`
+    pub fn state(&self) -> &'static str {
+        self.game.state_name()
+    }
+
+    /// The board being played on or looked at, if there is one
+    pub fn board(&self) -> Option<&Board> {
+        match &self.game.state {
+            State::Playing(playing) | State::SwapOffer(playing) => Some(&playing.board),
+            State::Done(done) => Some(&done.board),
+            State::Review(review) => Some(&review.done.board),
+            State::EnterNames(_) | State::CoinFlip(_) | State::Borrowed => None,
+        }
+    }
+
+    /// Who won the game that just finished, if it's finished and wasn't a draw
+    pub fn winner(&self) -> Option<Player> {
+        match &self.game.state {
+            State::Done(done) => done.winner(),
+            State::Review(review) => review.done.winner(),
+            _ => None,
+        }
+    }
+
+    /// Games won by X and by O, in that order
+    pub fn score(&self) -> (usize, usize) {
+        (self.game.scores[X.index()], self.game.scores[O.index()])
+    }
+
+    /// What's on the screen
+    pub fn screen(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+}
+
+/// Every state worth keeping a snapshot of, by name, drawn at `width` by `height`
+pub fn snapshots(width: u16, height: u16) -> Vec<(&'static str, Buffer)> {
+    // keys by their names in the settings file, where a square name like `b2` plays there
+    const WON: &str = "enter enter a1 b1 b2 c1 c3";
+    let states = [
+        ("names", Mode::Normal, ""),
+        ("playing", Mode::Normal, "enter enter b2 a1 right"),
+        ("taken", Mode::Normal, "enter enter b2 b2"),
+        ("won", Mode::Normal, WON),
+        (
+            "drawn",
+            Mode::Normal,
+            "enter enter b2 a1 c1 a3 a2 c2 b1 b3 c3",
+        ),
+        ("review", Mode::Normal, &format!("{WON} r left left")),
+        ("tutorial", Mode::Tutorial, ""),
+    ];
+    states
+        .into_iter()
+        .map(|(name, mode, keys)| {
+            let mut sim = Simulation::new(&Settings::default(), mode);
+            sim.send(Event::Resize(width, height));
+            for key in keys.split_whitespace() {
+                match parse_square(key) {
+                    Some(_) => sim.play(key),
+                    None => sim.press(keys::parse_key(key).unwrap()),
+                }
+            }
+            (name, sim.screen().clone())
+        })
+        .collect()
+}
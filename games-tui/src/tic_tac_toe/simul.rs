@@ -0,0 +1,129 @@
+//! Playing the computer on several boards at once, with a tab for each along the top.
+use std::time::Instant;
+
+use crossterm::{
+    event::{Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::SetTitle,
+};
+use ratatui::{
+    layout::{
+        Constraint::{Fill, Length},
+        Layout,
+    },
+    style::{Modifier, Style},
+    text::{self, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use super::{handle_event, log, Game, Mode, Review, State, O, X};
+use crate::{
+    crash,
+    event_log::Value,
+    events::{AppEvent, Events},
+    locale::{tr, trf},
+    settings::Settings,
+};
+
+/// Play `boards` games against the computer at once, with tab and shift+tab to move between
+/// them. The computer keeps thinking on the boards that aren't shown.
+pub fn run_simul(
+    terminal: &mut ratatui::DefaultTerminal,
+    settings: &Settings,
+    boards: usize,
+) -> std::io::Result<()> {
+    let mut games: Vec<_> = (0..boards)
+        .map(|_| Game::new(settings, Mode::VsComputer))
+        .collect();
+    log("start", &[("mode", Value::Str("simul"))]);
+    execute!(
+        std::io::stdout(),
+        SetTitle(trf("tic-tac-toe — simul on {} boards", &[&boards]))
+    )?;
+    let mut events = Events::new(settings.tick_rate);
+    let mut active = 0;
+    // what ticks can change, as of the last frame drawn
+    let mut drawn = None;
+    let mut input = true;
+    let result = loop {
+        // the tabs show how every board is doing, so a change to any needs drawing
+        let view: Option<Vec<_>> = games.iter().map(Game::tick_view).collect();
+        if input || view.is_none() || view != drawn {
+            let game = &games[active];
+            crash::set_state(game.dump());
+            let started = Instant::now();
+            terminal.draw(|frame| draw_simul(&games, active, frame))?;
+            games[active].frames.record(started);
+            games[active].announce();
+            drawn = view;
+        }
+        input = true;
+        let quit = match events.next()? {
+            AppEvent::Input(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Tab => {
+                    active = (active + 1) % boards;
+                    false
+                }
+                KeyCode::BackTab => {
+                    active = (active + boards - 1) % boards;
+                    false
+                }
+                _ => handle_event(&mut games[active], Event::Key(key)),
+            },
+            AppEvent::Input(event) => handle_event(&mut games[active], event),
+            AppEvent::Tick => {
+                for game in &mut games {
+                    game.tick();
+                }
+                input = false;
+                false
+            }
+            AppEvent::Wake => {
+                for game in &mut games {
+                    game.bot_move();
+                }
+                false
+            }
+        };
+        if quit || games[active].exit {
+            break Ok(());
+        }
+    };
+    crash::set_state(String::new());
+    result
+}
+
+/// A row of tabs for the boards, with the one being played drawn below
+fn draw_simul(games: &[Game], active: usize, frame: &mut Frame) {
+    let [tabs_area, game_area] = Layout::vertical([Length(1), Fill(1)]).areas(frame.area());
+    let mut spans = vec![];
+    for (idx, game) in games.iter().enumerate() {
+        let label = format!(" {}: {} ", idx + 1, game.simul_status());
+        let style = if idx == active {
+            Style::new().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::new()
+        };
+        spans.push(Span::styled(label, style));
+    }
+    spans.push(Span::raw(format!(" {}", tr("tab for the next board"))));
+    frame.render_widget(Paragraph::new(text::Line::from(spans)), tabs_area);
+    games[active].draw_in(frame, game_area);
+}
+
+impl Game {
+    /// How the game is going, for its tab in a simul
+    fn simul_status(&self) -> &'static str {
+        tr(match &self.state {
+            State::Playing(playing) if playing.next == O => "thinking",
+            State::Playing(_) | State::SwapOffer(_) => "your move",
+            State::Done(done) | State::Review(Review { done, .. }) => match done.winner() {
+                Some(X) => "won",
+                Some(O) => "lost",
+                None => "drawn",
+            },
+            State::EnterNames(_) | State::CoinFlip(_) | State::Borrowed => "starting",
+        })
+    }
+}